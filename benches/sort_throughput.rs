@@ -0,0 +1,100 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+//!
+//! Throughput of `sort` over generated versions, end to end through the
+//! binary: `cargo bench --bench sort_throughput`.
+//!
+//! `SEM_TOOL_BENCH_VERSIONS` sets how many versions to generate (default
+//! 10,000,000). The grouped scenario holds everything in memory, so expect it
+//! to need a few GiB at the default size.
+use rand::prelude::*;
+use std::{
+    env,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    process::{Command, Stdio},
+    time::Instant,
+};
+
+const DEFAULT_VERSION_COUNT: usize = 10_000_000;
+
+/// Roughly registry shaped: mostly releases, some pre-releases, a little
+/// build metadata, and plenty of shared precedence.
+fn write_versions(path: &Path, count: usize) {
+    let mut rng = StdRng::seed_from_u64(0x5e3_700);
+    let mut out = BufWriter::new(File::create(path).expect("create input"));
+    for _ in 0..count {
+        write!(
+            out,
+            "{}.{}.{}",
+            rng.random_range(0..100),
+            rng.random_range(0..100),
+            rng.random_range(0..1000)
+        )
+        .expect("write input");
+        if rng.random_bool(0.2) {
+            write!(out, "-rc.{}", rng.random_range(0..10)).expect("write input");
+        }
+        if rng.random_bool(0.1) {
+            write!(out, "+build.{}", rng.random_range(0..100)).expect("write input");
+        }
+        writeln!(out).expect("write input");
+    }
+    out.flush().expect("write input");
+}
+
+fn main() {
+    let count = env::var("SEM_TOOL_BENCH_VERSIONS")
+        .map(|count| count.parse().expect("a number of versions"))
+        .unwrap_or(DEFAULT_VERSION_COUNT);
+
+    let input = env::temp_dir().join(format!("sem-tool-bench-{}.txt", std::process::id()));
+    write_versions(&input, count);
+
+    let buffer_size = (count / 10).max(1).to_string();
+    let scenarios: [(&str, Vec<&str>); 4] = [
+        ("sort", vec![]),
+        ("sort --flatten", vec!["--flatten"]),
+        (
+            "sort --flatten --lexical-sorting",
+            vec!["--flatten", "--lexical-sorting"],
+        ),
+        (
+            "sort --flatten --buffer-size <count/10>",
+            vec!["--flatten", "--buffer-size", &buffer_size],
+        ),
+    ];
+
+    println!("{count} versions");
+    for (name, args) in scenarios {
+        let start = Instant::now();
+        let status = Command::new(env!("CARGO_BIN_EXE_sem-tool"))
+            .args(["-o", "text", "sort"])
+            .args(&args)
+            .stdin(File::open(&input).expect("open input"))
+            .stdout(Stdio::null())
+            .status()
+            .expect("run sem-tool");
+        let elapsed = start.elapsed().as_secs_f64();
+        assert!(status.success(), "{name} failed: {status}");
+        println!(
+            "{name:<42} {elapsed:>8.2}s {:>12.0} versions/s",
+            count as f64 / elapsed
+        );
+    }
+
+    fs::remove_file(&input).expect("remove input");
+}
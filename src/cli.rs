@@ -20,16 +20,34 @@
 //!                     more important than rust-doc here.
 #![allow(rustdoc::bare_urls)]
 
-use crate::misc::{ApplicationError, ExitOutcome, OutputFormat, SubcommandResult, emit};
+use crate::commits::{CommitBumps, ConventionalCommit, TypeBump};
+use crate::expr::{Binding, EvalResult, Expression, Value, Variables};
+use crate::misc::{
+    ApplicationError, ExitOutcome, ExitStatusError, OutputFormat, SubcommandResult, emit,
+};
+use crate::policy::{LintResult, Policy};
 use crate::results::{
-    BoundaryKind, BoundaryVersionResult, ComparisonStatement, FilterTestResult, FlatVersionsList,
-    GenerateResult, OrderedVersionMap, SelectResult, SemverComponent, SerializableOrdering,
-    ValidateResult, VersionExplanation, VersionMutationResult,
+    BoundaryKind, BoundaryVersionResult, CompareExit, ComparisonMatrix, ComparisonStatement,
+    DiffBy, DiffResult, DistanceResult, ExitScheme, ExpandResult, ExpandStep, FilesPick,
+    FilterTestResult, FlatVersionsList, GapsResult, GenerateResult, GuardResult, IdentifierBump,
+    InputVersion, KeyedLinesList, LineKey, LineLimit, MatrixFormat, NextLevel, NextVersionResult,
+    OrderedVersionMap, PreReleaseStep, PrecedenceRules, Relation, RulesSortResult, SelectResult,
+    SemverComponent, SerializableOrdering, SortCheck, SortCheckResult, UniqBy, UniqKeep,
+    UniqResult, UnkeyedLines, ValidateResult, VersionDiff, VersionExplanation,
+    VersionMutationResult, VersionRange, VersionRendering, VersionedFilesResult, retain_wanted,
 };
-use clap::{Parser, Subcommand};
+use crate::spill::Spiller;
+use crate::stamp::{StampResult, StampSources, format_date, timestamp};
+use clap::{Parser, Subcommand, ValueEnum};
+use regex::Regex;
 use semver::{Version, VersionReq};
 use std::error::Error;
-use std::io;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -69,8 +87,157 @@ struct BoundaryListArgs {
     /// ties instead of failing.
     allow_ambiguous: bool,
 
+    #[command(flatten)]
+    tokens: TokenArgs,
+
     /// Versions as arguments, or read one per line from stdin when omitted.
-    versions: Option<Vec<Version>>,
+    versions: Option<Vec<String>>,
+}
+
+/// How sort, min, max, and latest read version tokens, and print them back.
+#[derive(clap::Args, Debug, Clone)]
+struct TokenArgs {
+    #[clap(long, action)]
+    /// Accept a single leading `v` or `V` on each version (e.g. `v1.2.3` tags).
+    ///
+    /// `v1.2.3` is not a Semantic Version, so this is opt-in. The prefix is
+    /// only dropped for parsing, the original token is kept (see `--render`).
+    ///
+    /// References:
+    /// - https://semver.org/#is-v123-a-semantic-version
+    allow_v_prefix: bool,
+
+    #[clap(long, value_enum, default_value_t = VersionRendering::Canonical)]
+    /// How to print each version.
+    ///
+    /// `original` prints tokens as they were given, which is what you want
+    /// when feeding results back into `git checkout <tag>`. `provenance` adds
+    /// the source (`argument`, `stdin`) and 1-based line or argument position.
+    render: VersionRendering,
+}
+
+/// The relation `compare` tests with `--exit-scheme test-v1`.
+#[derive(clap::Args, Debug, Clone)]
+#[group(id = "relation", multiple = false)]
+struct RelationArgs {
+    /// Exit with success only if A < B, semantically.
+    #[clap(long, action)]
+    lt: bool,
+    /// Exit with success only if A <= B, semantically.
+    #[clap(long, action)]
+    le: bool,
+    /// Exit with success only if A == B, semantically.
+    #[clap(long, action)]
+    eq: bool,
+    /// Exit with success only if A != B, semantically.
+    #[clap(long, action)]
+    ne: bool,
+    /// Exit with success only if A >= B, semantically.
+    #[clap(long, action)]
+    ge: bool,
+    /// Exit with success only if A > B, semantically.
+    #[clap(long, action)]
+    gt: bool,
+}
+
+impl RelationArgs {
+    fn relation(&self) -> Option<Relation> {
+        [
+            (self.lt, Relation::Lt),
+            (self.le, Relation::Le),
+            (self.eq, Relation::Eq),
+            (self.ne, Relation::Ne),
+            (self.ge, Relation::Ge),
+            (self.gt, Relation::Gt),
+        ]
+        .into_iter()
+        .find_map(|(set, relation)| set.then_some(relation))
+    }
+}
+
+/// Variables for `eval` and `assert` expressions.
+#[derive(clap::Args, Debug, Clone)]
+struct ExpressionArgs {
+    /// Bind a name to a version (or, if it doesn't parse as one, a string).
+    /// May be repeated.
+    ///
+    /// Names that aren't bound are read from the environment.
+    #[clap(long, value_name = "NAME=VALUE")]
+    var: Vec<Binding>,
+
+    /// Bind a name to a list of versions read from a file (or `-` for
+    /// stdin), one per line. May be repeated.
+    #[clap(long, value_name = "NAME=FILE")]
+    list: Vec<Binding>,
+
+    /// The expression, e.g. `new > old && new ~ ^1 && new.pre == ""`.
+    expression: String,
+}
+
+impl ExpressionArgs {
+    fn evaluate(self) -> Result<EvalResult, Box<dyn Error>> {
+        let expression = Expression::parse(&self.expression)?;
+        let mut variables = Variables::default();
+        for Binding { name, value } in self.var {
+            variables.bind(name, Value::from_variable(&value));
+        }
+        for Binding { name, value } in self.list {
+            variables.bind(name, Value::List(read_version_source(Path::new(&value))?));
+        }
+        Ok(expression.evaluate(&variables)?)
+    }
+}
+
+/// Arguments for `sort` over whole lines, keyed by an embedded version.
+#[derive(clap::Args, Debug, Clone)]
+struct SortKeyArgs {
+    #[clap(
+        long,
+        short = 'k',
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+        group = "line_key",
+        conflicts_with_all = ["versions", "key_regex", "merge", "allow_v_prefix", "render"],
+    )]
+    /// Sort whole lines from stdin, by the version in this 1-based field.
+    ///
+    /// Fields are split on whitespace, unless `--field-separator` is set.
+    /// The output is always a flat list of the original lines.
+    key: Option<usize>,
+
+    #[clap(long, short = 't', requires = "key")]
+    /// Separator for `--key` fields (e.g. `:` for `image:tag` lines).
+    field_separator: Option<String>,
+
+    #[clap(
+        long,
+        group = "line_key",
+        conflicts_with_all = ["versions", "merge", "allow_v_prefix", "render"],
+    )]
+    /// Sort whole lines from stdin, by the version this regex selects.
+    ///
+    /// Uses the `version` named capture group if present, otherwise the
+    /// first capture group, otherwise the whole match. Only ASCII classes
+    /// are available, so prefer `[0-9]` to `\d`.
+    ///
+    /// The output is always a flat list of the original lines.
+    key_regex: Option<Regex>,
+
+    #[clap(long, value_enum, default_value_t = UnkeyedLines::Last, requires = "line_key")]
+    /// Where to place lines without a valid version under the key.
+    unkeyed: UnkeyedLines,
+}
+
+impl SortKeyArgs {
+    fn line_key(self) -> Option<LineKey> {
+        match (self.key, self.key_regex) {
+            (Some(index), _) => Some(LineKey::Field {
+                index,
+                separator: self.field_separator,
+            }),
+            (None, Some(re)) => Some(LineKey::Regex(re)),
+            (None, None) => None,
+        }
+    }
 }
 
 /// All commands available
@@ -98,8 +265,9 @@ enum Commands {
     /// (meaningful results under Semantic Versioning), as well as Lexical
     /// results (meaningless, but handy for sorting text lists).
     ///
-    /// Without enabling `--set_exit_status`, the exit status is generally
-    /// meaningless, other than confirming that the arguments were valid.
+    /// Without `--set-exit-status`, `--exit-scheme`, a relation such as
+    /// `--ge`, or `--fail-on-diff`, the exit status is generally meaningless,
+    /// other than confirming that the arguments were valid.
     Compare {
         /// If you want some slightly complex exit status codes for this dual
         /// compare, you can turn them on with this flag.
@@ -113,19 +281,115 @@ enum Commands {
         ///
         ///   - With Y being (0 if Less, 1 if Equal, 2 if Greater) on the Lexical Compare
         ///
-        /// The non-0 exit status codes, should be considered UNSTABLE, because something
-        /// better can probably be figured out.
+        /// This is the same as `--exit-scheme dual-v1`.
         #[clap(long, short = 'e', action)]
         set_exit_status: bool,
+        /// Set the exit status by a named scheme.
+        ///
+        /// The codes of a versioned scheme (`test-v1`) never change, while
+        /// the unversioned aliases (`test`) follow the latest version.
+        ///
+        /// A relation (`--lt`, `--le`, `--eq`, `--ne`, `--ge`, `--gt`)
+        /// implies `test-v1`.
+        #[clap(
+            long,
+            value_enum,
+            conflicts_with_all = [
+                "set_exit_status",
+                "fail_on_diff",
+                "fail_if_incompatible",
+                "semantic_exit_status",
+            ]
+        )]
+        exit_scheme: Option<ExitScheme>,
+        #[clap(flatten)]
+        relation: RelationArgs,
         /// Always exit with success when Semantic Versions are Equal.
         ///
         /// Mostly impacts the output when the flag `set_exit_status` is set.
-        #[clap(long, short = 's', action)]
+        /// A relation or `--exit-scheme` decides the exit status itself, so
+        /// this can't be combined with them.
+        #[clap(long, short = 's', action, conflicts_with = "relation")]
         semantic_exit_status: bool,
+        /// Exit with status 1 when the versions differ in this component, or
+        /// in a more significant one, and 0 otherwise.
+        ///
+        /// `--fail-on-diff major` fails on a major version change, which is
+        /// a breaking change under SemVer. For Cargo's rules, where `0.x`
+        /// minor changes are breaking too, use `--fail-if-incompatible`.
+        #[clap(
+            long,
+            value_enum,
+            conflicts_with_all = ["set_exit_status", "semantic_exit_status", "relation"]
+        )]
+        fail_on_diff: Option<VersionDiff>,
+        /// Exit with status 1 when the second version isn't allowed by
+        /// Cargo's default (caret) requirement on the first, and 0 otherwise.
+        ///
+        /// This is `cargo_compatible` in the output: unlike `--fail-on-diff
+        /// major`, `0.x` minor and `0.0.x` patch changes are breaking.
+        #[clap(
+            long,
+            action,
+            conflicts_with_all = [
+                "set_exit_status",
+                "semantic_exit_status",
+                "relation",
+                "fail_on_diff",
+            ]
+        )]
+        fail_if_incompatible: bool,
+        /// Show each step of SemVer 2.0.0 §11 taken to decide precedence,
+        /// and the rule that decided it.
+        ///
+        /// Only the semantic comparison is traced.
+        ///
+        /// Reference: https://semver.org/#spec-item-11
+        #[clap(long, action)]
+        trace: bool,
+        /// Also compare under the precedence rules of an ecosystem, or
+        /// `all` of them side by side, flagging whether they disagree.
+        ///
+        /// `spec` ignores build metadata, `cargo` (the semver crate) and
+        /// `npm` (node-semver's `compareBuild`) order by it, and `lexical`
+        /// compares the version text.
+        #[clap(long, value_enum)]
+        rules: Option<PrecedenceRules>,
+        /// Compare every pair of a list of versions instead, as an N×N table
+        /// of the semantic and lexical ordering of each row version against
+        /// each column version.
+        ///
+        /// Pairs where the semantic and lexical orderings disagree (what
+        /// would be a 110 or 112 exit status with `-e`) are marked, and
+        /// listed as `inconsistencies`.
+        ///
+        /// With no versions given, they are read from stdin, one version per
+        /// line.
+        #[clap(
+            long,
+            num_args = 0..,
+            value_name = "VERSION",
+            conflicts_with_all = [
+                "set_exit_status",
+                "semantic_exit_status",
+                "exit_scheme",
+                "relation",
+                "fail_on_diff",
+                "fail_if_incompatible",
+                "trace",
+                "rules",
+            ]
+        )]
+        matrix: Option<Vec<Version>>,
+        /// How `--matrix` is rendered with `--out text` [default: table].
+        #[clap(long, value_enum)]
+        matrix_format: Option<MatrixFormat>,
         /// The base version used for comparison.
-        a: Version,
+        #[clap(required_unless_present = "matrix", conflicts_with = "matrix")]
+        a: Option<Version>,
         /// The version we are comparing against.
-        b: Version,
+        #[clap(required_unless_present = "matrix", conflicts_with = "matrix")]
+        b: Option<Version>,
     },
     /// Sort a list of valid Semantic Versions, with either Semantic or Lexical ordering.
     ///
@@ -206,9 +470,83 @@ enum Commands {
         #[clap(long, action)]
         stable: bool,
 
+        #[clap(
+            long,
+            short = 'c',
+            value_enum,
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "first",
+            conflicts_with_all = ["flatten", "merge", "key", "key_regex", "fail_if_potentially_ambiguous"],
+        )]
+        /// Check that the input is already ordered, instead of sorting it.
+        ///
+        /// Reports the first (default) or, with `--check=all`, every version
+        /// that sorts before the one preceding it, and exits non-zero if there
+        /// are any.
+        /// `--reverse`, `--lexical-sorting`, `--filter` and `--stable` apply
+        /// as they do when sorting.
+        check: Option<SortCheck>,
+
+        #[clap(long, short = 'm', num_args = 1.., value_name = "FILE", conflicts_with = "versions")]
+        /// Merge files that are each already ordered (`-` for stdin), instead
+        /// of sorting everything again.
+        ///
+        /// Each file must already be in the order asked for, see `--check`.
+        merge: Option<Vec<PathBuf>>,
+
+        #[clap(
+            long,
+            value_name = "VERSIONS",
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+            requires = "flatten",
+            conflicts_with_all = [
+                "check",
+                "merge",
+                "key",
+                "key_regex",
+                "fail_if_potentially_ambiguous",
+            ],
+        )]
+        /// Hold at most this many versions in memory, spilling sorted runs to
+        /// temporary files past it, and merging them as output is written.
+        ///
+        /// For inputs too large to sort in memory. Requires `--flatten`. As
+        /// output starts before all versions are seen,
+        /// `--fail-if-potentially-ambiguous` is unavailable. Runs are written
+        /// under the system temporary directory (`TMPDIR`).
+        buffer_size: Option<usize>,
+
+        #[clap(
+            long,
+            value_enum,
+            conflicts_with_all = [
+                "lexical_sorting",
+                "flatten",
+                "fail_if_potentially_ambiguous",
+                "check",
+                "merge",
+                "buffer_size",
+                "key",
+                "key_regex",
+            ],
+        )]
+        /// Order by the precedence rules of an ecosystem instead, or by `all`
+        /// of them side by side, flagging whether they disagree.
+        ///
+        /// See `compare --help` for the rule sets. Output is flat, and
+        /// versions a rule set finds equal keep their input order.
+        rules: Option<PrecedenceRules>,
+
+        #[command(flatten)]
+        key: SortKeyArgs,
+
+        #[command(flatten)]
+        tokens: TokenArgs,
+
         /// If no versions are present, then the tool will read from stdin, one
         /// version per line.
-        versions: Option<Vec<Version>>,
+        versions: Option<Vec<String>>,
     },
     /// Test a Semantic Version against a filter
     FilterTest {
@@ -270,8 +608,10 @@ enum Commands {
     /// Bump commands will increment one segment of semver by the specified
     /// amount, and print out the mutated version.
     ///
-    /// Only Major, Minor and Patch are supported. You'll want to consider the
-    /// `set` subcommand for pre-release and build-metadata.
+    /// Numeric pre-release and build-metadata identifiers are selected by
+    /// 0-based index, or by the label before them: in `1.0.0-beta.3.build.7`,
+    /// `--bump-pre-release 3` and `--bump-pre-release build` both bump the
+    /// `7`. You'll want to consider the `set` subcommand to replace them.
     Bump {
         semantic_version: Version,
 
@@ -281,9 +621,170 @@ enum Commands {
         bump_minor: Option<u64>,
         #[clap(long, action)]
         bump_patch: Option<u64>,
-        // NOTE(canardleteer): We could actually do bumps on pre-release and
-        //                     build-metadata, if we supported a selector and
-        //                     confirmed the segment chosen was numeric.
+        /// Bump a numeric pre-release identifier, by 1 unless an AMOUNT is
+        /// given.
+        #[clap(long, value_name = "SELECTOR[=AMOUNT]")]
+        bump_pre_release: Option<IdentifierBump>,
+        /// Bump a numeric build-metadata identifier, by 1 unless an AMOUNT is
+        /// given.
+        #[clap(long, value_name = "SELECTOR[=AMOUNT]")]
+        bump_build_metadata: Option<IdentifierBump>,
+    },
+    /// Compute the version that follows a base version.
+    ///
+    /// `next <major|minor|patch|release> BASE` follows the usual SemVer
+    /// rules. A pre-release comes before its release, so the next version of
+    /// a pre-release is its own release when that is the kind asked for:
+    /// `next minor 1.3.0-rc.2` is `1.3.0`, but `next major 1.3.0-rc.2` is
+    /// `2.0.0`. `release` releases a pre-release, or is the next patch of a
+    /// release.
+    ///
+    /// With `--from-commits BASE`, commit messages are read from stdin (e.g.
+    /// `git log --format=%B%x00 v1.2.3..`), and Conventional Commits decide
+    /// the bump: a breaking change (`!`, or a `BREAKING CHANGE:` footer) is
+    /// major, `feat` is minor and `fix` is patch.
+    ///
+    /// Before 1.0.0, a major change bumps minor instead (SemVer §4), unless
+    /// `--leave-initial-development`.
+    ///
+    /// Pre-release and build metadata are dropped from the bumped version.
+    /// `-o text` prints only the version; each decision, and the commits
+    /// that drove the bump, are in the YAML and JSON output.
+    ///
+    /// References:
+    /// - https://semver.org/#spec-item-4
+    /// - https://semver.org/#spec-item-11
+    /// - https://www.conventionalcommits.org/en/v1.0.0/
+    Next {
+        /// Which part of the version changes.
+        #[clap(required_unless_present = "from_commits")]
+        level: Option<NextLevel>,
+
+        /// The version to bump, usually the latest release.
+        #[clap(required_unless_present = "from_commits")]
+        base: Option<Version>,
+
+        /// Bump BASE as the commit messages read from stdin call for.
+        ///
+        /// Messages separated by NUL (`%x00`) are read exactly. Otherwise
+        /// every line that looks like a Conventional Commits header starts a
+        /// commit.
+        #[clap(long, value_name = "BASE", conflicts_with_all = ["level", "base"])]
+        from_commits: Option<Version>,
+
+        /// The bump a commit type calls for, as `TYPE=LEVEL` (`perf=patch`,
+        /// `fix=none`). May be repeated.
+        #[clap(long, value_name = "TYPE=LEVEL")]
+        type_bump: Vec<TypeBump>,
+
+        /// Let a major change of a 0.y.z version release 1.0.0.
+        #[clap(long, action)]
+        leave_initial_development: bool,
+
+        /// Published versions to avoid (path, or `-` for stdin).
+        ///
+        /// The version keeps being bumped until it is unpublished and newer
+        /// than every published version of its line. Skipped candidates are
+        /// reported.
+        #[clap(long, value_name = "FILE")]
+        avoid_existing: Option<PathBuf>,
+    },
+    /// Move a version along its pre-release lifecycle, as npm's `semver.inc`.
+    ///
+    /// `preminor 1.2.3` starts `1.3.0-rc.0`, `prerelease 1.3.0-rc.4` gives
+    /// `1.3.0-rc.5`, and `promote 1.3.0-rc.5` releases `1.3.0`. With
+    /// `prerelease --id beta`, `1.3.0-alpha.3` switches channels to
+    /// `1.3.0-beta.0`.
+    ///
+    /// Build metadata is dropped.
+    ///
+    /// References:
+    /// - https://semver.org/#spec-item-9
+    /// - https://github.com/npm/node-semver#functions
+    #[clap(alias = "pre")]
+    PreRelease {
+        step: PreReleaseStep,
+
+        semantic_version: Version,
+
+        /// The pre-release identifier to count under, e.g. `alpha`, `beta`.
+        ///
+        /// Defaults to `rc` when starting a pre-release, and to the current
+        /// channel when incrementing one.
+        #[clap(long)]
+        id: Option<String>,
+    },
+    /// The next snapshot (nightly) version, after the latest stable release.
+    ///
+    /// The latest stable version (as `max --stable`) is bumped by `--level`,
+    /// and a pre-release of `--id`, the UTC date, and a counter for the day
+    /// is added: after `1.4.2`, `snapshot` gives `1.5.0-nightly.20261018.0`.
+    ///
+    /// Snapshots already in the list continue the day's counter, and the
+    /// result always has precedence over every snapshot of that version.
+    Snapshot {
+        /// The bump from the latest stable version to the snapshot version.
+        #[clap(long, value_enum, default_value_t = NextLevel::Minor)]
+        level: NextLevel,
+
+        /// The pre-release identifier of snapshots.
+        #[clap(long, default_value = "nightly")]
+        id: String,
+
+        /// Seconds since the epoch for the date, instead of
+        /// `SOURCE_DATE_EPOCH` or the clock.
+        #[clap(long, value_name = "SECONDS")]
+        timestamp: Option<u64>,
+
+        /// Bump a major change before 1.0.0 to 1.0.0, rather than minor.
+        #[clap(long, action)]
+        leave_initial_development: bool,
+
+        /// If no versions are present, then the tool will read from stdin, one
+        /// version per line.
+        versions: Option<Vec<Version>>,
+    },
+    /// Set build metadata from a template, e.g. to stamp a CI build.
+    ///
+    /// `stamp 1.2.3 -T '{sha:7}.{date:%Y%m%d}.{env:CI_PIPELINE_ID}'` gives
+    /// `1.2.3+0a1b2c3.20250101.42`. Placeholders are:
+    ///
+    /// - `{sha}` or `{sha:N}`: the commit (or its first `N` characters), from
+    ///   `--sha`, `GITHUB_SHA`, `CI_COMMIT_SHA`, `GIT_COMMIT`, or `HEAD` of
+    ///   the `.git` directory at or above the working directory.
+    /// - `{date}` or `{date:FORMAT}`: the UTC date, from `--timestamp`,
+    ///   `SOURCE_DATE_EPOCH`, or the clock. `FORMAT` understands `%Y`, `%y`,
+    ///   `%m`, `%d`, `%H`, `%M`, `%S` and `%s`, and defaults to `%Y%m%d`.
+    /// - `{env:NAME}`: an environment variable.
+    /// - `{NAME}`: a value given with `--value NAME=VALUE`.
+    ///
+    /// Characters outside of `[0-9A-Za-z-]` in a value are replaced with `-`,
+    /// and reported in structured output.
+    ///
+    /// References:
+    /// - https://semver.org/#spec-item-10
+    Stamp {
+        semantic_version: Version,
+
+        /// The build metadata to render.
+        #[clap(long, short = 'T')]
+        template: String,
+
+        /// The commit for `{sha}`.
+        #[clap(long)]
+        sha: Option<String>,
+
+        /// Seconds since the epoch for `{date}`.
+        #[clap(long, value_name = "SECONDS")]
+        timestamp: Option<u64>,
+
+        /// A value for a `{NAME}` placeholder. May be repeated.
+        #[clap(long, value_name = "NAME=VALUE")]
+        value: Vec<Binding>,
+
+        /// Append to the existing build metadata, rather than replacing it.
+        #[clap(long, action)]
+        append: bool,
     },
     /// Reset-on-bump: increment one numeric segment and zero less significant ones.
     ///
@@ -352,11 +853,320 @@ enum Commands {
         #[clap(long, short = 'F', action)]
         fail_if_not_found: bool,
     },
+    /// Collapse duplicate versions from a list.
+    ///
+    /// Unlike `sort`, which keeps every build-metadata variant under its
+    /// precedence group, `uniq` emits a single representative per group, in
+    /// order of first appearance. Unlike the coreutils `uniq`, duplicates do
+    /// not need to be adjacent.
+    ///
+    /// The default `--by=precedence` groups exactly as `sort` does: versions
+    /// that differ only in build metadata are duplicates (SemVer §10).
+    ///
+    /// References:
+    /// - https://semver.org/#spec-item-10
+    /// - https://doc.rust-lang.org/cargo/reference/specifying-dependencies.html#caret-requirements
+    Uniq {
+        /// What makes two versions duplicates of each other.
+        #[clap(long, value_enum, default_value_t = UniqBy::Precedence)]
+        by: UniqBy,
+
+        /// Which member of each duplicate group to emit.
+        ///
+        /// `min` and `max` compare by precedence only, so build-metadata
+        /// variants are never reordered against each other; the earliest
+        /// occurrence wins a tie.
+        #[clap(long, value_enum, default_value_t = UniqKeep::First)]
+        keep: UniqKeep,
+
+        /// Prefix each version by the number of occurrences (like `uniq -c`).
+        #[clap(long, short = 'c', action)]
+        count: bool,
+
+        /// If no versions are present, then the tool will read from stdin, one
+        /// version per line.
+        versions: Option<Vec<Version>>,
+    },
+    /// Set operations between two version lists.
+    ///
+    /// Reports versions found `only_left`, `only_right`, and in `both`,
+    /// each sorted by precedence. With `--by exact`, versions that differ
+    /// only in build metadata are ordered by it, as the semver crate does.
+    /// Useful for reconciling, for example, tags in git against artifacts in
+    /// a registry.
+    ///
+    /// Each source is a file with one version per line, or `-` for stdin
+    /// (only one side may be stdin).
+    ///
+    /// The exit status is 0 when both sides hold the same set, 1 when they
+    /// differ, and 2 when a source can't be read or parsed, as with
+    /// `diff(1)`.
+    Diff {
+        /// What makes a version on the left equal to one on the right.
+        ///
+        /// `precedence` ignores build metadata (SemVer §10), and reports
+        /// versions without it.
+        ///
+        /// References:
+        /// - https://semver.org/#spec-item-10
+        #[clap(long, value_enum, default_value_t = DiffBy::Exact)]
+        by: DiffBy,
+
+        /// Left version source (path, or `-` for stdin).
+        left: PathBuf,
+
+        /// Right version source (path, or `-` for stdin).
+        right: PathBuf,
+    },
+    /// Pick files by the Semantic Version in their names.
+    ///
+    /// Each path argument that is a directory is scanned (not recursively)
+    /// for files; other paths are used as given. With no paths, one path per
+    /// line is read from stdin.
+    ///
+    /// A `--template` such as `mytool-{version}-*.tar.gz` must match the
+    /// whole file name. `{version}` matches a Semantic Version, `*` and `?`
+    /// are globs, and everything else is literal. Since pre-release
+    /// identifiers may contain hyphens, a template cannot always tell a
+    /// pre-release from a hyphenated suffix: prefer `--regex` when it is
+    /// ambiguous. Names that don't match are ignored.
+    ///
+    /// Ordering follows `sort`, and `--pick max` (the default) or `min`
+    /// follow `max` and `min`. Several files carrying the very same version
+    /// (e.g. one per platform) are one release, and are all printed.
+    ///
+    /// The `latest-file` name is a visible alias for this subcommand.
+    #[command(visible_alias = "latest-file")]
+    Files {
+        /// File name template, containing `{version}` exactly once.
+        #[clap(long, short = 'T', required_unless_present = "regex")]
+        template: Option<String>,
+
+        /// Regex selecting the version in a file name, as `sort --key-regex`.
+        #[clap(long, conflicts_with = "template")]
+        regex: Option<Regex>,
+
+        /// Which files to print.
+        #[clap(long, value_enum, default_value_t = FilesPick::Max)]
+        pick: FilesPick,
+
+        #[clap(long, short = 'f', default_value = None)]
+        /// Only consider files whose version matches a filter.
+        ///
+        /// See `sort --help` for VersionReq documentation.
+        filter: Option<VersionReq>,
+
+        #[clap(long, action)]
+        /// Lexical ordering of build-metadata variants sharing precedence
+        /// (see `sort --help`), and a lexical tiebreak for `max` and `min`.
+        lexical_sorting: bool,
+
+        #[clap(long, short = 'r', action)]
+        /// Reverses ordering (see `sort --help`).
+        reverse: bool,
+
+        #[clap(long, action)]
+        /// Exclude versions with non-empty pre-release.
+        stable: bool,
+
+        #[clap(long, action)]
+        /// When the boundary has multiple build-metadata variants, print all
+        /// of them instead of failing.
+        allow_ambiguous: bool,
+
+        /// Files or directories, or read one path per line from stdin when
+        /// omitted.
+        paths: Option<Vec<PathBuf>>,
+    },
+    /// Report missing versions within the release lines of a list.
+    ///
+    /// Versions are ordered as `sort` orders them, then each pair of
+    /// neighbours is checked for a skipped major, minor or patch number
+    /// (`1.2.1` to `1.2.3` is missing `1.2.2`; `1.3.4` to `1.5.0` is missing
+    /// the `1.4` line).
+    ///
+    /// Pre-releases of the same release are checked too, when they end in a
+    /// numeric identifier: `rc.1` to `rc.3` is missing `rc.2`. Identifiers
+    /// are classified as in `explain`.
+    ///
+    /// Only gaps between neighbours are reported. A line that does not start
+    /// at `.0` is not considered a gap, since lists are often truncated.
+    Gaps {
+        #[clap(long, short = 'f', default_value = None)]
+        /// Only consider versions that match a filter.
+        ///
+        /// See `sort --help` for VersionReq documentation.
+        filter: Option<VersionReq>,
+
+        /// Exclude versions with a non-empty pre-release before looking for
+        /// gaps.
+        #[clap(long, action)]
+        stable: bool,
+
+        /// Exit with non-zero status when any gap is found.
+        #[clap(long, short = 'F', action)]
+        fail_if_found: bool,
+
+        /// If no versions are present, then the tool will read from stdin, one
+        /// version per line.
+        versions: Option<Vec<Version>>,
+    },
+    /// Check that a release candidate is a valid successor of the published
+    /// versions, before tagging it.
+    ///
+    /// Each rule passes or fails on its own:
+    ///
+    /// - `newer-than-line`: newer than every published version of its
+    ///   `MAJOR.MINOR` line.
+    /// - `unique-precedence`: not the precedence of a published version,
+    ///   e.g. with different build metadata.
+    /// - `no-skipped-numbers`: follows a released version (`1.2.0` to
+    ///   `1.4.0` skips `1.3.0`).
+    /// - `not-already-released`: not a pre-release of a published release.
+    ///
+    /// Exits non-zero when any rule fails.
+    Guard {
+        /// The version about to be published.
+        candidate: Version,
+
+        /// If no versions are present, then the tool will read from stdin, one
+        /// version per line.
+        versions: Option<Vec<Version>>,
+    },
+    /// Check versions against the project's versioning policy.
+    ///
+    /// The policy is read from `--policy`, or the nearest `.sem-tool.toml`
+    /// at or above the working directory. Its `[lint]` table configures the
+    /// rules, and `[lint.severity]` makes any of them a `warning` rather than
+    /// an `error`:
+    ///
+    /// - `pre-release-channels = ["alpha", "rc"]`: the first pre-release
+    ///   identifier must be one of these (`pre-release-channel`).
+    /// - `max-identifiers = 3`: the most identifiers a pre-release or build
+    ///   metadata may have (`max-identifiers`).
+    /// - `build-metadata = "required"` or `"forbidden"` (`build-metadata`).
+    /// - `initial-development = false`: no 0.y.z versions, except those
+    ///   listed before the first stable 1.0.0 or later
+    ///   (`initial-development`).
+    /// - `pre-release-counter = true`: the last pre-release identifier must
+    ///   be numeric (`pre-release-counter`).
+    ///
+    /// Exits non-zero when any error is found.
+    Lint {
+        /// The policy file to read, instead of searching for one.
+        #[clap(long, value_name = "FILE")]
+        policy: Option<PathBuf>,
+
+        /// If no versions are present, then the tool will read from stdin, one
+        /// version per line.
+        versions: Option<Vec<Version>>,
+    },
+    /// Evaluate an expression over versions, e.g. in a CI guard.
+    ///
+    /// Versions compare by precedence (as `compare`), `~` tests a requirement
+    /// (as `filter-test`), and `.major`, `.minor`, `.patch`, `.pre` and
+    /// `.build` select a component (as `select`). `max`, `min` and `len` take
+    /// versions or lists, and `&&`, `||` and `!` combine booleans.
+    ///
+    /// Exits non-zero when the value is `false`.
+    Eval {
+        #[clap(flatten)]
+        expression: ExpressionArgs,
+    },
+    /// Like `eval`, but the expression must be a boolean, and the first
+    /// failing term of a top level `&&` is reported.
+    Assert {
+        #[clap(flatten)]
+        expression: ExpressionArgs,
+    },
+    /// Enumerate the releases in a range, e.g. for a test matrix.
+    ///
+    /// `expand 1.2.0..1.4.3` lists every `MAJOR.MINOR.PATCH` from `1.2.0` to
+    /// `1.4.3`, inclusive. With `--step minor`, only the first version of
+    /// each minor line is listed (`1.2.0`, `1.3.0`, `1.4.0`).
+    ///
+    /// Where a line ends in between (how many patches `1.2` had, or minors
+    /// `1` had) is given with `--limit`, or taken from the highest published
+    /// version of that line in `--releases`.
+    ///
+    /// Without a range, lists the published versions of `--releases` that
+    /// match `--filter`, in order.
+    Expand {
+        /// How far apart the enumerated versions are.
+        #[clap(long, value_enum, default_value_t = ExpandStep::Patch)]
+        step: ExpandStep,
+
+        /// The highest patch of a minor line (`1.2=7`), or the highest minor
+        /// of a major line (`1=4`). May be repeated.
+        #[clap(long, value_name = "LINE=MAX")]
+        limit: Vec<LineLimit>,
+
+        /// A list of published versions (path, or `-` for stdin).
+        #[clap(long, value_name = "FILE")]
+        releases: Option<PathBuf>,
+
+        /// Exclude versions with a non-empty pre-release from `--releases`.
+        #[clap(long, action, requires = "releases")]
+        stable: bool,
+
+        #[clap(long, short = 'f', default_value = None)]
+        /// Only list versions that match a filter.
+        ///
+        /// See `sort --help` for VersionReq documentation.
+        filter: Option<VersionReq>,
+
+        /// The first and last release to list, as `A..B`.
+        #[clap(required_unless_present_all = ["releases", "filter"])]
+        range: Option<VersionRange>,
+    },
+    /// How far one version is behind (or ahead of) another.
+    ///
+    /// Reports component deltas the way people say them: the most
+    /// significant differing component is the difference between the two,
+    /// and each less significant one counts from zero on the newer line
+    /// (`1.2.3` is 3 minor and 2 patch behind `1.5.2`).
+    ///
+    /// With `--releases`, also counts the published versions after the older
+    /// of the two, up to and including the newer. Versions sharing
+    /// precedence (differing only in build metadata) count once.
+    Distance {
+        /// A list of published versions (path, or `-` for stdin).
+        ///
+        /// When `TO` is omitted, the latest of these is used.
+        #[clap(long, value_name = "FILE")]
+        releases: Option<PathBuf>,
+
+        /// Exclude versions with a non-empty pre-release from `--releases`.
+        #[clap(long, action, requires = "releases")]
+        stable: bool,
+
+        /// The version being measured, e.g. what is deployed.
+        from: Version,
+
+        /// The version to measure against.
+        #[clap(required_unless_present = "releases")]
+        to: Option<Version>,
+    },
 }
 
 pub(crate) fn run() -> Result<ExitOutcome, Box<dyn Error>> {
     let args = Args::parse();
+    if matches!(args.cmd, Commands::Diff { .. }) {
+        return run_command(args).map_err(|err| {
+            if err.is::<ExitStatusError>() {
+                err
+            } else {
+                Box::new(ExitStatusError {
+                    status: DiffResult::TROUBLE,
+                    err,
+                })
+            }
+        });
+    }
+    run_command(args)
+}
 
+fn run_command(args: Args) -> Result<ExitOutcome, Box<dyn Error>> {
     let mut ignore_exit_status_from_output = false;
 
     let result: SubcommandResult = match args.cmd {
@@ -366,21 +1176,88 @@ pub(crate) fn run() -> Result<ExitOutcome, Box<dyn Error>> {
         Commands::Compare {
             set_exit_status,
             semantic_exit_status,
+            exit_scheme,
+            relation,
+            fail_on_diff,
+            fail_if_incompatible,
+            trace,
+            rules,
+            matrix,
+            matrix_format,
             a,
             b,
         } => {
-            // If we don't consider non-equivalence an error, don't report one
-            // on process exit.
-            if !set_exit_status {
-                ignore_exit_status_from_output = true;
-            }
-            let res = ComparisonStatement::new(&a, &b);
+            if let Some(matrix) = matrix {
+                let versions = parse_versions((!matrix.is_empty()).then_some(matrix))?;
+                ComparisonMatrix::new(versions, matrix_format.unwrap_or_default()).into()
+            } else {
+                // clap drops `requires = "matrix"` since the versions
+                // conflict with it, so this is checked here.
+                if matrix_format.is_some() {
+                    return Err(Box::new(ApplicationError::InvalidArgument {
+                        expected: "--matrix with --matrix-format".to_string(),
+                        found: "--matrix-format without --matrix".to_string(),
+                    }));
+                }
+                let (Some(a), Some(b)) = (a, b) else {
+                    unreachable!("clap requires both versions without --matrix");
+                };
 
-            if semantic_exit_status && res.semantic_ordering() == &SerializableOrdering::Equal {
-                ignore_exit_status_from_output = true
-            }
+                let relation = relation.relation();
+                let failure = match fail_on_diff {
+                    Some(component) => Some(CompareExit::FailOnDiff(component)),
+                    None => fail_if_incompatible.then_some(CompareExit::Incompatible),
+                };
+                let exit = match (exit_scheme, relation, failure) {
+                    (_, _, Some(failure)) => failure,
+                    (None | Some(ExitScheme::TestV1), Some(relation), None) => {
+                        CompareExit::Test(relation)
+                    }
+                    (Some(ExitScheme::TestV1), None, None) => {
+                        return Err(Box::new(ApplicationError::InvalidArgument {
+                            expected: "a relation (--lt, --le, --eq, --ne, --ge, --gt)".to_string(),
+                            found: "--exit-scheme test-v1 without one".to_string(),
+                        }));
+                    }
+                    (Some(scheme), Some(relation), None) => {
+                        return Err(Box::new(ApplicationError::InvalidArgument {
+                            expected: "a relation only with --exit-scheme test-v1".to_string(),
+                            found: format!(
+                                "--{relation} with --exit-scheme {}",
+                                scheme
+                                    .to_possible_value()
+                                    .expect("no exit scheme is skipped")
+                                    .get_name()
+                            ),
+                        }));
+                    }
+                    (Some(ExitScheme::ThreeWayV1), None, None) => CompareExit::ThreeWay,
+                    (Some(ExitScheme::DualV1) | None, None, None) => CompareExit::Dual,
+                };
 
-            res.into()
+                // If we don't consider non-equivalence an error, don't report
+                // one on process exit.
+                if !set_exit_status
+                    && exit_scheme.is_none()
+                    && relation.is_none()
+                    && failure.is_none()
+                {
+                    ignore_exit_status_from_output = true;
+                }
+                let mut res = ComparisonStatement::new(&a, &b).exit_with(exit);
+                if trace {
+                    res = res.traced(&a, &b);
+                }
+                if let Some(rules) = rules {
+                    res = res.under_rules(&a, &b, rules);
+                }
+
+                if semantic_exit_status && res.semantic_ordering() == &SerializableOrdering::Equal {
+                    ignore_exit_status_from_output = true
+                }
+
+                res.into()
+            }
         }
         Commands::Sort {
             versions,
@@ -390,27 +1267,119 @@ pub(crate) fn run() -> Result<ExitOutcome, Box<dyn Error>> {
             flatten,
             fail_if_potentially_ambiguous,
             stable,
+            check,
+            merge,
+            buffer_size,
+            rules,
+            key,
+            tokens,
         } => {
-            let mut parsed_versions = parse_versions(versions)?;
+            let unkeyed = key.unkeyed;
+            // clap rejects combining these modes, so the order of the
+            // branches doesn't decide between them.
+            if let Some(rules) = rules {
+                let mut inputs = parse_input_versions(versions, &tokens)?;
+                retain_wanted(&mut inputs, &filter, stable);
+                RulesSortResult::new(inputs, rules, reverse).into()
+            } else if let Some(line_key) = key.line_key() {
+                let keyed_lines = KeyedLinesList::new(
+                    read_lines(io::stdin().lock(), "stdin")?,
+                    &line_key,
+                    unkeyed,
+                    &filter,
+                    lexical_sorting,
+                    reverse,
+                    stable,
+                );
 
-            let mut ordered_version_list = OrderedVersionMap::new(
-                &mut parsed_versions,
-                &filter,
-                lexical_sorting,
-                reverse,
-                stable,
-            );
+                if fail_if_potentially_ambiguous && keyed_lines.potentially_ambiguous() {
+                    return Err(Box::new(ApplicationError::FailedRequirementError {
+                        err: "Potential Ambiguity Detected".to_string(),
+                    }));
+                }
 
-            if fail_if_potentially_ambiguous && ordered_version_list.potentially_ambiguous() {
-                return Err(Box::new(ApplicationError::FailedRequirementError {
-                    err: "Potential Ambiguity Detected".to_string(),
-                }));
-            }
+                keyed_lines.into()
+            } else if let Some(check) = check {
+                let mut inputs = parse_input_versions(versions, &tokens)?;
+                retain_wanted(&mut inputs, &filter, stable);
+                SortCheckResult::check(&inputs, check, lexical_sorting, reverse).into()
+            } else if let Some(buffer_size) = buffer_size {
+                let source = if versions.is_some() {
+                    "argument"
+                } else {
+                    "stdin"
+                };
+                let mut spiller = Spiller::new(
+                    Arc::from(source),
+                    buffer_size,
+                    filter,
+                    lexical_sorting,
+                    reverse,
+                    stable,
+                    tokens.allow_v_prefix,
+                    tokens.render,
+                );
+                match versions {
+                    Some(versions) => {
+                        spiller.push(parse_input_versions(Some(versions), &tokens)?)?
+                    }
+                    None => read_input_batches(io::stdin().lock(), source, &tokens, |batch| {
+                        spiller.push(batch)
+                    })?,
+                }
+                spiller.finish()?
+            } else if flatten && merge.is_none() {
+                // Flattened output doesn't need the map, so order in place.
+                let flat = FlatVersionsList::new(
+                    parse_input_versions(versions, &tokens)?,
+                    &filter,
+                    lexical_sorting,
+                    reverse,
+                    stable,
+                );
+
+                if fail_if_potentially_ambiguous && flat.potentially_ambiguous() {
+                    return Err(Box::new(ApplicationError::FailedRequirementError {
+                        err: "Potential Ambiguity Detected".to_string(),
+                    }));
+                }
 
-            if flatten {
-                FlatVersionsList::from(&mut ordered_version_list).into()
+                flat.into()
             } else {
-                ordered_version_list.into()
+                let mut ordered_version_list = match merge {
+                    Some(sources) => {
+                        if sources.iter().filter(|s| *s == Path::new("-")).count() > 1 {
+                            return Err(Box::new(ApplicationError::InvalidArgument {
+                                expected: "at most one source to be stdin".to_string(),
+                                found: "stdin given more than once".to_string(),
+                            }));
+                        }
+                        let inputs = sources
+                            .iter()
+                            .map(|source| read_input_source(source, &tokens))
+                            .collect::<Result<_, _>>()?;
+                        OrderedVersionMap::merge(inputs, &filter, lexical_sorting, reverse, stable)?
+                    }
+                    None => OrderedVersionMap::from_inputs(
+                        parse_input_versions(versions, &tokens)?,
+                        &filter,
+                        lexical_sorting,
+                        reverse,
+                        stable,
+                    ),
+                };
+
+                if fail_if_potentially_ambiguous && ordered_version_list.potentially_ambiguous() {
+                    return Err(Box::new(ApplicationError::FailedRequirementError {
+                        err: "Potential Ambiguity Detected".to_string(),
+                    }));
+                }
+
+                if flatten {
+                    FlatVersionsList::from(&mut ordered_version_list).into()
+                } else {
+                    ordered_version_list.into()
+                }
             }
         }
         Commands::FilterTest {
@@ -443,8 +1412,103 @@ pub(crate) fn run() -> Result<ExitOutcome, Box<dyn Error>> {
             bump_major,
             bump_minor,
             bump_patch,
-        } => VersionMutationResult::bump(&semantic_version, bump_major, bump_minor, bump_patch)?
-            .into(),
+            bump_pre_release,
+            bump_build_metadata,
+        } => {
+            let bumped =
+                VersionMutationResult::bump(&semantic_version, bump_major, bump_minor, bump_patch)?;
+            VersionMutationResult::bump_identifiers(
+                &bumped.mutated_version,
+                bump_pre_release.as_ref(),
+                bump_build_metadata.as_ref(),
+            )?
+            .into()
+        }
+        Commands::Next {
+            level,
+            base,
+            from_commits,
+            type_bump,
+            leave_initial_development,
+            avoid_existing,
+        } => {
+            if from_commits.is_none() && !type_bump.is_empty() {
+                return Err(Box::new(ApplicationError::InvalidArgument {
+                    expected: "--type-bump with --from-commits".to_string(),
+                    found: "--type-bump without --from-commits".to_string(),
+                }));
+            }
+            if from_commits.is_some() && avoid_existing.as_deref() == Some(Path::new("-")) {
+                return Err(Box::new(ApplicationError::InvalidArgument {
+                    expected: "--avoid-existing from a file with --from-commits".to_string(),
+                    found: "both reading stdin".to_string(),
+                }));
+            }
+            let published = avoid_existing
+                .map(|source| read_version_source(&source))
+                .transpose()?;
+            let result = match (from_commits, level, base) {
+                (Some(base), _, _) => {
+                    let messages = read_lines(io::stdin().lock(), "stdin")?.join("\n");
+                    NextVersionResult::from_commits(
+                        &base,
+                        ConventionalCommit::parse_all(&messages),
+                        &CommitBumps::new(type_bump),
+                        leave_initial_development,
+                    )?
+                }
+                (None, Some(level), Some(base)) => {
+                    NextVersionResult::next(&base, level, leave_initial_development)?
+                }
+                _ => unreachable!("clap requires a level and base without --from-commits"),
+            };
+            match published {
+                Some(published) => result
+                    .avoid_existing(&published, leave_initial_development)?
+                    .into(),
+                None => result.into(),
+            }
+        }
+        Commands::PreRelease {
+            step,
+            semantic_version,
+            id,
+        } => VersionMutationResult::pre_release(&semantic_version, step, id.as_deref())?.into(),
+        Commands::Snapshot {
+            level,
+            id,
+            timestamp: explicit,
+            leave_initial_development,
+            versions,
+        } => {
+            let date = format_date(timestamp(explicit)?, "%Y%m%d")?;
+            NextVersionResult::snapshot(
+                &parse_versions(versions)?,
+                level,
+                &id,
+                &date,
+                leave_initial_development,
+            )?
+            .into()
+        }
+        Commands::Stamp {
+            semantic_version,
+            template,
+            sha,
+            timestamp,
+            value,
+            append,
+        } => {
+            let sources = StampSources {
+                sha,
+                timestamp,
+                values: value
+                    .into_iter()
+                    .map(|Binding { name, value }| (name, value))
+                    .collect(),
+            };
+            StampResult::stamp(&semantic_version, &template, &sources, append)?.into()
+        }
         Commands::BumpReset {
             semantic_version,
             major,
@@ -467,6 +1531,132 @@ pub(crate) fn run() -> Result<ExitOutcome, Box<dyn Error>> {
             small,
             fail_if_not_found,
         } => SelectResult::select(version.as_str(), component, small, fail_if_not_found)?.into(),
+        Commands::Uniq {
+            by,
+            keep,
+            count,
+            versions,
+        } => UniqResult::uniq(&parse_versions(versions)?, by, keep, count).into(),
+        Commands::Diff { by, left, right } => {
+            if left == Path::new("-") && right == Path::new("-") {
+                return Err(Box::new(ApplicationError::InvalidArgument {
+                    expected: "at most one source to be stdin".to_string(),
+                    found: "both sources are stdin".to_string(),
+                }));
+            }
+            let left = read_version_source(&left)?;
+            let right = read_version_source(&right)?;
+            DiffResult::diff(&left, &right, by).into()
+        }
+        Commands::Files {
+            template,
+            regex,
+            pick,
+            filter,
+            lexical_sorting,
+            reverse,
+            stable,
+            allow_ambiguous,
+            paths,
+        } => {
+            let key = match (template, regex) {
+                (_, Some(re)) => LineKey::Regex(re),
+                (Some(template), None) => LineKey::from_template(&template)?,
+                (None, None) => unreachable!("clap requires a template or a regex"),
+            };
+            VersionedFilesResult::new(
+                collect_paths(paths)?,
+                &key,
+                pick,
+                &filter,
+                lexical_sorting,
+                reverse,
+                stable,
+                allow_ambiguous,
+            )?
+            .into()
+        }
+        Commands::Gaps {
+            filter,
+            stable,
+            fail_if_found,
+            versions,
+        } => {
+            let parsed_versions = parse_versions(versions)?;
+            let map = OrderedVersionMap::new(parsed_versions, &filter, false, false, stable);
+            GapsResult::gaps(&map, fail_if_found).into()
+        }
+        Commands::Guard {
+            candidate,
+            versions,
+        } => {
+            let map = OrderedVersionMap::new(parse_versions(versions)?, &None, false, false, false);
+            GuardResult::guard(&candidate, &map).into()
+        }
+        Commands::Lint { policy, versions } => {
+            let policy = Policy::load(policy.as_deref())?;
+            LintResult::lint(&parse_versions(versions)?, &policy).into()
+        }
+        Commands::Eval { expression } => expression.evaluate()?.into(),
+        Commands::Assert { expression } => {
+            let result = expression.evaluate()?;
+            if !matches!(result.value(), Value::Bool(_)) {
+                return Err(Box::new(ApplicationError::InvalidArgument {
+                    expected: "an expression that is true or false".to_string(),
+                    found: result.value().to_string(),
+                }));
+            }
+            result.into()
+        }
+        Commands::Expand {
+            step,
+            limit,
+            releases,
+            stable,
+            filter,
+            range,
+        } => {
+            let releases = releases
+                .map(|source| read_version_source(&source))
+                .transpose()?;
+            match (range, releases) {
+                (Some(range), releases) => {
+                    let releases = releases.map(|versions| {
+                        OrderedVersionMap::new(versions, &None, false, false, stable)
+                    });
+                    ExpandResult::expand(&range, step, &limit, releases.as_ref(), &filter)?
+                }
+                (None, Some(versions)) => ExpandResult::published(&OrderedVersionMap::new(
+                    versions, &filter, false, false, stable,
+                )),
+                (None, None) => unreachable!("clap requires a range without --releases"),
+            }
+            .into()
+        }
+        Commands::Distance {
+            releases,
+            stable,
+            from,
+            to,
+        } => {
+            let releases = releases
+                .map(|source| {
+                    read_version_source(&source).map(|versions| {
+                        OrderedVersionMap::new(versions, &None, false, false, stable)
+                    })
+                })
+                .transpose()?;
+            let to = match (to, &releases) {
+                (Some(to), _) => to,
+                (None, Some(releases)) => releases.highest().cloned().ok_or_else(|| {
+                    ApplicationError::FailedRequirementError {
+                        err: "no releases to measure against".to_string(),
+                    }
+                })?,
+                (None, None) => unreachable!("clap requires TO without --releases"),
+            };
+            DistanceResult::distance(&from, &to, releases.as_ref())?.into()
+        }
     };
 
     emit(&result, args.out)?;
@@ -477,32 +1667,233 @@ pub(crate) fn run() -> Result<ExitOutcome, Box<dyn Error>> {
 fn parse_versions(versions: Option<Vec<Version>>) -> Result<Vec<Version>, Box<dyn Error>> {
     match versions {
         Some(versions) => Ok(versions),
+        None => parse_version_lines(io::stdin().lock(), "stdin"),
+    }
+}
+
+/// Lines read and parsed at a time, bounding how much raw input is held.
+const PARSE_BATCH_LINES: usize = 1 << 16;
+
+/// Below this many lines, parsing isn't worth spreading across threads.
+const PARALLEL_PARSE_MIN_LINES: usize = 1 << 12;
+
+/// Parse version tokens from arguments, or one per line from stdin, keeping
+/// each original token and where it came from.
+fn parse_input_versions(
+    versions: Option<Vec<String>>,
+    tokens: &TokenArgs,
+) -> Result<Vec<InputVersion>, Box<dyn Error>> {
+    match versions {
+        // These are parsed here rather than by clap, since `--allow-v-prefix`
+        // changes what parses, but a bad one is still a usage error.
+        Some(versions) => parse_input_batch(&versions, &Arc::from("argument"), 0, tokens)
+            .map_err(|err| ExitStatusError { status: 2, err }.into()),
         None => {
             let mut parsed_versions = Vec::new();
-            let lines = io::stdin().lines();
-            for (line_no, line) in lines.enumerate() {
-                match line {
-                    Ok(line) => {
-                        let line = line.trim();
-                        parsed_versions.push(Version::parse(line).map_err(|e| {
-                            eprintln!(
-                                "unable to parse an enumerated version: line {line_no}: {line}: {e}"
-                            );
-                            e
-                        })?);
-                    }
-                    Err(e) => {
-                        eprintln!("unable to read from stdin: {e}");
-                        return Err(Box::new(ApplicationError::InvalidArgument {
-                            expected: "to be able to read from stdin".to_string(),
-                            found: e.to_string(),
-                        }));
-                    }
+            read_input_batches(io::stdin().lock(), "stdin", tokens, |batch| {
+                parsed_versions.extend(batch);
+                Ok(())
+            })?;
+            Ok(parsed_versions)
+        }
+    }
+}
+
+/// Like [parse_input_versions], from a path with `-` meaning stdin.
+fn read_input_source(
+    source: &Path,
+    tokens: &TokenArgs,
+) -> Result<Vec<InputVersion>, Box<dyn Error>> {
+    if source == Path::new("-") {
+        return parse_input_versions(None, tokens);
+    }
+
+    let file = File::open(source).map_err(|e| {
+        eprintln!("unable to open {}: {e}", source.display());
+        ApplicationError::InvalidArgument {
+            expected: format!("to be able to read from {}", source.display()),
+            found: e.to_string(),
+        }
+    })?;
+    let mut parsed_versions = Vec::new();
+    read_input_batches(
+        BufReader::new(file),
+        &source.display().to_string(),
+        tokens,
+        |batch| {
+            parsed_versions.extend(batch);
+            Ok(())
+        },
+    )?;
+    Ok(parsed_versions)
+}
+
+/// Read one version per line, handing them to `sink` a batch at a time.
+fn read_input_batches(
+    reader: impl BufRead,
+    source: &str,
+    tokens: &TokenArgs,
+    mut sink: impl FnMut(Vec<InputVersion>) -> Result<(), Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    let source_name: Arc<str> = Arc::from(source);
+    let mut lines = reader.lines();
+    let mut first_line = 0;
+    loop {
+        let batch: Vec<String> = lines
+            .by_ref()
+            .take(PARSE_BATCH_LINES)
+            .collect::<Result<_, _>>()
+            .map_err(|e| {
+                eprintln!("unable to read from {source}: {e}");
+                ApplicationError::InvalidArgument {
+                    expected: format!("to be able to read from {source}"),
+                    found: e.to_string(),
                 }
+            })?;
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        sink(parse_input_batch(&batch, &source_name, first_line, tokens)?)?;
+        first_line += batch.len();
+    }
+}
+
+/// Parse a batch of tokens, spread across the available cores when large
+/// enough. `first_line` counts the lines before this batch.
+fn parse_input_batch(
+    lines: &[String],
+    source: &Arc<str>,
+    first_line: usize,
+    tokens: &TokenArgs,
+) -> Result<Vec<InputVersion>, Box<dyn Error>> {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk_size = lines.len().div_ceil(threads).max(PARALLEL_PARSE_MIN_LINES);
+    parse_input_chunks(lines, source, first_line, tokens, chunk_size)
+}
+
+/// Parse tokens on a thread per `chunk_size` lines.
+fn parse_input_chunks(
+    lines: &[String],
+    source: &Arc<str>,
+    first_line: usize,
+    tokens: &TokenArgs,
+    chunk_size: usize,
+) -> Result<Vec<InputVersion>, Box<dyn Error>> {
+    let parse_chunk = |offset: usize, chunk: &[String]| {
+        chunk
+            .iter()
+            .enumerate()
+            .map(|(index, token)| {
+                let line = offset + index + 1;
+                InputVersion::parse(token, source, line, tokens.allow_v_prefix)
+                    .map(|parsed| parsed.rendered(tokens.render))
+                    .map_err(|e| (line, e))
+            })
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    let parsed = if lines.len() <= chunk_size {
+        parse_chunk(first_line, lines)
+    } else {
+        thread::scope(|scope| {
+            let handles: Vec<_> = lines
+                .chunks(chunk_size)
+                .enumerate()
+                .map(|(index, chunk)| {
+                    scope.spawn(move || parse_chunk(first_line + index * chunk_size, chunk))
+                })
+                .collect();
+
+            let mut parsed = Vec::with_capacity(lines.len());
+            for handle in handles {
+                parsed.extend(handle.join().expect("version parsing thread panicked")?);
             }
-            Ok(parsed_versions)
+            Ok(parsed)
+        })
+    };
+
+    parsed.map_err(|(line, e)| {
+        eprintln!(
+            "unable to parse a version: {source}:{line}: {}: {e}",
+            lines[line - first_line - 1].trim()
+        );
+        e.into()
+    })
+}
+
+/// Read one version per line from a path, with `-` meaning stdin.
+fn read_version_source(source: &Path) -> Result<Vec<Version>, Box<dyn Error>> {
+    if source == Path::new("-") {
+        return parse_version_lines(io::stdin().lock(), "stdin");
+    }
+
+    let file = File::open(source).map_err(|e| {
+        eprintln!("unable to open {}: {e}", source.display());
+        ApplicationError::InvalidArgument {
+            expected: format!("to be able to read from {}", source.display()),
+            found: e.to_string(),
         }
+    })?;
+    parse_version_lines(BufReader::new(file), &source.display().to_string())
+}
+
+/// Expand directories into the files they hold, or read paths from stdin.
+fn collect_paths(paths: Option<Vec<PathBuf>>) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let Some(paths) = paths else {
+        return Ok(read_lines(io::stdin().lock(), "stdin")?
+            .into_iter()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect());
+    };
+
+    let mut collected = Vec::new();
+    for path in paths {
+        if !path.is_dir() {
+            collected.push(path);
+            continue;
+        }
+
+        let entries = std::fs::read_dir(&path).map_err(|e| {
+            eprintln!("unable to read directory {}: {e}", path.display());
+            ApplicationError::InvalidArgument {
+                expected: format!("to be able to read from {}", path.display()),
+                found: e.to_string(),
+            }
+        })?;
+        for entry in entries {
+            let entry = entry?.path();
+            if entry.is_file() {
+                collected.push(entry);
+            }
+        }
+    }
+    Ok(collected)
+}
+
+fn parse_version_lines(reader: impl BufRead, source: &str) -> Result<Vec<Version>, Box<dyn Error>> {
+    let mut parsed_versions = Vec::new();
+    for (line_no, line) in read_lines(reader, source)?.iter().enumerate() {
+        let line = line.trim();
+        parsed_versions.push(Version::parse(line).map_err(|e| {
+            eprintln!("unable to parse an enumerated version: line {line_no}: {line}: {e}");
+            e
+        })?);
     }
+    Ok(parsed_versions)
+}
+
+fn read_lines(reader: impl BufRead, source: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    reader.lines().collect::<Result<_, _>>().map_err(|e| {
+        eprintln!("unable to read from {source}: {e}");
+        Box::new(ApplicationError::InvalidArgument {
+            expected: format!("to be able to read from {source}"),
+            found: e.to_string(),
+        })
+        .into()
+    })
 }
 
 fn boundary_versions(
@@ -515,12 +1906,12 @@ fn boundary_versions(
         reverse,
         stable,
         allow_ambiguous,
+        tokens,
         versions,
     } = args;
 
-    let mut parsed_versions = parse_versions(versions)?;
-    let map = OrderedVersionMap::new(
-        &mut parsed_versions,
+    let map = OrderedVersionMap::from_inputs(
+        parse_input_versions(versions, &tokens)?,
         &filter,
         lexical_sorting,
         reverse,
@@ -533,14 +1924,39 @@ fn boundary_versions(
 
 #[cfg(test)]
 mod tests {
+    use super::{TokenArgs, parse_input_chunks};
     use crate::results::{
-        ComparisonStatement, FilterTestResult, GenerateResult, OrderedVersionMap, SelectResult,
-        SemverComponent, SerializableOrdering, ValidateResult, VersionExplanation,
-        VersionMutationResult, version_without_build_metadata,
+        ComparisonMatrix, ComparisonStatement, DiffBy, DiffResult, FilterTestResult, GapsResult,
+        GenerateResult, MatrixFormat, OrderedVersionMap, SelectResult, SemverComponent,
+        SerializableOrdering, UniqBy, UniqKeep, UniqResult, ValidateResult, VersionExplanation,
+        VersionMutationResult, VersionRendering, version_without_build_metadata,
     };
     use proptest::prelude::*;
     use proptest_semver::*;
     use semver::Version;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parse_input_chunks() {
+        let tokens = TokenArgs {
+            allow_v_prefix: true,
+            render: VersionRendering::Provenance,
+        };
+        let source: Arc<str> = Arc::from("stdin");
+        let lines: Vec<String> = (0..10).map(|i| format!("v1.{i}.0")).collect();
+
+        for chunk_size in [1, 3, 10] {
+            let parsed = parse_input_chunks(&lines, &source, 20, &tokens, chunk_size).unwrap();
+            assert_eq!(parsed.len(), 10);
+            for (i, input) in parsed.iter().enumerate() {
+                assert_eq!(input.to_string(), format!("v1.{i}.0\tstdin:{}", 21 + i));
+            }
+        }
+
+        let mut bad = lines.clone();
+        bad[7] = "a.b.c".to_string();
+        assert!(parse_input_chunks(&bad, &source, 0, &tokens, 3).is_err());
+    }
 
     proptest! {
         //                 None of these tests do much more than ensure the
@@ -570,6 +1986,16 @@ mod tests {
             };
         }
 
+        #[test]
+        fn compare_matrix(versions in arb_vec_versions(8), format in prop_oneof![
+            Just(MatrixFormat::Table),
+            Just(MatrixFormat::Markdown),
+            Just(MatrixFormat::Csv),
+        ]) {
+            let matrix = ComparisonMatrix::new(versions, format);
+            let _ = matrix.to_string();
+        }
+
         #[test]
         fn explain(version in arb_version()) {
             let _ = VersionExplanation::from(&version);
@@ -587,8 +2013,40 @@ mod tests {
 
         #[test]
         fn sort(versions in arb_vec_versions(256), filter in arb_optional_version_req(0.5, MAX_COMPARATORS_IN_VERSION_REQ_STRING), lexical_sorting in any::<bool>(), reverse in any::<bool>(), stable in any::<bool>()) {
-            let mut versions = versions.clone();
-            OrderedVersionMap::new(&mut versions, &filter, lexical_sorting, reverse, stable);
+            OrderedVersionMap::new(versions, &filter, lexical_sorting, reverse, stable);
+        }
+
+        #[test]
+        fn uniq(versions in arb_vec_versions(256), by in prop_oneof![
+            Just(UniqBy::Exact),
+            Just(UniqBy::Precedence),
+            Just(UniqBy::Minor),
+            Just(UniqBy::Major),
+            Just(UniqBy::Compat),
+        ], keep in prop_oneof![
+            Just(UniqKeep::First),
+            Just(UniqKeep::Last),
+            Just(UniqKeep::Min),
+            Just(UniqKeep::Max),
+        ], count in any::<bool>()) {
+            UniqResult::uniq(&versions, by, keep, count);
+        }
+
+        #[test]
+        fn diff(left in arb_vec_versions(64), right in arb_vec_versions(64), by in prop_oneof![
+            Just(DiffBy::Exact),
+            Just(DiffBy::Precedence),
+        ]) {
+            let result = DiffResult::diff(&left, &right, by);
+            let reflexive = DiffResult::diff(&left, &left, by);
+            prop_assert!(!reflexive.differs());
+            let _ = format!("{result}");
+        }
+
+        #[test]
+        fn gaps(versions in arb_vec_versions(256), stable in any::<bool>()) {
+            let map = OrderedVersionMap::new(versions, &None, false, false, stable);
+            let _ = format!("{}", GapsResult::gaps(&map, false));
         }
 
         #[test]
@@ -0,0 +1,226 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+//!
+//! Reading Conventional Commits, for `next --from-commits`.
+//!
+//! Messages from `git log --format=%B%x00` are separated by NUL, and only the
+//! first line of each is a header. Without NULs, there's no telling where one
+//! message ends, so every line that looks like a header starts a commit, and
+//! a `BREAKING CHANGE:` footer belongs to the header before it. Git trailers
+//! (`Signed-off-by: x`) look like headers, but never start a commit.
+//!
+//! References:
+//! - https://www.conventionalcommits.org/en/v1.0.0/
+use std::{collections::HashMap, str::FromStr, sync::LazyLock};
+
+use clap::ValueEnum;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::regex::{CONVENTIONAL_COMMIT_BREAKING_REGEX, CONVENTIONAL_COMMIT_HEADER_REGEX};
+use crate::results::BumpLevel;
+
+static HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(CONVENTIONAL_COMMIT_HEADER_REGEX).expect("valid regex"));
+static BREAKING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(CONVENTIONAL_COMMIT_BREAKING_REGEX).expect("valid regex"));
+
+/// A commit whose header follows Conventional Commits.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub(crate) struct ConventionalCommit {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+    breaking: bool,
+    header: String,
+}
+
+impl ConventionalCommit {
+    fn from_header(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let captures = HEADER.captures(line)?;
+        Some(Self {
+            // Types are case insensitive (Conventional Commits §15).
+            kind: captures.get(1)?.as_str().to_lowercase(),
+            scope: captures.get(2).map(|scope| scope.as_str().to_string()),
+            breaking: captures.get(3).is_some(),
+            header: line.to_string(),
+        })
+    }
+
+    /// Every conventional commit in `messages`, in order. Other commits are
+    /// skipped.
+    pub(crate) fn parse_all(messages: &str) -> Vec<Self> {
+        let is_breaking = |line: &str| BREAKING.is_match(line.trim());
+        if messages.contains('\0') {
+            return messages
+                .split('\0')
+                .filter_map(|message| {
+                    let mut lines = message.trim().lines();
+                    let mut commit = Self::from_header(lines.next()?)?;
+                    commit.breaking |= lines.any(is_breaking);
+                    Some(commit)
+                })
+                .collect();
+        }
+
+        // Footers look like headers too (`BREAKING-CHANGE: x`, `Refs: y`).
+        // Git trailer tokens have dashes (`Signed-off-by:`), which types
+        // don't, and the rest of a trailer's paragraph is trailers as well.
+        let mut commits: Vec<Self> = Vec::new();
+        let mut in_trailers = false;
+        for line in messages.lines() {
+            if line.trim().is_empty() {
+                in_trailers = false;
+            } else if is_breaking(line) {
+                in_trailers = true;
+                if let Some(commit) = commits.last_mut() {
+                    commit.breaking = true;
+                }
+            } else if let Some(commit) = Self::from_header(line) {
+                if in_trailers || commit.kind.contains('-') {
+                    in_trailers = true;
+                } else {
+                    commits.push(commit);
+                }
+            }
+        }
+        commits
+    }
+}
+
+/// `TYPE=LEVEL`, from `--type-bump`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TypeBump {
+    kind: String,
+    level: BumpLevel,
+}
+
+impl FromStr for TypeBump {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, level) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected TYPE=LEVEL, found {s}"))?;
+        Ok(Self {
+            kind: kind.to_lowercase(),
+            level: BumpLevel::from_str(level, true)?,
+        })
+    }
+}
+
+/// Which bump each commit type calls for.
+///
+/// A breaking change is always a major bump. Otherwise `feat` is a minor
+/// bump, `fix` is a patch bump, and other types don't bump, unless
+/// overridden.
+pub(crate) struct CommitBumps {
+    by_type: HashMap<String, BumpLevel>,
+}
+
+impl CommitBumps {
+    pub(crate) fn new(overrides: Vec<TypeBump>) -> Self {
+        let mut by_type = HashMap::from([
+            ("feat".to_string(), BumpLevel::Minor),
+            ("fix".to_string(), BumpLevel::Patch),
+        ]);
+        by_type.extend(
+            overrides
+                .into_iter()
+                .map(|TypeBump { kind, level }| (kind, level)),
+        );
+        Self { by_type }
+    }
+
+    pub(crate) fn level(&self, commit: &ConventionalCommit) -> BumpLevel {
+        if commit.breaking {
+            BumpLevel::Major
+        } else {
+            self.by_type
+                .get(&commit.kind)
+                .copied()
+                .unwrap_or(BumpLevel::None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commits() {
+        let messages = "feat(parser): add ranges\n\nSome body.\n\n\
+                        chore: tidy up\n\
+                        not conventional\n\
+                        Fix!: drop the old flag\n\
+                        fix: typo\n\
+                        \n\
+                        BREAKING CHANGE: the output moved\n";
+        let commits = ConventionalCommit::parse_all(messages);
+        let summary: Vec<(&str, Option<&str>, bool)> = commits
+            .iter()
+            .map(|c| (c.kind.as_str(), c.scope.as_deref(), c.breaking))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("feat", Some("parser"), false),
+                ("chore", None, false),
+                ("fix", None, true),
+                ("fix", None, true),
+            ]
+        );
+
+        // Footers and trailers are not headers.
+        let commits = ConventionalCommit::parse_all(
+            "feat: a\n\nBREAKING-CHANGE: dropped flag\n\n\
+             fix: b\n\nSigned-off-by: A <a@example.com>\nRefs: #12\n\n\
+             docs: c\n\nCo-authored-by: B <b@example.com>\n",
+        );
+        let summary: Vec<(&str, bool)> = commits
+            .iter()
+            .map(|c| (c.kind.as_str(), c.breaking))
+            .collect();
+        assert_eq!(summary, [("feat", true), ("fix", false), ("docs", false)]);
+
+        // With NULs, only the first line of each message is a header.
+        let commits = ConventionalCommit::parse_all(
+            "feat: a\n\nfix: not a header\n\0docs: b\n\nBREAKING-CHANGE: c\n\0merge x\n\0",
+        );
+        let summary: Vec<(&str, bool)> = commits
+            .iter()
+            .map(|c| (c.kind.as_str(), c.breaking))
+            .collect();
+        assert_eq!(summary, [("feat", false), ("docs", true)]);
+    }
+
+    #[test]
+    fn test_commit_bumps() {
+        let bumps = CommitBumps::new(vec![
+            "perf=patch".parse().unwrap(),
+            "FIX=none".parse().unwrap(),
+        ]);
+        let level = |header: &str| bumps.level(&ConventionalCommit::from_header(header).unwrap());
+        assert_eq!(level("feat: a"), BumpLevel::Minor);
+        assert_eq!(level("perf: a"), BumpLevel::Patch);
+        assert_eq!(level("fix: a"), BumpLevel::None);
+        assert_eq!(level("docs!: a"), BumpLevel::Major);
+        assert!("perf".parse::<TypeBump>().is_err());
+        assert!("perf=huge".parse::<TypeBump>().is_err());
+    }
+}
@@ -0,0 +1,848 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+//!
+//! A small expression language over versions, for `eval` and `assert`.
+//!
+//! ```text
+//! or         := and ( "||" and )*
+//! and        := unary ( "&&" unary )*
+//! unary      := "!" unary | comparison
+//! comparison := postfix ( ( "==" | "!=" | "<" | "<=" | ">" | ">=" | "~" ) postfix )?
+//! postfix    := primary ( "." component )*
+//! primary    := NUMBER | VERSION | REQUIREMENT | STRING | "true" | "false"
+//!             | NAME | NAME "(" args ")" | "(" or ")" | "[" args "]"
+//! ```
+//!
+//! Versions compare by SemVer precedence, as `compare` does, `~` tests a
+//! requirement as `filter-test` does, and components are those `select`
+//! extracts. Requirement literals start with `^`, or `~` directly followed by
+//! a digit; anything else (`>=1.2, <2`) is written as a string. After an
+//! operand, such a `~` is also the operator, so `new ~1.2` is `new ~ ~1.2`.
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    env, fmt,
+    ops::Range,
+    process::{ExitCode, Termination},
+    str::FromStr,
+};
+
+use semver::{Version, VersionReq};
+use serde::{Serialize, Serializer};
+
+use crate::misc::ApplicationError;
+use crate::results::{SelectResult, SemverComponent};
+
+fn invalid(expected: impl Into<String>, found: impl Into<String>) -> ApplicationError {
+    ApplicationError::InvalidArgument {
+        expected: expected.into(),
+        found: found.into(),
+    }
+}
+
+/// A value an expression evaluates to.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Bool(bool),
+    Number(u64),
+    String(String),
+    Version(Version),
+    Requirement(VersionReq),
+    List(Vec<Version>),
+}
+
+impl Value {
+    /// A variable's text is a version if it parses as one.
+    pub(crate) fn from_variable(text: &str) -> Self {
+        Version::parse(text)
+            .map(Self::Version)
+            .unwrap_or_else(|_| Self::String(text.to_string()))
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Bool(_) => "a boolean",
+            Self::Number(_) => "a number",
+            Self::String(_) => "a string",
+            Self::Version(_) => "a version",
+            Self::Requirement(_) => "a requirement",
+            Self::List(_) => "a list",
+        }
+    }
+
+    fn into_bool(self, context: &str) -> Result<bool, ApplicationError> {
+        match self {
+            Self::Bool(b) => Ok(b),
+            other => Err(invalid(
+                format!("a boolean {context}"),
+                format!("{} ({other})", other.kind()),
+            )),
+        }
+    }
+
+    /// Strings stand in for versions wherever a version is expected.
+    fn into_version(self, context: &str) -> Result<Version, ApplicationError> {
+        match self {
+            Self::Version(v) => Ok(v),
+            Self::String(s) => Version::parse(&s)
+                .map_err(|e| invalid(format!("a version {context}"), format!("\"{s}\": {e}"))),
+            other => Err(invalid(
+                format!("a version {context}"),
+                format!("{} ({other})", other.kind()),
+            )),
+        }
+    }
+
+    /// Strings stand in for requirements wherever a requirement is expected.
+    fn into_requirement(self, context: &str) -> Result<VersionReq, ApplicationError> {
+        match self {
+            Self::Requirement(r) => Ok(r),
+            Self::String(s) => VersionReq::parse(&s)
+                .map_err(|e| invalid(format!("a requirement {context}"), format!("\"{s}\": {e}"))),
+            other => Err(invalid(
+                format!("a requirement {context}"),
+                format!("{} ({other})", other.kind()),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::String(s) => write!(f, "{s}"),
+            Self::Version(v) => write!(f, "{v}"),
+            Self::Requirement(r) => write!(f, "{r}"),
+            Self::List(versions) => {
+                let versions: Vec<String> = versions.iter().map(Version::to_string).collect();
+                write!(f, "[{}]", versions.join(", "))
+            }
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Bool(b) => serializer.serialize_bool(*b),
+            Self::Number(n) => serializer.serialize_u64(*n),
+            Self::List(versions) => serializer.collect_seq(versions.iter().map(Version::to_string)),
+            other => serializer.collect_str(other),
+        }
+    }
+}
+
+/// Named values an expression can refer to.
+///
+/// Names that aren't bound are looked up in the environment.
+#[derive(Default)]
+pub(crate) struct Variables {
+    bound: HashMap<String, Value>,
+}
+
+impl Variables {
+    pub(crate) fn bind(&mut self, name: String, value: Value) {
+        self.bound.insert(name, value);
+    }
+
+    fn lookup(&self, name: &str) -> Result<Value, ApplicationError> {
+        if let Some(value) = self.bound.get(name) {
+            return Ok(value.clone());
+        }
+        env::var(name)
+            .map(|text| Value::from_variable(&text))
+            .map_err(|_| {
+                invalid(
+                    format!("a --var, --list or environment variable named {name}"),
+                    "nothing",
+                )
+            })
+    }
+}
+
+/// `NAME=VALUE`, from `--var` and `--list`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Binding {
+    pub(crate) name: String,
+    pub(crate) value: String,
+}
+
+impl FromStr for Binding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected NAME=VALUE, found {s}"))?;
+        if name.is_empty()
+            || name.starts_with(|c: char| c.is_ascii_digit())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(format!("{name} is not a name, use letters, digits and _"));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Name(String),
+    Number(u64),
+    Version(Version),
+    Requirement(VersionReq),
+    String(String),
+    Op(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => write!(f, "{name}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Version(v) => write!(f, "{v}"),
+            Self::Requirement(r) => write!(f, "{r}"),
+            Self::String(s) => write!(f, "\"{s}\""),
+            Self::Op(op) => write!(f, "{op}"),
+        }
+    }
+}
+
+const OPERATORS: [&str; 16] = [
+    "&&", "||", "==", "!=", "<=", ">=", "<", ">", "~", "!", "(", ")", "[", "]", ",", ".",
+];
+
+/// Whether an operand can end with `token`, so an operator comes next.
+fn ends_operand(token: &Token) -> bool {
+    !matches!(token, Token::Op(op) if !matches!(*op, ")" | "]"))
+}
+
+fn is_literal_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | '*')
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, Range<usize>)>, ApplicationError> {
+    let mut tokens = Vec::new();
+    let mut rest = source.char_indices().peekable();
+    while let Some(&(start, c)) = rest.peek() {
+        let literal_end = |from: usize| {
+            source[from..]
+                .find(|c| !is_literal_char(c))
+                .map_or(source.len(), |end| from + end)
+        };
+        let (token, end) = if c.is_whitespace() {
+            rest.next();
+            continue;
+        } else if c == '"' {
+            let mut text = String::new();
+            let mut end = None;
+            rest.next();
+            while let Some((i, c)) = rest.next() {
+                match c {
+                    '"' => {
+                        end = Some(i + 1);
+                        break;
+                    }
+                    '\\' => text.extend(rest.next().map(|(_, c)| c)),
+                    c => text.push(c),
+                }
+            }
+            let end = end.ok_or_else(|| {
+                invalid(
+                    format!("a closing '\"' for the string at column {}", start + 1),
+                    "the end of the expression",
+                )
+            })?;
+            (Token::String(text), end)
+        } else if c.is_ascii_digit() {
+            let end = literal_end(start);
+            let text = &source[start..end];
+            let token = if text.bytes().all(|b| b.is_ascii_digit()) {
+                text.parse().map(Token::Number).map_err(|e| {
+                    invalid(
+                        format!("a number at column {}", start + 1),
+                        format!("{text}: {e}"),
+                    )
+                })?
+            } else {
+                Version::parse(text).map(Token::Version).map_err(|e| {
+                    invalid(
+                        format!("a number or version at column {}", start + 1),
+                        format!("{text}: {e}"),
+                    )
+                })?
+            };
+            (token, end)
+        } else if (c == '^' || c == '~')
+            && source[start + 1..].starts_with(|c: char| c.is_ascii_digit())
+        {
+            let end = literal_end(start + 1);
+            let text = &source[start..end];
+            let token = VersionReq::parse(text)
+                .map(Token::Requirement)
+                .map_err(|e| {
+                    invalid(
+                        format!("a requirement at column {}", start + 1),
+                        format!("{text}: {e}"),
+                    )
+                })?;
+            if c == '~' && tokens.last().is_some_and(|(token, _)| ends_operand(token)) {
+                tokens.push((Token::Op("~"), start..start + 1));
+            }
+            (token, end)
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let end = source[start..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .map_or(source.len(), |end| start + end);
+            (Token::Name(source[start..end].to_string()), end)
+        } else if let Some(op) = OPERATORS.iter().find(|op| source[start..].starts_with(*op)) {
+            (Token::Op(op), start + op.len())
+        } else {
+            return Err(invalid(
+                format!("an operator or operand at column {}", start + 1),
+                c.to_string(),
+            ));
+        };
+        while rest.peek().is_some_and(|&(i, _)| i < end) {
+            rest.next();
+        }
+        tokens.push((token, start..end));
+    }
+    Ok(tokens)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Matches,
+}
+
+impl Comparison {
+    fn from_op(op: &str) -> Option<Self> {
+        Some(match op {
+            "==" => Self::Eq,
+            "!=" => Self::Ne,
+            "<" => Self::Lt,
+            "<=" => Self::Le,
+            ">" => Self::Gt,
+            ">=" => Self::Ge,
+            "~" => Self::Matches,
+            _ => return None,
+        })
+    }
+
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Eq => ordering.is_eq(),
+            Self::Ne => ordering.is_ne(),
+            Self::Lt => ordering.is_lt(),
+            Self::Le => ordering.is_le(),
+            Self::Gt => ordering.is_gt(),
+            Self::Ge => ordering.is_ge(),
+            Self::Matches => unreachable!("matching is not an ordering"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Function {
+    Max,
+    Min,
+    Len,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Literal(Value),
+    Variable(String),
+    Component(Box<Expr>, SemverComponent),
+    Call(Function, Vec<Expr>),
+    List(Vec<Expr>),
+    Not(Box<Expr>),
+    Compare(Box<Expr>, Comparison, Box<Expr>),
+    /// Each term, with where it is in the source.
+    All(Vec<(Expr, Range<usize>)>),
+    Any(Vec<Expr>),
+}
+
+struct Parser {
+    tokens: Vec<(Token, Range<usize>)>,
+    position: usize,
+    source_len: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn peek_op(&self, op: &str) -> bool {
+        matches!(self.peek(), Some(Token::Op(o)) if *o == op)
+    }
+
+    fn column(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .map_or(self.source_len, |(_, span)| span.start)
+            + 1
+    }
+
+    fn found(&self) -> String {
+        self.peek()
+            .map_or_else(|| "the end of the expression".to_string(), Token::to_string)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .map(|(token, _)| token.clone());
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), ApplicationError> {
+        if self.peek_op(op) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(invalid(
+                format!("'{op}' at column {}", self.column()),
+                self.found(),
+            ))
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ApplicationError> {
+        let mut terms = vec![self.parse_and()?];
+        while self.peek_op("||") {
+            self.position += 1;
+            terms.push(self.parse_and()?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            Expr::Any(terms)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ApplicationError> {
+        let mut terms = vec![self.parse_spanned()?];
+        while self.peek_op("&&") {
+            self.position += 1;
+            terms.push(self.parse_spanned()?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.remove(0).0
+        } else {
+            Expr::All(terms)
+        })
+    }
+
+    fn parse_spanned(&mut self) -> Result<(Expr, Range<usize>), ApplicationError> {
+        let first = self.position;
+        let expr = self.parse_unary()?;
+        let start = self.tokens[first].1.start;
+        let end = self.tokens[self.position - 1].1.end;
+        Ok((expr, start..end))
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ApplicationError> {
+        if self.peek_op("!") {
+            self.position += 1;
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        let left = self.parse_postfix()?;
+        let comparison = match self.peek() {
+            Some(Token::Op(op)) => Comparison::from_op(op),
+            _ => None,
+        };
+        let Some(comparison) = comparison else {
+            return Ok(left);
+        };
+        self.position += 1;
+        let right = self.parse_postfix()?;
+        if matches!(self.peek(), Some(Token::Op(op)) if Comparison::from_op(op).is_some()) {
+            return Err(invalid(
+                format!(
+                    "'&&' or '||' between comparisons at column {}",
+                    self.column()
+                ),
+                self.found(),
+            ));
+        }
+        Ok(Expr::Compare(Box::new(left), comparison, Box::new(right)))
+    }
+
+    fn parse_postfix(&mut self) -> Result<Expr, ApplicationError> {
+        let mut expr = self.parse_primary()?;
+        while self.peek_op(".") {
+            self.position += 1;
+            let expected = || "major, minor, patch, pre or build after '.'".to_string();
+            let component = match self.next() {
+                Some(Token::Name(name)) => match name.as_str() {
+                    "major" => SemverComponent::Major,
+                    "minor" => SemverComponent::Minor,
+                    "patch" => SemverComponent::Patch,
+                    "pre" => SemverComponent::PreRelease,
+                    "build" => SemverComponent::BuildMetadata,
+                    _ => return Err(invalid(expected(), name)),
+                },
+                other => {
+                    return Err(invalid(
+                        expected(),
+                        other.map_or_else(
+                            || "the end of the expression".to_string(),
+                            |t| t.to_string(),
+                        ),
+                    ));
+                }
+            };
+            expr = Expr::Component(Box::new(expr), component);
+        }
+        Ok(expr)
+    }
+
+    fn parse_args(&mut self, close: &str) -> Result<Vec<Expr>, ApplicationError> {
+        let mut args = Vec::new();
+        while !self.peek_op(close) {
+            args.push(self.parse_or()?);
+            if !self.peek_op(close) {
+                self.expect(",")?;
+            }
+        }
+        self.expect(close)?;
+        Ok(args)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, ApplicationError> {
+        let expected = format!("an operand at column {}", self.column());
+        let found = self.found();
+        Ok(match self.next() {
+            Some(Token::Number(n)) => Expr::Literal(Value::Number(n)),
+            Some(Token::Version(v)) => Expr::Literal(Value::Version(v)),
+            Some(Token::Requirement(r)) => Expr::Literal(Value::Requirement(r)),
+            Some(Token::String(s)) => Expr::Literal(Value::String(s)),
+            Some(Token::Name(name)) if name == "true" => Expr::Literal(Value::Bool(true)),
+            Some(Token::Name(name)) if name == "false" => Expr::Literal(Value::Bool(false)),
+            Some(Token::Name(name)) if self.peek_op("(") => {
+                let function = match name.as_str() {
+                    "max" => Function::Max,
+                    "min" => Function::Min,
+                    "len" => Function::Len,
+                    _ => return Err(invalid("a function of max, min or len", name)),
+                };
+                self.position += 1;
+                Expr::Call(function, self.parse_args(")")?)
+            }
+            Some(Token::Name(name)) => Expr::Variable(name),
+            Some(Token::Op("(")) => {
+                let expr = self.parse_or()?;
+                self.expect(")")?;
+                expr
+            }
+            Some(Token::Op("[")) => Expr::List(self.parse_args("]")?),
+            _ => return Err(invalid(expected, found)),
+        })
+    }
+}
+
+/// A parsed expression.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Expression {
+    source: String,
+    root: Expr,
+}
+
+impl Expression {
+    pub(crate) fn parse(source: &str) -> Result<Self, ApplicationError> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            position: 0,
+            source_len: source.len(),
+        };
+        let root = parser.parse_or()?;
+        if parser.peek().is_some() {
+            return Err(invalid(
+                format!("an operator at column {}", parser.column()),
+                parser.found(),
+            ));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            root,
+        })
+    }
+
+    /// Evaluate, noting the first term of a top level `&&` that didn't hold.
+    pub(crate) fn evaluate(&self, variables: &Variables) -> Result<EvalResult, ApplicationError> {
+        let (value, failed) = match &self.root {
+            Expr::All(terms) => {
+                let mut failed = None;
+                for (term, span) in terms {
+                    if !evaluate(term, variables)?.into_bool("on each side of '&&'")? {
+                        failed = Some(self.source[span.clone()].to_string());
+                        break;
+                    }
+                }
+                (Value::Bool(failed.is_none()), failed)
+            }
+            root => (evaluate(root, variables)?, None),
+        };
+        Ok(EvalResult {
+            expression: self.source.clone(),
+            value,
+            failed,
+        })
+    }
+}
+
+fn evaluate(expr: &Expr, variables: &Variables) -> Result<Value, ApplicationError> {
+    Ok(match expr {
+        Expr::Literal(value) => value.clone(),
+        Expr::Variable(name) => variables.lookup(name)?,
+        Expr::Component(expr, component) => {
+            let version = evaluate(expr, variables)?.into_version("before '.'")?;
+            let value =
+                SelectResult::extract_from_version(&version, *component).unwrap_or_default();
+            match component {
+                SemverComponent::PreRelease | SemverComponent::BuildMetadata => {
+                    Value::String(value)
+                }
+                _ => Value::Number(value.parse().expect("numeric component")),
+            }
+        }
+        Expr::Call(function, args) => {
+            let mut versions = Vec::new();
+            for arg in args {
+                match evaluate(arg, variables)? {
+                    Value::List(list) => versions.extend(list),
+                    other => versions.push(other.into_version("or list as an argument")?),
+                }
+            }
+            match function {
+                Function::Len => Value::Number(versions.len() as u64),
+                Function::Max | Function::Min => {
+                    let pick = if *function == Function::Max {
+                        Iterator::max_by
+                    } else {
+                        Iterator::min_by
+                    };
+                    pick(versions.into_iter(), |a: &Version, b: &Version| {
+                        a.cmp_precedence(b)
+                    })
+                    .map(Value::Version)
+                    .ok_or_else(|| {
+                        ApplicationError::FailedRequirementError {
+                            err: "max and min need at least one version".to_string(),
+                        }
+                    })?
+                }
+            }
+        }
+        Expr::List(items) => Value::List(
+            items
+                .iter()
+                .map(|item| evaluate(item, variables)?.into_version("in a list"))
+                .collect::<Result<_, _>>()?,
+        ),
+        Expr::Not(expr) => Value::Bool(!evaluate(expr, variables)?.into_bool("after '!'")?),
+        Expr::Compare(left, Comparison::Matches, right) => {
+            let version = evaluate(left, variables)?.into_version("on the left of '~'")?;
+            let requirement =
+                evaluate(right, variables)?.into_requirement("on the right of '~'")?;
+            Value::Bool(requirement.matches(&version))
+        }
+        Expr::Compare(left, comparison, right) => {
+            let ordering = match (evaluate(left, variables)?, evaluate(right, variables)?) {
+                (Value::Number(a), Value::Number(b)) => a.cmp(&b),
+                (Value::String(a), Value::String(b)) => a.cmp(&b),
+                (Value::Version(a), b) => a.cmp_precedence(&b.into_version("to compare with")?),
+                (a, Value::Version(b)) => a.into_version("to compare with")?.cmp_precedence(&b),
+                (Value::Bool(a), Value::Bool(b))
+                    if matches!(comparison, Comparison::Eq | Comparison::Ne) =>
+                {
+                    a.cmp(&b)
+                }
+                (a, b) => {
+                    return Err(invalid(
+                        "two numbers, strings or versions to compare",
+                        format!("{} and {}", a.kind(), b.kind()),
+                    ));
+                }
+            };
+            Value::Bool(comparison.holds(ordering))
+        }
+        Expr::All(terms) => {
+            for (term, _) in terms {
+                if !evaluate(term, variables)?.into_bool("on each side of '&&'")? {
+                    return Ok(Value::Bool(false));
+                }
+            }
+            Value::Bool(true)
+        }
+        Expr::Any(terms) => {
+            for term in terms {
+                if evaluate(term, variables)?.into_bool("on each side of '||'")? {
+                    return Ok(Value::Bool(true));
+                }
+            }
+            Value::Bool(false)
+        }
+    })
+}
+
+/// The value of an expression, for `eval` and `assert`.
+#[derive(Serialize, PartialEq, Debug)]
+pub(crate) struct EvalResult {
+    expression: String,
+    value: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failed: Option<String>,
+}
+
+impl EvalResult {
+    pub(crate) const fn value(&self) -> &Value {
+        &self.value
+    }
+}
+
+impl fmt::Display for EvalResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            Value::List(versions) => {
+                for version in versions {
+                    writeln!(f, "{version}")?;
+                }
+            }
+            value => writeln!(f, "{value}")?,
+        }
+        if let Some(failed) = &self.failed {
+            writeln!(f, "Failed: {failed}")?;
+        }
+        Ok(())
+    }
+}
+
+/// `false` exits non-zero, like `filter-test`; any other value succeeds.
+impl Termination for EvalResult {
+    fn report(self) -> ExitCode {
+        if self.value == Value::Bool(false) {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(source: &str, variables: &Variables) -> Result<Value, ApplicationError> {
+        Ok(Expression::parse(source)?.evaluate(variables)?.value)
+    }
+
+    #[test]
+    fn test_expression_values() {
+        let mut variables = Variables::default();
+        variables.bind("new".to_string(), Value::from_variable("1.4.0"));
+        variables.bind("old".to_string(), Value::from_variable("1.3.2-rc.1+b"));
+        variables.bind(
+            "list".to_string(),
+            Value::List(
+                ["1.0.0", "1.4.0+x", "1.4.0-rc.1"]
+                    .iter()
+                    .map(|s| Version::parse(s).unwrap())
+                    .collect(),
+            ),
+        );
+        let cases = [
+            ("new > old", Value::Bool(true)),
+            (
+                "new > old && new ~ ^1 && new.pre == \"\"",
+                Value::Bool(true),
+            ),
+            (
+                "new ~ \">=1.2, <1.4\" || old ~ ~1.3.2-rc",
+                Value::Bool(true),
+            ),
+            ("new ~ ~1.2", Value::Bool(false)),
+            ("new ~1.2", Value::Bool(false)),
+            (
+                "new ~1.4 && (new) ~1 && max([old]) ~1.3.2-rc",
+                Value::Bool(true),
+            ),
+            ("max(list) == new", Value::Bool(true)),
+            ("min(list, 0.9.0)", Value::from_variable("0.9.0")),
+            ("len(list)", Value::Number(3)),
+            ("old.pre", Value::String("rc.1".to_string())),
+            ("old.build", Value::String("b".to_string())),
+            ("new.minor", Value::Number(4)),
+            ("new.minor >= 4 && !(new.patch != 0)", Value::Bool(true)),
+            ("new == \"1.4.0+other\"", Value::Bool(true)),
+            (
+                "[1.0.0, \"2.0.0\"]",
+                Value::List(vec![Version::new(1, 0, 0), Version::new(2, 0, 0)]),
+            ),
+            ("true && false", Value::Bool(false)),
+            ("\"a\\\"b\" < \"b\"", Value::Bool(true)),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(eval(source, &variables).unwrap(), expected, "{source}");
+        }
+
+        for source in [
+            "",
+            "new >",
+            "len(list) + 1",
+            "new ~ ^",
+            "new > old > 1.0.0",
+            "new.nope",
+            "nope(list)",
+            "new ~ 1",
+            "1 == new.pre",
+            "new && old",
+            "max()",
+            "\"unterminated",
+            "1.2",
+            "undefined_variable_name_for_test",
+        ] {
+            assert!(eval(source, &variables).is_err(), "{source}");
+        }
+    }
+
+    #[test]
+    fn test_expression_failed_term() {
+        let mut variables = Variables::default();
+        variables.bind("new".to_string(), Value::from_variable("1.4.0"));
+        let result = Expression::parse("new ~ ^1 &&  new.major > 1 && new > 2.0.0")
+            .unwrap()
+            .evaluate(&variables)
+            .unwrap();
+        assert_eq!(result.value, Value::Bool(false));
+        assert_eq!(result.failed.as_deref(), Some("new.major > 1"));
+        assert_eq!(result.to_string(), "false\nFailed: new.major > 1\n");
+    }
+}
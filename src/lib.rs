@@ -18,9 +18,14 @@
 //! supported library API.
 
 mod cli;
+mod commits;
+mod expr;
 mod misc;
+mod policy;
 mod regex;
 mod results;
+mod spill;
+mod stamp;
 
 use std::error::Error;
 use std::process::{ExitCode, Termination};
@@ -31,6 +36,12 @@ pub fn run() -> Result<ExitCode, Box<dyn Error>> {
     cli::run().map(|outcome| outcome.report())
 }
 
+/// The exit status for an error returned by [run].
+#[doc(hidden)]
+pub fn error_exit_code(err: &(dyn Error + 'static)) -> ExitCode {
+    misc::error_exit_code(err)
+}
+
 /// Hidden exports for integration tests only.
 #[doc(hidden)]
 pub mod test_support {
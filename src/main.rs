@@ -20,7 +20,7 @@ fn main() -> ExitCode {
         Ok(code) => code,
         Err(e) => {
             eprintln!("Error: {e:?}");
-            ExitCode::FAILURE
+            sem_tool::error_exit_code(&*e)
         }
     }
 }
@@ -17,13 +17,21 @@
 use clap::ValueEnum;
 use core::fmt;
 use serde::Serialize;
+use std::io::{self, BufWriter, Write};
 use std::process::{ExitCode, Termination};
 use thiserror::Error;
 
+use crate::expr::EvalResult;
+use crate::policy::LintResult;
 use crate::results::{
-    BoundaryVersionResult, ComparisonStatement, FilterTestResult, FlatVersionsList, GenerateResult,
-    OrderedVersionMap, SelectResult, ValidateResult, VersionExplanation, VersionMutationResult,
+    BoundaryVersionResult, ComparisonMatrix, ComparisonStatement, DiffResult, DistanceResult,
+    ExpandResult, FilterTestResult, FlatVersionsList, GapsResult, GenerateResult, GuardResult,
+    KeyedLinesList, NextVersionResult, OrderedVersionMap, RulesSortResult, SelectResult,
+    SortCheckResult, UniqResult, ValidateResult, VersionExplanation, VersionMutationResult,
+    VersionedFilesResult,
 };
+use crate::spill::SpilledVersionsList;
+use crate::stamp::StampResult;
 
 #[derive(Error, Debug)]
 pub(crate) enum ApplicationError {
@@ -40,6 +48,27 @@ pub(crate) enum ApplicationError {
     FailedRequirementError { err: String },
 }
 
+/// An error that exits with its own status rather than 1.
+#[derive(Error)]
+#[error("{err}")]
+pub(crate) struct ExitStatusError {
+    pub(crate) status: u8,
+    pub(crate) err: Box<dyn std::error::Error>,
+}
+
+/// Shown as the error it wraps, so `main` reports it the same way.
+impl fmt::Debug for ExitStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.err, f)
+    }
+}
+
+/// The exit status for an error that ends the process: its own, or 1.
+pub(crate) fn error_exit_code(err: &(dyn std::error::Error + 'static)) -> ExitCode {
+    err.downcast_ref::<ExitStatusError>()
+        .map_or(ExitCode::FAILURE, |err| ExitCode::from(err.status))
+}
+
 #[derive(ValueEnum, Clone, Debug)]
 pub(crate) enum OutputFormat {
     Text,
@@ -102,16 +131,32 @@ macro_rules! subcommand_result {
 
 subcommand_result! {
     enum SubcommandResult {
+        VersionExplanation(VersionExplanation),
         ComparisonStatement(ComparisonStatement),
+        ComparisonMatrix(ComparisonMatrix),
         OrderedVersionMap(OrderedVersionMap),
-        VersionExplanation(VersionExplanation),
         FlatVersionsList(FlatVersionsList),
-        GenerateResult(GenerateResult),
+        KeyedLinesList(KeyedLinesList),
+        SortCheckResult(SortCheckResult),
+        SpilledVersionsList(SpilledVersionsList),
+        RulesSortResult(RulesSortResult),
         FilterTestResult(FilterTestResult),
         ValidateResult(ValidateResult),
+        GenerateResult(GenerateResult),
         VersionMutation(VersionMutationResult),
-        SelectResult(SelectResult),
+        NextVersionResult(NextVersionResult),
+        StampResult(StampResult),
         BoundaryVersionResult(BoundaryVersionResult),
+        SelectResult(SelectResult),
+        UniqResult(UniqResult),
+        DiffResult(DiffResult),
+        VersionedFilesResult(VersionedFilesResult),
+        GapsResult(GapsResult),
+        GuardResult(GuardResult),
+        LintResult(LintResult),
+        EvalResult(EvalResult),
+        ExpandResult(ExpandResult),
+        DistanceResult(DistanceResult),
     }
 }
 
@@ -119,36 +164,56 @@ impl Termination for SubcommandResult {
     fn report(self) -> ExitCode {
         match self {
             Self::ComparisonStatement(s) => s.report(),
+            Self::ComparisonMatrix(s) => s.report(),
+            Self::SortCheckResult(s) => s.report(),
+            Self::RulesSortResult(s) => s.report(),
             Self::FilterTestResult(s) => s.report(),
             Self::ValidateResult(s) => s.report(),
+            Self::NextVersionResult(s) => s.report(),
             Self::SelectResult(s) => s.report(),
-            Self::OrderedVersionMap(_)
-            | Self::VersionExplanation(_)
+            Self::DiffResult(s) => s.report(),
+            Self::GapsResult(s) => s.report(),
+            Self::GuardResult(s) => s.report(),
+            Self::LintResult(s) => s.report(),
+            Self::EvalResult(s) => s.report(),
+            Self::ExpandResult(s) => s.report(),
+            Self::DistanceResult(s) => s.report(),
+            Self::VersionExplanation(_)
+            | Self::OrderedVersionMap(_)
             | Self::FlatVersionsList(_)
+            | Self::KeyedLinesList(_)
+            | Self::SpilledVersionsList(_)
             | Self::GenerateResult(_)
             | Self::VersionMutation(_)
-            | Self::BoundaryVersionResult(_) => ExitCode::SUCCESS,
+            | Self::StampResult(_)
+            | Self::BoundaryVersionResult(_)
+            | Self::UniqResult(_)
+            | Self::VersionedFilesResult(_) => ExitCode::SUCCESS,
         }
     }
 }
 
+fn output_error(e: impl fmt::Display) -> ApplicationError {
+    ApplicationError::OutputFormatError { err: e.to_string() }
+}
+
 pub(crate) fn emit(
     result: &SubcommandResult,
     format: OutputFormat,
 ) -> Result<(), ApplicationError> {
+    // Results are written as they are formatted, rather than rendered into a
+    // string first, so large lists stream out.
+    let mut out = BufWriter::new(io::stdout().lock());
     match format {
-        OutputFormat::Text => print!("{result}"),
+        OutputFormat::Text => write!(out, "{result}").map_err(output_error)?,
         OutputFormat::Yaml => {
-            println!("---");
-            let yaml = serde_yaml::to_string(result)
-                .map_err(|e| ApplicationError::OutputFormatError { err: e.to_string() })?;
-            print!("{yaml}");
+            writeln!(out, "---").map_err(output_error)?;
+            serde_yaml::to_writer(&mut out, result).map_err(output_error)?;
         }
         OutputFormat::Json => {
-            let json = serde_json::to_string(result)
-                .map_err(|e| ApplicationError::OutputFormatError { err: e.to_string() })?;
-            println!("{json}");
+            serde_json::to_writer(&mut out, result).map_err(output_error)?;
+            writeln!(out).map_err(output_error)?;
         }
     }
-    Ok(())
+    out.flush().map_err(output_error)
 }
@@ -0,0 +1,404 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+//!
+//! A project's versioning policy, from `.sem-tool.toml`, for `lint`.
+//!
+//! ```toml
+//! [lint]
+//! pre-release-channels = ["alpha", "beta", "rc"]
+//! max-identifiers = 3
+//! build-metadata = "forbidden"
+//! initial-development = false
+//! pre-release-counter = true
+//!
+//! [lint.severity]
+//! build-metadata = "warning"
+//! ```
+//!
+//! Every rule is off unless configured, and is an error unless given another
+//! severity.
+use std::{
+    collections::HashMap,
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    process::{ExitCode, Termination},
+};
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::misc::ApplicationError;
+use crate::results::VersionExplanation;
+
+/// Where `lint` looks for a policy, in the working directory or above it.
+pub(crate) const POLICY_FILE: &str = ".sem-tool.toml";
+
+/// The contents of a policy file.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct Policy {
+    #[serde(default)]
+    lint: LintPolicy,
+}
+
+impl Policy {
+    /// Read `path`, or the nearest [POLICY_FILE] at or above the working
+    /// directory.
+    pub(crate) fn load(path: Option<&Path>) -> Result<Self, ApplicationError> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => Self::find()?,
+        };
+        let contents =
+            fs::read_to_string(&path).map_err(|e| ApplicationError::InvalidArgument {
+                expected: format!("to be able to read from {}", path.display()),
+                found: e.to_string(),
+            })?;
+        toml::from_str(&contents).map_err(|e| ApplicationError::InvalidArgument {
+            expected: format!("a policy in {}", path.display()),
+            found: e.to_string(),
+        })
+    }
+
+    fn find() -> Result<PathBuf, ApplicationError> {
+        let cwd = env::current_dir()
+            .map_err(|e| ApplicationError::FailedRequirementError { err: e.to_string() })?;
+        cwd.ancestors()
+            .map(|dir| dir.join(POLICY_FILE))
+            .find(|path| path.is_file())
+            .ok_or_else(|| ApplicationError::FailedRequirementError {
+                err: format!(
+                    "no {POLICY_FILE} in {} or above it, and no --policy",
+                    cwd.display()
+                ),
+            })
+    }
+}
+
+/// Whether build metadata may, must, or must not be present.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+enum BuildMetadataPolicy {
+    #[default]
+    Allowed,
+    Required,
+    Forbidden,
+}
+
+/// The `[lint]` table of a policy file.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct LintPolicy {
+    /// The first pre-release identifier must be one of these.
+    pre_release_channels: Option<Vec<String>>,
+    /// The most identifiers a pre-release, or build metadata, may have.
+    max_identifiers: Option<usize>,
+    #[serde(default)]
+    build_metadata: BuildMetadataPolicy,
+    /// Whether `0.y.z` versions may still be published (SemVer §4).
+    #[serde(default = "allowed")]
+    initial_development: bool,
+    /// Whether the last pre-release identifier must be a numeric counter.
+    #[serde(default)]
+    pre_release_counter: bool,
+    #[serde(default)]
+    severity: HashMap<LintRule, Severity>,
+}
+
+fn allowed() -> bool {
+    true
+}
+
+impl Default for LintPolicy {
+    fn default() -> Self {
+        Self {
+            pre_release_channels: None,
+            max_identifiers: None,
+            build_metadata: BuildMetadataPolicy::default(),
+            initial_development: allowed(),
+            pre_release_counter: false,
+            severity: HashMap::new(),
+        }
+    }
+}
+
+/// A rule of a [Policy], as named in findings and `[lint.severity]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum LintRule {
+    PreReleaseChannel,
+    MaxIdentifiers,
+    BuildMetadata,
+    InitialDevelopment,
+    PreReleaseCounter,
+}
+
+impl fmt::Display for LintRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintRule::PreReleaseChannel => write!(f, "pre-release-channel"),
+            LintRule::MaxIdentifiers => write!(f, "max-identifiers"),
+            LintRule::BuildMetadata => write!(f, "build-metadata"),
+            LintRule::InitialDevelopment => write!(f, "initial-development"),
+            LintRule::PreReleaseCounter => write!(f, "pre-release-counter"),
+        }
+    }
+}
+
+/// How much a finding matters. Only errors fail `lint`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Severity {
+    #[default]
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A version breaking a rule of the policy.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub(crate) struct LintFinding {
+    version: Version,
+    rule: LintRule,
+    severity: Severity,
+    message: String,
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: {}: {}",
+            self.severity, self.version, self.rule, self.message
+        )
+    }
+}
+
+/// Every way the versions linted break the policy.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct LintResult {
+    passed: bool,
+    findings: Vec<LintFinding>,
+}
+
+impl LintResult {
+    /// Check each of `versions` against `policy`.
+    ///
+    /// A list in publishing order may hold history from before 1.0.0:
+    /// `0.y.z` versions listed before the first stable `1.0.0` or later
+    /// aren't findings of `initial-development`.
+    pub(crate) fn lint(versions: &[Version], policy: &Policy) -> Self {
+        let policy = &policy.lint;
+        let general_availability = versions
+            .iter()
+            .position(|version| version.major > 0 && version.pre.is_empty());
+
+        let mut findings = Vec::new();
+        for (index, version) in versions.iter().enumerate() {
+            let explained = VersionExplanation::from(version);
+            let mut find = |rule, message| {
+                findings.push(LintFinding {
+                    version: version.clone(),
+                    rule,
+                    severity: policy.severity.get(&rule).copied().unwrap_or_default(),
+                    message,
+                })
+            };
+            let pre = explained.prerelease();
+            let build = explained.build_metadata();
+
+            if let (Some(channels), Some(channel)) = (&policy.pre_release_channels, pre.first())
+                && !channels.iter().any(|allowed| allowed == channel.value())
+            {
+                find(
+                    LintRule::PreReleaseChannel,
+                    format!("{} is not one of {}", channel.value(), channels.join(", ")),
+                );
+            }
+
+            if let Some(max) = policy.max_identifiers {
+                for (segment, identifiers) in [("pre-release", pre), ("build metadata", build)] {
+                    if identifiers.len() > max {
+                        find(
+                            LintRule::MaxIdentifiers,
+                            format!(
+                                "{segment} has {} identifiers, more than {max}",
+                                identifiers.len()
+                            ),
+                        );
+                    }
+                }
+            }
+
+            match (policy.build_metadata, build.is_empty()) {
+                (BuildMetadataPolicy::Required, true) => find(
+                    LintRule::BuildMetadata,
+                    "build metadata is required".to_string(),
+                ),
+                (BuildMetadataPolicy::Forbidden, false) => find(
+                    LintRule::BuildMetadata,
+                    "build metadata is forbidden".to_string(),
+                ),
+                _ => {}
+            }
+
+            if !policy.initial_development
+                && explained.major() == 0
+                && general_availability.is_none_or(|ga| index > ga)
+            {
+                find(
+                    LintRule::InitialDevelopment,
+                    "0.y.z is initial development, which is over".to_string(),
+                );
+            }
+
+            if policy.pre_release_counter
+                && let Some(last) = pre.last()
+                && !last.is_numeric()
+            {
+                find(
+                    LintRule::PreReleaseCounter,
+                    format!(
+                        "the last pre-release identifier, {}, is not numeric",
+                        last.value()
+                    ),
+                );
+            }
+        }
+
+        Self {
+            passed: findings
+                .iter()
+                .all(|finding| finding.severity != Severity::Error),
+            findings,
+        }
+    }
+}
+
+impl fmt::Display for LintResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for finding in &self.findings {
+            writeln!(f, "{finding}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Termination for LintResult {
+    fn report(self) -> ExitCode {
+        if self.passed {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: &str = r#"
+        [lint]
+        pre-release-channels = ["alpha", "beta", "rc"]
+        max-identifiers = 2
+        build-metadata = "forbidden"
+        initial-development = false
+        pre-release-counter = true
+
+        [lint.severity]
+        build-metadata = "warning"
+    "#;
+
+    fn findings(policy: &str, versions: &[&str]) -> (bool, Vec<(String, LintRule)>) {
+        let policy: Policy = toml::from_str(policy).unwrap();
+        let versions: Vec<Version> = versions
+            .iter()
+            .map(|s| Version::parse(s).unwrap())
+            .collect();
+        let result = LintResult::lint(&versions, &policy);
+        (
+            result.passed,
+            result
+                .findings
+                .into_iter()
+                .map(|finding| (finding.version.to_string(), finding.rule))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_policy() {
+        assert_eq!(toml::from_str::<Policy>("").unwrap(), Policy::default());
+        assert!(toml::from_str::<Policy>("[lint]\nunknown = 1").is_err());
+        assert!(toml::from_str::<Policy>("[lint]\nbuild-metadata = \"maybe\"").is_err());
+        assert!(toml::from_str::<Policy>("[lint.severity]\nnope = \"error\"").is_err());
+        assert!(toml::from_str::<Policy>("[lint.severity]\nbuild-metadata = \"fatal\"").is_err());
+    }
+
+    #[test]
+    fn test_lint() {
+        let v = |s: &str| s.to_string();
+        assert_eq!(findings("", &["0.1.0-x.y.z+a"]), (true, vec![]));
+        assert_eq!(
+            findings(POLICY, &["1.2.3", "1.3.0-rc.1", "1.3.0-beta.2"]),
+            (true, vec![])
+        );
+        assert_eq!(
+            findings(POLICY, &["1.3.0-nightly.1"]),
+            (
+                false,
+                vec![(v("1.3.0-nightly.1"), LintRule::PreReleaseChannel)]
+            )
+        );
+        assert_eq!(
+            findings(POLICY, &["1.3.0-rc.1.2"]),
+            (false, vec![(v("1.3.0-rc.1.2"), LintRule::MaxIdentifiers)])
+        );
+        assert_eq!(
+            findings(POLICY, &["1.3.0-rc"]),
+            (false, vec![(v("1.3.0-rc"), LintRule::PreReleaseCounter)])
+        );
+        // Only a warning.
+        assert_eq!(
+            findings(POLICY, &["1.3.0+ci"]),
+            (true, vec![(v("1.3.0+ci"), LintRule::BuildMetadata)])
+        );
+        // History before 1.0.0 is fine, but not after it.
+        assert_eq!(
+            findings(POLICY, &["0.1.0", "1.0.0-rc.1", "1.0.0", "0.9.1"]),
+            (false, vec![(v("0.9.1"), LintRule::InitialDevelopment)])
+        );
+        assert_eq!(
+            findings(POLICY, &["0.9.1"]),
+            (false, vec![(v("0.9.1"), LintRule::InitialDevelopment)])
+        );
+        assert_eq!(
+            findings(
+                "[lint]\nbuild-metadata = \"required\"",
+                &["1.0.0", "1.0.0+b"]
+            ),
+            (false, vec![(v("1.0.0"), LintRule::BuildMetadata)])
+        );
+    }
+}
@@ -30,6 +30,13 @@ pub(crate) const ALWAYS_PRERELEASE_REGEX: &str = r"(?-u:(?:((?:0|[1-9]\d*|\d*[a-
 pub(crate) const ALWAYS_BUILD_METADATA_REGEX: &str =
     r"(?-u:(?:([0-9a-zA-Z-]+(?:\.[0-9a-zA-Z-]+)*)))";
 
+/// Regex for a Conventional Commits 1.0.0 header: `type(scope)!: description`.
+pub(crate) const CONVENTIONAL_COMMIT_HEADER_REGEX: &str =
+    r"^([A-Za-z][A-Za-z0-9-]*)(?:\(([^()\r\n]*)\))?(!)?: (\S.*)$";
+
+/// Regex for a Conventional Commits 1.0.0 breaking change footer.
+pub(crate) const CONVENTIONAL_COMMIT_BREAKING_REGEX: &str = r"^BREAKING[ -]CHANGE: ";
+
 /// Generally the "maximum limit on repetitions" allowed for the regex string
 /// generator, to prevent automata making overly useless decisions for the
 /// purposes of this tool.
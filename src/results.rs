@@ -15,19 +15,25 @@
 //!
 //! These are generally the "Results" we're looking for, as types.
 use std::{
+    borrow::Cow,
     cmp::Ordering,
+    collections::{BTreeSet, VecDeque},
     error::Error,
     fmt,
+    path::PathBuf,
     process::{ExitCode, Termination},
+    str::FromStr,
+    sync::Arc,
 };
 
 use clap::ValueEnum;
 use indexmap::IndexMap;
 use rand::prelude::*;
 use regex::Regex;
-use semver::{BuildMetadata, Prerelease, Version, VersionReq};
-use serde::Serialize;
+use semver::{BuildMetadata, Comparator, Op, Prerelease, Version, VersionReq};
+use serde::{Serialize, Serializer, ser::SerializeStruct};
 
+use super::commits::{CommitBumps, ConventionalCommit};
 use super::regex::{SEMVER_REGEX, generate_any_valid_semver, generate_u64_safe_semver};
 
 macro_rules! impl_success_termination {
@@ -185,7 +191,7 @@ impl SelectResult {
         })
     }
 
-    fn extract_from_version(v: &Version, component: SemverComponent) -> Option<String> {
+    pub(crate) fn extract_from_version(v: &Version, component: SemverComponent) -> Option<String> {
         match component {
             SemverComponent::Major => Some(v.major.to_string()),
             SemverComponent::Minor => Some(v.minor.to_string()),
@@ -287,6 +293,16 @@ pub(crate) struct PreMetaSegment {
     value: String,
 }
 
+impl PreMetaSegment {
+    pub(crate) fn is_numeric(&self) -> bool {
+        self.kind == SegmentType::Numeric
+    }
+
+    pub(crate) fn value(&self) -> &str {
+        &self.value
+    }
+}
+
 impl fmt::Display for PreMetaSegment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} ({})", self.value, self.kind)
@@ -369,6 +385,22 @@ impl From<&Version> for VersionExplanation {
     }
 }
 
+impl VersionExplanation {
+    pub(crate) fn major(&self) -> u64 {
+        self.major
+    }
+
+    /// The pre-release identifiers, empty for a release.
+    pub(crate) fn prerelease(&self) -> &[PreMetaSegment] {
+        self.prerelease.as_deref().unwrap_or_default()
+    }
+
+    /// The build metadata identifiers, empty without build metadata.
+    pub(crate) fn build_metadata(&self) -> &[PreMetaSegment] {
+        self.build_metadata.as_deref().unwrap_or_default()
+    }
+}
+
 impl fmt::Display for VersionExplanation {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Major: {}", self.major)?;
@@ -391,16 +423,145 @@ impl fmt::Display for VersionExplanation {
     }
 }
 
+/// How a version read from input is printed back out.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum VersionRendering {
+    /// The parsed version, re-rendered by the semver crate.
+    #[default]
+    Canonical,
+    /// The token exactly as it was given (surrounding whitespace removed).
+    Original,
+    /// The parsed version, with its original token, source, and line.
+    Provenance,
+}
+
+/// A version, along with the token it was parsed from and where that token
+/// came from.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct InputVersion {
+    version: Version,
+    /// The token as given, kept only when it differs from how `version`
+    /// renders. Valid versions round-trip through the semver crate, so that
+    /// is only when a `v` prefix was dropped.
+    original: Option<Box<str>>,
+    source: Arc<str>,
+    line: usize,
+    render: VersionRendering,
+}
+
+impl InputVersion {
+    /// Parse a token read from `source`, at 1-based `line` (or argument
+    /// position).
+    ///
+    /// With `allow_v_prefix`, a single leading `v` or `V` is accepted and
+    /// dropped for parsing, but kept in the original token.
+    pub(crate) fn parse(
+        token: &str,
+        source: &Arc<str>,
+        line: usize,
+        allow_v_prefix: bool,
+    ) -> Result<Self, semver::Error> {
+        let original = token.trim();
+        let unprefixed = if allow_v_prefix {
+            original.strip_prefix(['v', 'V']).unwrap_or(original)
+        } else {
+            original
+        };
+
+        Ok(Self {
+            version: Version::parse(unprefixed)?,
+            original: (unprefixed.len() != original.len()).then(|| original.into()),
+            source: Arc::clone(source),
+            line,
+            render: VersionRendering::default(),
+        })
+    }
+
+    pub(crate) fn rendered(self, render: VersionRendering) -> Self {
+        Self { render, ..self }
+    }
+
+    pub(crate) fn version(&self) -> &Version {
+        &self.version
+    }
+
+    pub(crate) fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The token exactly as it was given (surrounding whitespace removed).
+    pub(crate) fn original(&self) -> Cow<'_, str> {
+        match &self.original {
+            Some(original) => Cow::Borrowed(original),
+            None => Cow::Owned(self.version.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for InputVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.render {
+            VersionRendering::Canonical => write!(f, "{}", self.version),
+            VersionRendering::Original => write!(f, "{}", self.original()),
+            VersionRendering::Provenance => {
+                write!(f, "{}\t{}:{}", self.original(), self.source, self.line)
+            }
+        }
+    }
+}
+
+impl Serialize for InputVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.render {
+            VersionRendering::Canonical => self.version.serialize(serializer),
+            VersionRendering::Original => self.original().serialize(serializer),
+            VersionRendering::Provenance => {
+                let mut state = serializer.serialize_struct("InputVersion", 4)?;
+                state.serialize_field("version", &self.version)?;
+                state.serialize_field("original", &self.original())?;
+                state.serialize_field("source", &*self.source)?;
+                state.serialize_field("line", &self.line)?;
+                state.end()
+            }
+        }
+    }
+}
+
 /// A simple list of Versions.
 #[derive(Serialize, PartialEq)]
 pub(crate) struct FlatVersionsList {
-    versions: Vec<Version>,
+    versions: Vec<InputVersion>,
     potentially_ambiguous: bool,
 }
 
+impl FlatVersionsList {
+    /// The same order as a flattened [OrderedVersionMap], without building
+    /// the map.
+    pub(crate) fn new(
+        mut inputs: Vec<InputVersion>,
+        filter: &Option<VersionReq>,
+        lexical_sorting: bool,
+        reverse: bool,
+        stable: bool,
+    ) -> Self {
+        retain_wanted(&mut inputs, filter, stable);
+        let potentially_ambiguous =
+            order_by_precedence(&mut inputs, |i| &i.version, lexical_sorting, reverse);
+
+        Self {
+            versions: inputs,
+            potentially_ambiguous,
+        }
+    }
+
+    pub(crate) fn potentially_ambiguous(&self) -> bool {
+        self.potentially_ambiguous
+    }
+}
+
 impl From<&mut OrderedVersionMap> for FlatVersionsList {
     fn from(value: &mut OrderedVersionMap) -> Self {
-        let mut flat: Vec<Version> = Vec::new();
+        let mut flat: Vec<InputVersion> = Vec::new();
 
         value.inner.iter_mut().for_each(|vv| flat.append(vv.1));
         Self {
@@ -420,78 +581,308 @@ impl fmt::Display for FlatVersionsList {
 #[derive(Serialize)]
 pub(crate) struct OrderedVersionMap {
     #[serde(rename(serialize = "versions"))]
-    inner: IndexMap<Version, Vec<Version>>,
+    inner: IndexMap<Version, Vec<InputVersion>>,
     potentially_ambiguous: bool,
 }
 
 impl OrderedVersionMap {
     pub(crate) fn new(
-        versions: &mut Vec<Version>,
+        versions: Vec<Version>,
         filter: &Option<VersionReq>,
         lexical_sorting: bool,
         reverse: bool,
         stable: bool,
     ) -> Self {
-        if let Some(filter) = filter {
-            versions.retain(|v| filter.matches(v));
+        let source: Arc<str> = Arc::from("input");
+        let inputs = versions
+            .into_iter()
+            .enumerate()
+            .map(|(index, version)| InputVersion {
+                version,
+                original: None,
+                source: Arc::clone(&source),
+                line: index + 1,
+                render: VersionRendering::default(),
+            })
+            .collect();
+
+        Self::from_inputs(inputs, filter, lexical_sorting, reverse, stable)
+    }
+
+    /// Like [OrderedVersionMap::new], but keeping the original tokens and
+    /// where they came from.
+    pub(crate) fn from_inputs(
+        mut inputs: Vec<InputVersion>,
+        filter: &Option<VersionReq>,
+        lexical_sorting: bool,
+        reverse: bool,
+        stable: bool,
+    ) -> Self {
+        retain_wanted(&mut inputs, filter, stable);
+
+        let (ordered_version_map, potentially_ambiguous) =
+            group_by_precedence(inputs, |i| &i.version, lexical_sorting, reverse);
+
+        Self {
+            inner: ordered_version_map,
+            potentially_ambiguous,
         }
+    }
 
-        if stable {
-            versions.retain(|v| v.pre.is_empty());
+    /// Merge inputs that are each already in `sort` order, without sorting
+    /// them again.
+    ///
+    /// Fails on the first input that is not in order (see
+    /// [SortCheckResult::check]).
+    pub(crate) fn merge(
+        inputs: Vec<Vec<InputVersion>>,
+        filter: &Option<VersionReq>,
+        lexical_sorting: bool,
+        reverse: bool,
+        stable: bool,
+    ) -> Result<Self, super::misc::ApplicationError> {
+        for input in &inputs {
+            let check = SortCheckResult::check(input, SortCheck::First, lexical_sorting, reverse);
+            if let Some(disorder) = check.disorders.first() {
+                return Err(super::misc::ApplicationError::FailedRequirementError {
+                    err: format!("merge input is not sorted: {disorder}"),
+                });
+            }
         }
 
-        // Generally sort the input for keys into the IndexMap.
-        // Reverse the ordering, if appropriate.
-        if reverse {
-            versions.sort_by(|a, b| b.cmp(a));
-        } else {
-            versions.sort();
+        let mut queues: Vec<VecDeque<InputVersion>> =
+            inputs.into_iter().map(VecDeque::from).collect();
+        let mut merged = Vec::new();
+        // Few inputs are expected, so a scan of their heads will do. Ties go
+        // to the earliest input.
+        while let Some(index) = queues
+            .iter()
+            .enumerate()
+            .filter_map(|(index, queue)| queue.front().map(|head| (index, head)))
+            .min_by(|(_, a), (_, b)| sort_order(&a.version, &b.version, lexical_sorting, reverse))
+            .map(|(index, _)| index)
+        {
+            merged.extend(queues[index].pop_front());
         }
 
-        // Create our return structure.
-        let mut ordered_version_map: IndexMap<Version, Vec<Version>> = IndexMap::new();
+        retain_wanted(&mut merged, filter, stable);
 
-        // Capture all keys and complete Versions.
-        for version in versions {
-            let key = version_without_build_metadata(version);
-            match ordered_version_map.get_mut(&key) {
-                Some(v) => v.push(version.clone()),
-                None => {
-                    let new_value = vec![version.clone()];
-                    let map_response = ordered_version_map.insert(key, new_value);
-                    if map_response.is_some() {
-                        panic!("should not have gotten a map response for an empty key")
-                    }
-                }
+        let potentially_ambiguous = order_ties(&mut merged, |i| &i.version, lexical_sorting);
+
+        Ok(Self {
+            inner: group_ordered(merged, |i| &i.version),
+            potentially_ambiguous,
+        })
+    }
+
+    pub(crate) fn potentially_ambiguous(&self) -> bool {
+        self.potentially_ambiguous
+    }
+
+    /// The highest precedence present, without build metadata.
+    pub(crate) fn highest(&self) -> Option<&Version> {
+        self.inner.keys().max()
+    }
+}
+
+/// Order items by the version they carry, and group them under that
+/// version's precedence (build metadata ignored, SemVer §10).
+///
+/// This is the ordering behind `sort`, see [order_by_precedence].
+///
+/// Also returns whether any group holds more than one item.
+fn group_by_precedence<T, F>(
+    mut items: Vec<T>,
+    version: F,
+    lexical_sorting: bool,
+    reverse: bool,
+) -> (IndexMap<Version, Vec<T>>, bool)
+where
+    F: Fn(&T) -> &Version,
+{
+    let potentially_ambiguous = order_by_precedence(&mut items, &version, lexical_sorting, reverse);
+    (group_ordered(items, version), potentially_ambiguous)
+}
+
+/// Order items in place by the version they carry.
+///
+/// This is the ordering behind `sort`: precedence (reversed if asked), with
+/// items of equal precedence either lexically ordered, or shuffled to emulate
+/// the undefined ordering the spec leaves them in.
+///
+/// Returns whether any two items share precedence.
+fn order_by_precedence<T, F>(
+    items: &mut [T],
+    version: F,
+    lexical_sorting: bool,
+    reverse: bool,
+) -> bool
+where
+    F: Fn(&T) -> &Version,
+{
+    // Reverse the ordering, if appropriate.
+    if reverse {
+        items.sort_by(|a, b| version(b).cmp(version(a)));
+    } else {
+        items.sort_by(|a, b| version(a).cmp(version(b)));
+    }
+
+    order_ties(items, version, lexical_sorting)
+}
+
+/// For items already in `sort` order, shuffle each run of equal precedence
+/// unless lexically sorting, and return whether there were any.
+fn order_ties<T, F>(items: &mut [T], version: F, lexical_sorting: bool) -> bool
+where
+    F: Fn(&T) -> &Version,
+{
+    let mut rng = rand::rng();
+    let mut potentially_ambiguous = false;
+    for ties in items.chunk_by_mut(|a, b| version(a).cmp_precedence(version(b)).is_eq()) {
+        if ties.len() > 1 {
+            potentially_ambiguous = true;
+            if !lexical_sorting {
+                // reverse is silently ignored in this case.
+                ties.shuffle(&mut rng);
             }
         }
+    }
+    potentially_ambiguous
+}
 
-        let mut potentially_ambiguous = false;
-        // For each key, sort each list of versions in an appropriate order.
-        for (_, v) in ordered_version_map.iter_mut() {
-            if lexical_sorting {
-                if reverse {
-                    v.sort_by(|a, b| b.cmp(a));
-                } else {
-                    v.sort();
-                }
-            } else {
-                v.shuffle(&mut rand::rng());
-                // reverse is silently ignored in this case.
+/// Group items already in `sort` order under their version's precedence.
+fn group_ordered<T, F>(items: Vec<T>, version: F) -> IndexMap<Version, Vec<T>>
+where
+    F: Fn(&T) -> &Version,
+{
+    let mut groups: IndexMap<Version, Vec<T>> = IndexMap::new();
+    for item in items {
+        let key = version_without_build_metadata(version(&item));
+        groups.entry(key).or_default().push(item);
+    }
+    groups
+}
+
+/// Drop inputs that don't match the filter, or aren't stable when asked.
+pub(crate) fn retain_wanted(
+    inputs: &mut Vec<InputVersion>,
+    filter: &Option<VersionReq>,
+    stable: bool,
+) {
+    if let Some(filter) = filter {
+        inputs.retain(|i| filter.matches(&i.version));
+    }
+
+    if stable {
+        inputs.retain(|i| i.version.pre.is_empty());
+    }
+}
+
+/// The order `sort` produces: precedence (build metadata ignored, SemVer
+/// §10), or a total order when lexically sorting, reversed if asked.
+fn sort_order(a: &Version, b: &Version, lexical_sorting: bool, reverse: bool) -> Ordering {
+    let ordering = if lexical_sorting {
+        a.cmp(b)
+    } else {
+        a.cmp_precedence(b)
+    };
+    if reverse {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+/// How many out-of-order versions `sort --check` reports.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SortCheck {
+    /// Stop at the first version out of order.
+    First,
+    /// Report every version out of order.
+    All,
+}
+
+/// A version that sorts before the one preceding it.
+#[derive(Serialize)]
+pub(crate) struct Disorder {
+    source: String,
+    line: usize,
+    previous: InputVersion,
+    version: InputVersion,
+}
+
+impl fmt::Display for Disorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: disorder: {} after {}",
+            self.source, self.line, self.version, self.previous
+        )
+    }
+}
+
+/// Result of `sort --check`.
+#[derive(Serialize)]
+pub(crate) struct SortCheckResult {
+    sorted: bool,
+    disorders: Vec<Disorder>,
+}
+
+impl SortCheckResult {
+    /// Check that inputs are already in the order `sort` would produce.
+    ///
+    /// Versions of equal precedence may appear in any order unless lexically
+    /// sorting, as their order is otherwise undefined.
+    pub(crate) fn check(
+        inputs: &[InputVersion],
+        check: SortCheck,
+        lexical_sorting: bool,
+        reverse: bool,
+    ) -> Self {
+        let mut disorders = Vec::new();
+        for pair in inputs.windows(2) {
+            let (previous, version) = (&pair[0], &pair[1]);
+            if sort_order(
+                &previous.version,
+                &version.version,
+                lexical_sorting,
+                reverse,
+            ) != Ordering::Greater
+            {
+                continue;
             }
-            if v.len() > 1 {
-                potentially_ambiguous = true
+
+            disorders.push(Disorder {
+                source: version.source.to_string(),
+                line: version.line,
+                previous: previous.clone(),
+                version: version.clone(),
+            });
+            if check == SortCheck::First {
+                break;
             }
         }
 
         Self {
-            inner: ordered_version_map,
-            potentially_ambiguous,
+            sorted: disorders.is_empty(),
+            disorders,
         }
     }
+}
 
-    pub(crate) fn potentially_ambiguous(&self) -> bool {
-        self.potentially_ambiguous
+impl fmt::Display for SortCheckResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln_items(&self.disorders, f)
+    }
+}
+
+impl Termination for SortCheckResult {
+    fn report(self) -> ExitCode {
+        if self.sorted {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        }
     }
 }
 
@@ -510,78 +901,1829 @@ impl fmt::Display for OrderedVersionMap {
     }
 }
 
-/// A statement about the comparison about 2 versions
-#[derive(Serialize, PartialEq)]
-pub(crate) struct ComparisonStatement {
-    semantic_ordering: SerializableOrdering,
-    lexical_ordering: SerializableOrdering,
+/// Where the version lives within a line of text, for `sort --key`.
+#[derive(Debug)]
+pub(crate) enum LineKey {
+    /// A 1-based field, split on a separator (or on whitespace when `None`).
+    Field {
+        index: usize,
+        separator: Option<String>,
+    },
+    /// The `version` named capture group if present, otherwise the first
+    /// capture group, otherwise the whole match.
+    Regex(Regex),
 }
 
-impl ComparisonStatement {
-    pub(crate) fn new(a: &Version, b: &Version) -> Self {
-        let a_no_build = version_without_build_metadata(a);
-        let b_no_build = version_without_build_metadata(b);
-
-        Self {
-            semantic_ordering: a_no_build.cmp(&b_no_build).into(),
-            lexical_ordering: a.cmp(b).into(),
+impl LineKey {
+    fn extract<'a>(&self, line: &'a str) -> Option<&'a str> {
+        match self {
+            LineKey::Field { index, separator } => {
+                let position = index.checked_sub(1)?;
+                match separator {
+                    Some(separator) => line.split(separator.as_str()).nth(position),
+                    None => line.split_whitespace().nth(position),
+                }
+            }
+            LineKey::Regex(re) => {
+                let captures = re.captures(line)?;
+                captures
+                    .name("version")
+                    .or_else(|| captures.get(1))
+                    .or_else(|| captures.get(0))
+                    .map(|m| m.as_str())
+            }
         }
     }
 
-    pub(crate) fn semantic_ordering(&self) -> &SerializableOrdering {
-        &self.semantic_ordering
+    /// Build a regex key from a file name template, such as
+    /// `mytool-{version}-*.tar.gz`.
+    ///
+    /// `{version}` must appear exactly once and matches a Semantic Version;
+    /// `*` and `?` are globs, and everything else is literal. The template
+    /// must match the whole name.
+    pub(crate) fn from_template(template: &str) -> Result<Self, super::misc::ApplicationError> {
+        let parts: Vec<&str> = template.split("{version}").collect();
+        if parts.len() != 2 {
+            return Err(super::misc::ApplicationError::InvalidArgument {
+                expected: "a template containing `{version}` exactly once".to_string(),
+                found: template.to_string(),
+            });
+        }
+
+        let glob = |literal: &str| -> String {
+            literal
+                .chars()
+                .map(|c| match c {
+                    '*' => ".*".to_string(),
+                    '?' => ".".to_string(),
+                    c => regex::escape(c.encode_utf8(&mut [0; 4])),
+                })
+                .collect()
+        };
+        let pattern = format!(
+            "^{}(?P<version>{}){}$",
+            glob(parts[0]),
+            &SEMVER_REGEX[1..SEMVER_REGEX.len() - 1],
+            glob(parts[1])
+        );
+
+        Regex::new(&pattern).map(LineKey::Regex).map_err(|e| {
+            super::misc::ApplicationError::InvalidArgument {
+                expected: "a template that compiles to a regex".to_string(),
+                found: e.to_string(),
+            }
+        })
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn lexical_ordering(&self) -> &SerializableOrdering {
-        &self.lexical_ordering
+    /// The version keying a line, if the key selects a valid one.
+    pub(crate) fn version(&self, line: &str) -> Option<Version> {
+        self.extract(line)
+            .and_then(|token| Version::parse(token.trim()).ok())
     }
 }
 
+/// Where `sort --key` places lines without a parsable version.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum UnkeyedLines {
+    /// Before every keyed line, in input order.
+    First,
+    /// After every keyed line, in input order.
+    Last,
+    /// Leave them out of the output.
+    Drop,
+}
+
+/// Whole lines of text, ordered by the version embedded in each.
 #[derive(Serialize, PartialEq)]
-pub(crate) struct GenerateResult {
-    #[serde(rename(serialize = "versions"))]
-    inner: Vec<String>,
+pub(crate) struct KeyedLinesList {
+    lines: Vec<String>,
+    potentially_ambiguous: bool,
 }
 
-impl GenerateResult {
-    pub(crate) fn new(small: bool, count: usize) -> Self {
-        let inner = if small {
-            generate_u64_safe_semver(count)
-        } else {
-            generate_any_valid_semver(count)
+impl KeyedLinesList {
+    pub(crate) fn new(
+        lines: Vec<String>,
+        key: &LineKey,
+        unkeyed: UnkeyedLines,
+        filter: &Option<VersionReq>,
+        lexical_sorting: bool,
+        reverse: bool,
+        stable: bool,
+    ) -> Self {
+        let mut keyed: Vec<(Version, String)> = Vec::new();
+        let mut unkeyed_lines: Vec<String> = Vec::new();
+
+        for line in lines {
+            match key.version(&line) {
+                Some(version) => keyed.push((version, line)),
+                None => unkeyed_lines.push(line),
+            }
+        }
+
+        if let Some(filter) = filter {
+            keyed.retain(|(v, _)| filter.matches(v));
+        }
+
+        if stable {
+            keyed.retain(|(v, _)| v.pre.is_empty());
+        }
+
+        let (groups, potentially_ambiguous) =
+            group_by_precedence(keyed, |(v, _)| v, lexical_sorting, reverse);
+        let sorted = groups.into_values().flatten().map(|(_, line)| line);
+
+        let lines = match unkeyed {
+            UnkeyedLines::First => unkeyed_lines.into_iter().chain(sorted).collect(),
+            UnkeyedLines::Last => sorted.chain(unkeyed_lines).collect(),
+            UnkeyedLines::Drop => sorted.collect(),
         };
-        GenerateResult { inner }
+
+        Self {
+            lines,
+            potentially_ambiguous,
+        }
     }
 
-    #[cfg(test)]
-    pub(crate) fn into_inner(self) -> Vec<String> {
-        self.inner
+    pub(crate) fn potentially_ambiguous(&self) -> bool {
+        self.potentially_ambiguous
     }
 }
 
-impl fmt::Display for GenerateResult {
+impl fmt::Display for KeyedLinesList {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln_items(&self.inner, f)
+        writeln_items(&self.lines, f)
     }
 }
 
-#[derive(Serialize, PartialEq)]
-pub(crate) struct VersionMutationResult {
-    pub(crate) mutated_version: Version,
-}
-
-/// Which end of the precedence-ordered version groups to select.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub(crate) enum BoundaryKind {
-    Min,
+/// Which files `files` emits.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FilesPick {
+    /// Files carrying the maximum precedence version.
+    Max,
+    /// Files carrying the minimum precedence version.
+    Min,
+    /// Every matching file, in `sort` order.
+    All,
+}
+
+/// A file, and the version found in its name.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct VersionedFile {
+    path: String,
+    version: Version,
+}
+
+/// Files selected by the version embedded in their names.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct VersionedFilesResult {
+    files: Vec<VersionedFile>,
+    potentially_ambiguous: bool,
+    lexical_tiebreak_used: bool,
+    stable_filter_applied: bool,
+}
+
+impl VersionedFilesResult {
+    /// Key each path by its file name, then order or pick a boundary as
+    /// `sort`, `max` and `min` do.
+    ///
+    /// Several files carrying the very same version (e.g. one per platform)
+    /// are one release, and are emitted together. Files at the same
+    /// precedence with differing build metadata are ambiguous, as for `max`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        paths: Vec<PathBuf>,
+        key: &LineKey,
+        pick: FilesPick,
+        filter: &Option<VersionReq>,
+        lexical_sorting: bool,
+        reverse: bool,
+        stable: bool,
+        allow_ambiguous: bool,
+    ) -> Result<Self, super::misc::ApplicationError> {
+        let mut keyed: Vec<(Version, PathBuf)> = paths
+            .into_iter()
+            .filter_map(|path| {
+                let version = key.version(path.file_name()?.to_str()?)?;
+                Some((version, path))
+            })
+            .collect();
+
+        if let Some(filter) = filter {
+            keyed.retain(|(v, _)| filter.matches(v));
+        }
+
+        if stable {
+            keyed.retain(|(v, _)| v.pre.is_empty());
+        }
+
+        let (groups, _) = group_by_precedence(keyed, |(v, _)| v, lexical_sorting, reverse);
+
+        let distinct = |group: &[(Version, PathBuf)]| -> usize {
+            group.iter().map(|(v, _)| v).collect::<BTreeSet<_>>().len()
+        };
+
+        let (selected, potentially_ambiguous, lexical_tiebreak_used) = match pick {
+            FilesPick::All => {
+                let potentially_ambiguous = groups.values().any(|g| distinct(g) > 1);
+                (
+                    groups.into_values().flatten().collect(),
+                    potentially_ambiguous,
+                    false,
+                )
+            }
+            FilesPick::Max | FilesPick::Min => {
+                let boundary = match pick {
+                    FilesPick::Max => groups.into_iter().max_by(|a, b| a.0.cmp(&b.0)),
+                    _ => groups.into_iter().min_by(|a, b| a.0.cmp(&b.0)),
+                };
+                let Some((_, group)) = boundary else {
+                    return Err(super::misc::ApplicationError::FailedRequirementError {
+                        err: "no versions remaining after filters".to_string(),
+                    });
+                };
+
+                let potentially_ambiguous = distinct(&group) > 1;
+                if potentially_ambiguous && !allow_ambiguous && !lexical_sorting {
+                    return Err(super::misc::ApplicationError::FailedRequirementError {
+                        err: "ambiguous boundary (same precedence, differing build metadata)"
+                            .to_string(),
+                    });
+                }
+
+                if potentially_ambiguous && !allow_ambiguous {
+                    let versions = group.iter().map(|(v, _)| v);
+                    let picked = match pick {
+                        FilesPick::Max => versions.max(),
+                        _ => versions.min(),
+                    }
+                    .expect("non-empty group")
+                    .clone();
+                    let group = group.into_iter().filter(|(v, _)| *v == picked).collect();
+                    (group, true, true)
+                } else {
+                    (group, potentially_ambiguous, false)
+                }
+            }
+        };
+
+        Ok(Self {
+            files: selected
+                .into_iter()
+                .map(|(version, path)| VersionedFile {
+                    path: path.display().to_string(),
+                    version,
+                })
+                .collect(),
+            potentially_ambiguous,
+            lexical_tiebreak_used,
+            stable_filter_applied: stable,
+        })
+    }
+}
+
+impl fmt::Display for VersionedFilesResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for file in &self.files {
+            writeln!(f, "{}", file.path)?;
+        }
+        Ok(())
+    }
+}
+
+/// How `uniq` decides two versions are duplicates of each other.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum UniqBy {
+    /// Identical strings, including build metadata.
+    Exact,
+    /// Identical precedence (build metadata ignored, SemVer §10).
+    Precedence,
+    /// Same MAJOR.MINOR release line.
+    Minor,
+    /// Same MAJOR release line.
+    Major,
+    /// Same Cargo caret-compatible line (`^1.2.3`, `^0.2.3`, `^0.0.3`).
+    Compat,
+}
+
+impl UniqBy {
+    /// The grouping key for a version.
+    ///
+    /// Every key is itself a [Version], so the `precedence` key is exactly
+    /// the one [OrderedVersionMap] groups by.
+    fn key(&self, version: &Version) -> Version {
+        match self {
+            UniqBy::Exact => version.clone(),
+            UniqBy::Precedence => version_without_build_metadata(version),
+            UniqBy::Minor => Version::new(version.major, version.minor, 0),
+            UniqBy::Major => Version::new(version.major, 0, 0),
+            UniqBy::Compat => match (version.major, version.minor) {
+                (0, 0) => Version::new(0, 0, version.patch),
+                (0, minor) => Version::new(0, minor, 0),
+                (major, _) => Version::new(major, 0, 0),
+            },
+        }
+    }
+}
+
+/// Which member of a duplicate group `uniq` emits.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum UniqKeep {
+    /// The first occurrence in input order.
+    First,
+    /// The last occurrence in input order.
+    Last,
+    /// The lowest precedence member (earliest occurrence wins ties).
+    Min,
+    /// The highest precedence member (earliest occurrence wins ties).
+    Max,
+}
+
+/// A single representative emitted by `uniq`.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct UniqEntry {
+    version: Version,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    count: Option<usize>,
+}
+
+/// A deduplicated list of versions, in order of first appearance.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct UniqResult {
+    by: UniqBy,
+    versions: Vec<UniqEntry>,
+}
+
+impl UniqResult {
+    pub(crate) fn uniq(versions: &[Version], by: UniqBy, keep: UniqKeep, count: bool) -> Self {
+        let mut groups: IndexMap<Version, (Version, usize)> = IndexMap::new();
+
+        for version in versions {
+            let key = by.key(version);
+            match groups.get_mut(&key) {
+                Some((representative, seen)) => {
+                    *seen += 1;
+                    let precedence = version_without_build_metadata(version)
+                        .cmp(&version_without_build_metadata(representative));
+                    let replace = match keep {
+                        UniqKeep::First => false,
+                        UniqKeep::Last => true,
+                        UniqKeep::Min => precedence == Ordering::Less,
+                        UniqKeep::Max => precedence == Ordering::Greater,
+                    };
+                    if replace {
+                        *representative = version.clone();
+                    }
+                }
+                None => {
+                    groups.insert(key, (version.clone(), 1));
+                }
+            }
+        }
+
+        Self {
+            by,
+            versions: groups
+                .into_values()
+                .map(|(version, seen)| UniqEntry {
+                    version,
+                    count: count.then_some(seen),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for UniqResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.versions {
+            match entry.count {
+                // Mirrors the column layout of `uniq -c`.
+                Some(count) => writeln!(f, "{count:>7} {}", entry.version)?,
+                None => writeln!(f, "{}", entry.version)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Which component of a release sequence a [Gap] was found in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum GapKind {
+    Major,
+    Minor,
+    Patch,
+    PreRelease,
+}
+
+impl fmt::Display for GapKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GapKind::Major => write!(f, "major"),
+            GapKind::Minor => write!(f, "minor"),
+            GapKind::Patch => write!(f, "patch"),
+            GapKind::PreRelease => write!(f, "pre-release"),
+        }
+    }
+}
+
+/// A run of missing versions between two neighbouring versions of a list.
+///
+/// Missing major and minor lines are named by their first release
+/// (`X.0.0`, `X.Y.0`).
+#[derive(Serialize, PartialEq)]
+pub(crate) struct Gap {
+    kind: GapKind,
+    after: Version,
+    before: Version,
+    first_missing: Version,
+    last_missing: Version,
+    missing_count: u64,
+}
+
+impl fmt::Display for Gap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} -> {}: missing ",
+            self.kind, self.after, self.before
+        )?;
+        if self.missing_count == 1 {
+            write!(f, "{}", self.first_missing)
+        } else {
+            write!(
+                f,
+                "{} .. {} ({})",
+                self.first_missing, self.last_missing, self.missing_count
+            )
+        }
+    }
+}
+
+/// An inclusive range of releases, `A..B`, for `expand`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct VersionRange {
+    start: Version,
+    end: Version,
+}
+
+impl FromStr for VersionRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once("..")
+            .ok_or_else(|| format!("expected a range like 1.2.0..1.4.3, found {s}"))?;
+        let bound = |bound: &str| {
+            let version = Version::parse(bound).map_err(|e| format!("{bound}: {e}"))?;
+            if !version.pre.is_empty() || !version.build.is_empty() {
+                return Err(format!("{bound}: bounds are MAJOR.MINOR.PATCH only"));
+            }
+            Ok(version)
+        };
+        let (start, end) = (bound(start)?, bound(end)?);
+        if start > end {
+            return Err(format!("{start} is after {end}"));
+        }
+        Ok(Self { start, end })
+    }
+}
+
+/// The highest minor of a major line (`1=4`), or the highest patch of a
+/// minor line (`1.4=3`), for `expand`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct LineLimit {
+    major: u64,
+    minor: Option<u64>,
+    max: u64,
+}
+
+impl FromStr for LineLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("expected MAJOR=MAX or MAJOR.MINOR=MAX, found {s}");
+        let (line, max) = s.split_once('=').ok_or_else(invalid)?;
+        let number = |n: &str| n.parse::<u64>().map_err(|_| invalid());
+        let (major, minor) = match line.split_once('.') {
+            Some((major, minor)) => (number(major)?, Some(number(minor)?)),
+            None => (number(line)?, None),
+        };
+        Ok(Self {
+            major,
+            minor,
+            max: number(max)?,
+        })
+    }
+}
+
+/// How far apart the versions `expand` generates are.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ExpandStep {
+    /// The first version of each minor line.
+    Minor,
+    /// Every patch of each minor line.
+    Patch,
+}
+
+/// The most versions `expand` will enumerate, before `--filter`.
+pub(crate) const MAX_EXPANDED_VERSIONS: u64 = 10_000_000;
+
+/// Versions enumerated by `expand`, in ascending order.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct ExpandResult {
+    versions: Vec<Version>,
+}
+
+impl ExpandResult {
+    /// Every release in `range` at `step`.
+    ///
+    /// Where a line ends is taken from `limits` first, then from the highest
+    /// version of that line in `releases`.
+    pub(crate) fn expand(
+        range: &VersionRange,
+        step: ExpandStep,
+        limits: &[LineLimit],
+        releases: Option<&OrderedVersionMap>,
+        filter: &Option<VersionReq>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let limit = |major: u64, minor: Option<u64>| -> Result<u64, Box<dyn Error>> {
+            let line = match minor {
+                Some(minor) => format!("{major}.{minor}"),
+                None => major.to_string(),
+            };
+            limits
+                .iter()
+                .rev()
+                .find(|limit| limit.major == major && limit.minor == minor)
+                .map(|limit| limit.max)
+                .or_else(|| {
+                    releases?
+                        .inner
+                        .keys()
+                        .filter(|release| {
+                            release.major == major
+                                && minor.is_none_or(|minor| release.minor == minor)
+                        })
+                        .map(|release| match minor {
+                            Some(_) => release.patch,
+                            None => release.minor,
+                        })
+                        .max()
+                })
+                .ok_or_else(|| {
+                    format!(
+                        "no upper limit known for the {line} line, give --limit {line}=MAX or --releases"
+                    )
+                    .into()
+                })
+        };
+
+        let (start, end) = (&range.start, &range.end);
+        let mut versions = Vec::new();
+        for major in start.major..=end.major {
+            let first_minor = if major == start.major { start.minor } else { 0 };
+            let last_minor = if major == end.major {
+                end.minor
+            } else {
+                limit(major, None)?
+            };
+            for minor in first_minor..=last_minor {
+                let first_patch = if (major, minor) == (start.major, start.minor) {
+                    start.patch
+                } else {
+                    0
+                };
+                let last_patch = match step {
+                    ExpandStep::Minor => first_patch,
+                    ExpandStep::Patch if (major, minor) == (end.major, end.minor) => end.patch,
+                    ExpandStep::Patch => limit(major, Some(minor))?,
+                };
+                if first_patch <= last_patch
+                    && (last_patch - first_patch)
+                        .saturating_add(1)
+                        .saturating_add(versions.len() as u64)
+                        > MAX_EXPANDED_VERSIONS
+                {
+                    return Err(format!(
+                        "{start}..{end} expands to more than {MAX_EXPANDED_VERSIONS} versions, narrow the range or the limits"
+                    )
+                    .into());
+                }
+                versions.extend(
+                    (first_patch..=last_patch).map(|patch| Version::new(major, minor, patch)),
+                );
+            }
+        }
+
+        if let Some(filter) = filter {
+            versions.retain(|version| filter.matches(version));
+        }
+        Ok(Self { versions })
+    }
+
+    /// Every version in `releases`, in order.
+    pub(crate) fn published(releases: &OrderedVersionMap) -> Self {
+        Self {
+            versions: releases
+                .inner
+                .values()
+                .flatten()
+                .map(|input| input.version.clone())
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for ExpandResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln_items(&self.versions, f)
+    }
+}
+
+/// Where `from` stands relative to `to`, by precedence.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum DistanceDirection {
+    Behind,
+    Level,
+    Ahead,
+}
+
+/// How far apart two versions are, for `distance`.
+///
+/// Component deltas count the way people say it: the most significant
+/// differing component is the difference between the two, and each less
+/// significant one is counted from zero on the newer line. `1.2.3` is
+/// "3 minors and 2 patches behind" `1.5.2`, and "1 major behind" `2.0.0`.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct DistanceResult {
+    from: Version,
+    to: Version,
+    direction: DistanceDirection,
+    diff: VersionDiff,
+    major: u64,
+    minor: u64,
+    patch: u64,
+    /// Published versions after the older of the two, up to and including
+    /// the newer, when a release list is given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    releases_between: Option<usize>,
+}
+
+impl DistanceResult {
+    pub(crate) fn distance(
+        from: &Version,
+        to: &Version,
+        releases: Option<&OrderedVersionMap>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let direction = match from.cmp_precedence(to) {
+            Ordering::Less => DistanceDirection::Behind,
+            Ordering::Equal => DistanceDirection::Level,
+            Ordering::Greater => DistanceDirection::Ahead,
+        };
+        let (older, newer) = match direction {
+            DistanceDirection::Ahead => (to, from),
+            _ => (from, to),
+        };
+
+        let delta = |name: &str, newer: u64, older: u64| {
+            newer
+                .checked_sub(older)
+                .ok_or_else(|| format!("{name} distance ({newer} - {older}) underflows u64"))
+        };
+        let (major, minor, patch) = if older.major != newer.major {
+            (
+                delta("major", newer.major, older.major)?,
+                newer.minor,
+                newer.patch,
+            )
+        } else if older.minor != newer.minor {
+            (0, delta("minor", newer.minor, older.minor)?, newer.patch)
+        } else {
+            (0, 0, delta("patch", newer.patch, older.patch)?)
+        };
+
+        let releases_between = releases.map(|releases| {
+            releases
+                .inner
+                .keys()
+                .filter(|release| {
+                    older.cmp_precedence(release).is_lt() && release.cmp_precedence(newer).is_le()
+                })
+                .count()
+        });
+
+        Ok(Self {
+            from: from.clone(),
+            to: to.clone(),
+            direction,
+            diff: VersionDiff::between(older, newer),
+            major,
+            minor,
+            patch,
+            releases_between,
+        })
+    }
+}
+
+impl fmt::Display for DistanceResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let relation = match self.direction {
+            DistanceDirection::Behind => "behind",
+            DistanceDirection::Level => "level with",
+            DistanceDirection::Ahead => "ahead of",
+        };
+        writeln!(
+            f,
+            "{} is {relation} {} by {} major, {} minor, {} patch",
+            self.from, self.to, self.major, self.minor, self.patch
+        )?;
+        writeln!(f, "Differs in: {}", self.diff)?;
+        if let Some(releases_between) = self.releases_between {
+            writeln!(f, "Published releases between: {releases_between}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Missing versions within the release lines of a list.
+///
+/// Only gaps between neighbouring versions are reported: a line that starts
+/// above `.0`, or a list that starts above `0.0.0`, is not a gap.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct GapsResult {
+    gaps: Vec<Gap>,
+    #[serde(skip)]
+    fail_if_found: bool,
+}
+
+impl GapsResult {
+    pub(crate) fn gaps(map: &OrderedVersionMap, fail_if_found: bool) -> Self {
+        // The map keys are already in ascending precedence, so every release
+        // triple (and its pre-releases) is a contiguous run.
+        let mut triples: Vec<Vec<&Version>> = Vec::new();
+        for key in map.inner.keys() {
+            match triples.last_mut() {
+                Some(run)
+                    if (run[0].major, run[0].minor, run[0].patch)
+                        == (key.major, key.minor, key.patch) =>
+                {
+                    run.push(key)
+                }
+                _ => triples.push(vec![key]),
+            }
+        }
+
+        let mut gaps = Vec::new();
+        for (i, run) in triples.iter().enumerate() {
+            gaps.extend(Self::pre_release_gaps(run));
+
+            let Some(next) = triples.get(i + 1) else {
+                continue;
+            };
+            let (after, before) = (*run.last().expect("non-empty run"), next[0]);
+
+            let (kind, from, to) = if before.major != after.major {
+                (GapKind::Major, after.major, before.major)
+            } else if before.minor != after.minor {
+                (GapKind::Minor, after.minor, before.minor)
+            } else {
+                (GapKind::Patch, after.patch, before.patch)
+            };
+            if to - from < 2 {
+                continue;
+            }
+
+            let missing = |n: u64| match kind {
+                GapKind::Major => Version::new(n, 0, 0),
+                GapKind::Minor => Version::new(after.major, n, 0),
+                _ => Version::new(after.major, after.minor, n),
+            };
+            gaps.push(Gap {
+                kind,
+                after: after.clone(),
+                before: before.clone(),
+                first_missing: missing(from + 1),
+                last_missing: missing(to - 1),
+                missing_count: to - from - 1,
+            });
+        }
+
+        Self {
+            gaps,
+            fail_if_found,
+        }
+    }
+
+    /// Gaps between numeric pre-release counters sharing the same channel
+    /// (every identifier but the last), e.g. `rc.1` to `rc.3`.
+    fn pre_release_gaps(run: &[&Version]) -> Vec<Gap> {
+        let mut channels: IndexMap<String, Vec<(u64, &Version)>> = IndexMap::new();
+        for version in run.iter().filter(|v| !v.pre.is_empty()) {
+            let pre = version.pre.as_str();
+            let (channel, last) = match pre.rsplit_once('.') {
+                Some((channel, last)) => (channel, last),
+                None => ("", pre),
+            };
+            // Counters beyond u64 are valid, but skipped.
+            let segment = PreMetaSegment::from(last);
+            if let (SegmentType::Numeric, Ok(n)) = (segment.kind, segment.value.parse::<u64>()) {
+                channels
+                    .entry(channel.to_string())
+                    .or_default()
+                    .push((n, version));
+            }
+        }
+
+        let mut gaps = Vec::new();
+        for (channel, counters) in channels {
+            for pair in counters.windows(2) {
+                let ((from, after), (to, before)) = (pair[0], pair[1]);
+                if to - from < 2 {
+                    continue;
+                }
+                let missing = |n: u64| {
+                    let pre = if channel.is_empty() {
+                        n.to_string()
+                    } else {
+                        format!("{channel}.{n}")
+                    };
+                    Version {
+                        pre: Prerelease::new(&pre).expect("channel of a valid pre-release"),
+                        ..Version::new(after.major, after.minor, after.patch)
+                    }
+                };
+                gaps.push(Gap {
+                    kind: GapKind::PreRelease,
+                    after: after.clone(),
+                    before: before.clone(),
+                    first_missing: missing(from + 1),
+                    last_missing: missing(to - 1),
+                    missing_count: to - from - 1,
+                });
+            }
+        }
+        gaps
+    }
+}
+
+impl fmt::Display for GapsResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln_items(&self.gaps, f)
+    }
+}
+
+impl Termination for GapsResult {
+    fn report(self) -> ExitCode {
+        if self.fail_if_found && !self.gaps.is_empty() {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+/// A rule a release candidate is checked against, by `guard`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum GuardRule {
+    /// Newer than every published version of its `MAJOR.MINOR` line.
+    NewerThanLine,
+    /// Not the precedence of a published version (SemVer §10).
+    UniquePrecedence,
+    /// The release after one that is released (or the first).
+    NoSkippedNumbers,
+    /// Not a pre-release of a published release (SemVer §9).
+    NotAlreadyReleased,
+}
+
+impl fmt::Display for GuardRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuardRule::NewerThanLine => write!(f, "newer-than-line"),
+            GuardRule::UniquePrecedence => write!(f, "unique-precedence"),
+            GuardRule::NoSkippedNumbers => write!(f, "no-skipped-numbers"),
+            GuardRule::NotAlreadyReleased => write!(f, "not-already-released"),
+        }
+    }
+}
+
+/// Whether a candidate passed one [GuardRule], and why.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct GuardCheck {
+    rule: GuardRule,
+    passed: bool,
+    detail: String,
+}
+
+impl fmt::Display for GuardCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.passed { "pass" } else { "FAIL" };
+        write!(f, "{verdict}: {}: {}", self.rule, self.detail)
+    }
+}
+
+/// Whether a release candidate is a valid successor of the published
+/// versions, rule by rule.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct GuardResult {
+    candidate: Version,
+    passed: bool,
+    checks: Vec<GuardCheck>,
+}
+
+impl GuardResult {
+    pub(crate) fn guard(candidate: &Version, published: &OrderedVersionMap) -> Self {
+        let key = version_without_build_metadata(candidate);
+        let triple = (key.major, key.minor, key.patch);
+        let triple_of = |version: &Version| (version.major, version.minor, version.patch);
+        let check = |rule, passed, detail| GuardCheck {
+            rule,
+            passed,
+            detail,
+        };
+
+        let line = format!("{}.{}", key.major, key.minor);
+        let newer_than_line = match published
+            .inner
+            .keys()
+            .filter(|version| (version.major, version.minor) == (key.major, key.minor))
+            .max()
+        {
+            None => check(
+                GuardRule::NewerThanLine,
+                true,
+                format!("nothing in {line} is published"),
+            ),
+            Some(highest) => {
+                let ordering = *ComparisonStatement::new(candidate, highest).semantic_ordering();
+                check(
+                    GuardRule::NewerThanLine,
+                    ordering == SerializableOrdering::Greater,
+                    format!(
+                        "{key} {} {highest}, the highest of {line}",
+                        ordering.symbol()
+                    ),
+                )
+            }
+        };
+
+        let unique_precedence = match published.inner.get(&key) {
+            None => check(
+                GuardRule::UniquePrecedence,
+                true,
+                format!("{key} is not published"),
+            ),
+            Some(inputs) => check(
+                GuardRule::UniquePrecedence,
+                false,
+                format!(
+                    "{candidate} has the precedence of published {}",
+                    inputs
+                        .iter()
+                        .map(|input| input.version.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            ),
+        };
+
+        // The release this one follows is only known down to the first
+        // component that isn't zero: `1.3.0` follows any `1.2.x` release,
+        // but not `1.2.0-rc.1` alone.
+        let (major, minor, patch) = triple;
+        let predecessor = if patch > 0 {
+            Some((major, Some(minor), Some(patch - 1)))
+        } else if minor > 0 {
+            Some((major, Some(minor - 1), None))
+        } else if major > 0 {
+            Some((major - 1, None, None))
+        } else {
+            None
+        };
+        let no_skipped_numbers = match predecessor {
+            _ if published.inner.is_empty() => check(
+                GuardRule::NoSkippedNumbers,
+                true,
+                "nothing is published yet".to_string(),
+            ),
+            _ if published
+                .inner
+                .keys()
+                .any(|version| triple_of(version) == triple) =>
+            {
+                check(
+                    GuardRule::NoSkippedNumbers,
+                    true,
+                    format!("{major}.{minor}.{patch} is already under way"),
+                )
+            }
+            None => check(
+                GuardRule::NoSkippedNumbers,
+                true,
+                "0.0.0 is the first release".to_string(),
+            ),
+            Some((major, minor, patch)) => {
+                let found = published.inner.keys().any(|version| {
+                    version.pre.is_empty()
+                        && version.major == major
+                        && minor.is_none_or(|minor| version.minor == minor)
+                        && patch.is_none_or(|patch| version.patch == patch)
+                });
+                let before = match (minor, patch) {
+                    (Some(minor), Some(patch)) => format!("{major}.{minor}.{patch}"),
+                    (Some(minor), None) => format!("{major}.{minor}.x"),
+                    _ => format!("{major}.x"),
+                };
+                check(
+                    GuardRule::NoSkippedNumbers,
+                    found,
+                    if found {
+                        format!("follows {before}")
+                    } else {
+                        format!("{before} is not released")
+                    },
+                )
+            }
+        };
+
+        let release = Version::new(major, minor, patch);
+        let not_already_released = if key.pre.is_empty() {
+            check(
+                GuardRule::NotAlreadyReleased,
+                true,
+                format!("{key} is not a pre-release"),
+            )
+        } else if published.inner.contains_key(&release) {
+            check(
+                GuardRule::NotAlreadyReleased,
+                false,
+                format!("{release} is already released"),
+            )
+        } else {
+            check(
+                GuardRule::NotAlreadyReleased,
+                true,
+                format!("{release} is not released yet"),
+            )
+        };
+
+        let checks = vec![
+            newer_than_line,
+            unique_precedence,
+            no_skipped_numbers,
+            not_already_released,
+        ];
+        Self {
+            candidate: candidate.clone(),
+            passed: checks.iter().all(|check| check.passed),
+            checks,
+        }
+    }
+}
+
+impl fmt::Display for GuardResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln_items(&self.checks, f)
+    }
+}
+
+impl Termination for GuardResult {
+    fn report(self) -> ExitCode {
+        if self.passed {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// How `diff` decides a version is present on both sides.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum DiffBy {
+    /// Identical versions, including build metadata.
+    Exact,
+    /// Identical precedence (build metadata ignored, SemVer §10).
+    Precedence,
+}
+
+impl DiffBy {
+    fn key(&self, version: &Version) -> Version {
+        match self {
+            DiffBy::Exact => version.clone(),
+            DiffBy::Precedence => version_without_build_metadata(version),
+        }
+    }
+}
+
+/// Set operations between two version lists.
+///
+/// Each side is reduced to a set of keys under [DiffBy], so with
+/// `precedence` the reported versions carry no build metadata.
+///
+/// Sets are ordered by `Version`'s `Ord`, which is precedence, then build
+/// metadata between versions sharing it.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct DiffResult {
+    by: DiffBy,
+    only_left: Vec<Version>,
+    only_right: Vec<Version>,
+    both: Vec<Version>,
+}
+
+impl DiffResult {
+    pub(crate) fn diff(left: &[Version], right: &[Version], by: DiffBy) -> Self {
+        let left: BTreeSet<Version> = left.iter().map(|v| by.key(v)).collect();
+        let right: BTreeSet<Version> = right.iter().map(|v| by.key(v)).collect();
+
+        Self {
+            by,
+            only_left: left.difference(&right).cloned().collect(),
+            only_right: right.difference(&left).cloned().collect(),
+            both: left.intersection(&right).cloned().collect(),
+        }
+    }
+
+    pub(crate) fn differs(&self) -> bool {
+        !self.only_left.is_empty() || !self.only_right.is_empty()
+    }
+}
+
+impl fmt::Display for DiffResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for v in &self.only_left {
+            writeln!(f, "< {v}")?;
+        }
+        for v in &self.only_right {
+            writeln!(f, "> {v}")?;
+        }
+        for v in &self.both {
+            writeln!(f, "= {v}")?;
+        }
+        Ok(())
+    }
+}
+
+impl DiffResult {
+    /// The exit status when the sets can't be compared at all (an unreadable
+    /// or invalid source), so it isn't mistaken for "differ".
+    pub(crate) const TROUBLE: u8 = 2;
+}
+
+/// Like `diff(1)`: 0 when both sides hold the same set, 1 when they differ,
+/// and [DiffResult::TROUBLE] on any error.
+impl Termination for DiffResult {
+    fn report(self) -> ExitCode {
+        if self.differs() {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+/// The most significant component two versions differ in, in the spirit of
+/// node-semver's `diff`.
+///
+/// Ordered from least to most significant, so `>=` reads as "at least as
+/// significant as".
+#[derive(ValueEnum, Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum VersionDiff {
+    /// The versions are identical.
+    #[value(skip)]
+    None,
+    Build,
+    Prerelease,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl VersionDiff {
+    /// Components are checked from major down to build metadata, so a
+    /// pre-release of the same release differs in `prerelease`.
+    pub(crate) fn between(a: &Version, b: &Version) -> Self {
+        if a.major != b.major {
+            Self::Major
+        } else if a.minor != b.minor {
+            Self::Minor
+        } else if a.patch != b.patch {
+            Self::Patch
+        } else if a.pre != b.pre {
+            Self::Prerelease
+        } else if a.build != b.build {
+            Self::Build
+        } else {
+            Self::None
+        }
+    }
+}
+
+impl fmt::Display for VersionDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::None => "none",
+            Self::Build => "build",
+            Self::Prerelease => "prerelease",
+            Self::Patch => "patch",
+            Self::Minor => "minor",
+            Self::Major => "major",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// If moving from `a` to `b` is allowed by Cargo's default (caret)
+/// requirement on `a`, i.e. `b` matches `^a`.
+///
+/// Reference: https://doc.rust-lang.org/cargo/reference/specifying-dependencies.html#caret-requirements
+pub(crate) fn cargo_compatible(a: &Version, b: &Version) -> bool {
+    let caret = Comparator {
+        op: Op::Caret,
+        major: a.major,
+        minor: Some(a.minor),
+        patch: Some(a.patch),
+        pre: a.pre.clone(),
+    };
+    caret.matches(b)
+}
+
+/// A rule of SemVer 2.0.0 that takes part in deciding precedence.
+///
+/// Serialized as its section number.
+///
+/// Reference: https://semver.org/#spec-item-11
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SpecRule {
+    #[serde(rename = "10")]
+    BuildMetadataIgnored,
+    #[serde(rename = "11.1")]
+    PrecedenceOrder,
+    #[serde(rename = "11.2")]
+    NumericVersionCore,
+    #[serde(rename = "11.3")]
+    PreReleaseLower,
+    #[serde(rename = "11.4.1")]
+    NumericIdentifiers,
+    #[serde(rename = "11.4.2")]
+    AsciiIdentifiers,
+    #[serde(rename = "11.4.3")]
+    NumericBeforeAscii,
+    #[serde(rename = "11.4.4")]
+    MoreIdentifiers,
+}
+
+impl SpecRule {
+    fn section(&self) -> &'static str {
+        match self {
+            Self::BuildMetadataIgnored => "10",
+            Self::PrecedenceOrder => "11.1",
+            Self::NumericVersionCore => "11.2",
+            Self::PreReleaseLower => "11.3",
+            Self::NumericIdentifiers => "11.4.1",
+            Self::AsciiIdentifiers => "11.4.2",
+            Self::NumericBeforeAscii => "11.4.3",
+            Self::MoreIdentifiers => "11.4.4",
+        }
+    }
+
+    fn summary(&self) -> &'static str {
+        match self {
+            Self::BuildMetadataIgnored => "build metadata is ignored when determining precedence",
+            Self::PrecedenceOrder => {
+                "precedence is decided by major, minor, patch and pre-release, in that order"
+            }
+            Self::NumericVersionCore => "major, minor and patch are compared numerically",
+            Self::PreReleaseLower => {
+                "a pre-release version has lower precedence than the normal version"
+            }
+            Self::NumericIdentifiers => "numeric identifiers are compared numerically",
+            Self::AsciiIdentifiers => {
+                "identifiers with letters or hyphens are compared lexically in ASCII sort order"
+            }
+            Self::NumericBeforeAscii => {
+                "numeric identifiers have lower precedence than non-numeric identifiers"
+            }
+            Self::MoreIdentifiers => {
+                "a larger set of pre-release fields has higher precedence, when all preceding identifiers are equal"
+            }
+        }
+    }
+}
+
+impl fmt::Display for SpecRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "§{} {}", self.section(), self.summary())
+    }
+}
+
+/// One comparison made while deciding precedence.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct TraceStep {
+    rule: SpecRule,
+    compared: String,
+    a: Option<String>,
+    b: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    a_kind: Option<SegmentType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    b_kind: Option<SegmentType>,
+    ordering: SerializableOrdering,
+}
+
+impl TraceStep {
+    fn new(
+        rule: SpecRule,
+        compared: impl Into<String>,
+        a: impl ToString,
+        b: impl ToString,
+        ordering: Ordering,
+    ) -> Self {
+        Self {
+            rule,
+            compared: compared.into(),
+            a: Some(a.to_string()),
+            b: Some(b.to_string()),
+            a_kind: None,
+            b_kind: None,
+            ordering: ordering.into(),
+        }
+    }
+
+    fn identifiers(
+        index: usize,
+        a: Option<PreMetaSegment>,
+        b: Option<PreMetaSegment>,
+    ) -> (Self, SpecRule) {
+        let rule = match (&a, &b) {
+            (Some(a), Some(b)) => match (&a.kind, &b.kind) {
+                (SegmentType::Numeric, SegmentType::Numeric) => SpecRule::NumericIdentifiers,
+                (SegmentType::Ascii, SegmentType::Ascii) => SpecRule::AsciiIdentifiers,
+                _ => SpecRule::NumericBeforeAscii,
+            },
+            _ => SpecRule::MoreIdentifiers,
+        };
+        let ordering = match (&a, &b) {
+            (Some(a), Some(b)) => match (&a.kind, &b.kind) {
+                // Numeric identifiers have no leading zeroes, and no limit on
+                // their size.
+                (SegmentType::Numeric, SegmentType::Numeric) => a
+                    .value
+                    .len()
+                    .cmp(&b.value.len())
+                    .then_with(|| a.value.cmp(&b.value)),
+                (SegmentType::Ascii, SegmentType::Ascii) => a.value.cmp(&b.value),
+                (SegmentType::Numeric, SegmentType::Ascii) => Ordering::Less,
+                (SegmentType::Ascii, SegmentType::Numeric) => Ordering::Greater,
+            },
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        };
+        let step = Self {
+            rule,
+            compared: format!("pre-release identifier {}", index + 1),
+            a_kind: a.as_ref().map(|a| a.kind.clone()),
+            b_kind: b.as_ref().map(|b| b.kind.clone()),
+            a: a.map(|a| a.value),
+            b: b.map(|b| b.value),
+            ordering: ordering.into(),
+        };
+        (step, rule)
+    }
+}
+
+impl fmt::Display for TraceStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = |value: &Option<String>, kind: &Option<SegmentType>| match (value, kind) {
+            (Some(value), Some(kind)) => format!("{value} ({kind})"),
+            (Some(value), None) => value.clone(),
+            (None, _) => "(none)".to_string(),
+        };
+        write!(
+            f,
+            "§{} {}: {} {} {}",
+            self.rule.section(),
+            self.compared,
+            side(&self.a, &self.a_kind),
+            self.ordering.symbol(),
+            side(&self.b, &self.b_kind)
+        )
+    }
+}
+
+/// The steps of SemVer 2.0.0 §11 taken to decide the precedence of `a`
+/// against `b`, ending with the rule that decided it.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct PrecedenceTrace {
+    steps: Vec<TraceStep>,
+    ordering: SerializableOrdering,
+    decided_by: SpecRule,
+}
+
+impl PrecedenceTrace {
+    pub(crate) fn new(a: &Version, b: &Version) -> Self {
+        let mut steps = Vec::new();
+        let mut decide = |step: TraceStep, rule: SpecRule| {
+            let ordering = Ordering::from(&step.ordering);
+            steps.push(step);
+            ordering.is_ne().then_some((ordering, rule))
+        };
+
+        let decided = [
+            ("major", a.major, b.major),
+            ("minor", a.minor, b.minor),
+            ("patch", a.patch, b.patch),
+        ]
+        .into_iter()
+        .find_map(|(compared, a, b)| {
+            decide(
+                TraceStep::new(SpecRule::NumericVersionCore, compared, a, b, a.cmp(&b)),
+                SpecRule::NumericVersionCore,
+            )
+        })
+        .or_else(|| {
+            let (a_pre, b_pre) = (!a.pre.is_empty(), !b.pre.is_empty());
+            decide(
+                TraceStep::new(
+                    SpecRule::PreReleaseLower,
+                    "has pre-release",
+                    a_pre,
+                    b_pre,
+                    b_pre.cmp(&a_pre),
+                ),
+                SpecRule::PreReleaseLower,
+            )
+        })
+        .or_else(|| {
+            if a.pre.is_empty() {
+                return None;
+            }
+            let a_ids: Vec<&str> = a.pre.split('.').collect();
+            let b_ids: Vec<&str> = b.pre.split('.').collect();
+            (0..a_ids.len().max(b_ids.len())).find_map(|index| {
+                let (step, rule) = TraceStep::identifiers(
+                    index,
+                    a_ids.get(index).map(|&id| id.into()),
+                    b_ids.get(index).map(|&id| id.into()),
+                );
+                decide(step, rule)
+            })
+        });
+
+        let (ordering, decided_by) = decided.unwrap_or_else(|| {
+            if a.build != b.build {
+                decide(
+                    TraceStep::new(
+                        SpecRule::BuildMetadataIgnored,
+                        "build metadata",
+                        &a.build,
+                        &b.build,
+                        Ordering::Equal,
+                    ),
+                    SpecRule::BuildMetadataIgnored,
+                );
+                (Ordering::Equal, SpecRule::BuildMetadataIgnored)
+            } else {
+                (Ordering::Equal, SpecRule::PrecedenceOrder)
+            }
+        });
+
+        Self {
+            steps,
+            ordering: ordering.into(),
+            decided_by,
+        }
+    }
+}
+
+impl fmt::Display for PrecedenceTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Trace:")?;
+        for step in &self.steps {
+            writeln!(f, "- {step}")?;
+        }
+        writeln!(f, "Decided by: {}", self.decided_by)
+    }
+}
+
+/// A named scheme for the exit status of `compare`.
+///
+/// The codes of a released scheme never change: a different mapping gets a
+/// new version of the name. The unversioned aliases follow the latest
+/// version of each scheme.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ExitScheme {
+    /// 0 when the requested relation (`--lt`, `--ge`, ...) holds, and 1
+    /// when it doesn't, like `test`.
+    #[value(alias = "test")]
+    TestV1,
+    /// 0 when semantically Equal, 1 when Less, and 2 when Greater.
+    ///
+    /// Errors also exit 1, and usage errors 2, but print nothing to stdout.
+    #[value(alias = "three-way")]
+    ThreeWayV1,
+    /// 0 when both semantically and lexically Equal, otherwise 1XY (see
+    /// `--set-exit-status`).
+    #[value(alias = "dual")]
+    DualV1,
+}
+
+/// A relation of `a` to `b`, by precedence (SemVer §11).
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Relation {
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    Ge,
+    Gt,
+}
+
+impl Relation {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Lt => ordering.is_lt(),
+            Self::Le => ordering.is_le(),
+            Self::Eq => ordering.is_eq(),
+            Self::Ne => ordering.is_ne(),
+            Self::Ge => ordering.is_ge(),
+            Self::Gt => ordering.is_gt(),
+        }
+    }
+}
+
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Lt => "lt",
+            Self::Le => "le",
+            Self::Eq => "eq",
+            Self::Ne => "ne",
+            Self::Ge => "ge",
+            Self::Gt => "gt",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The outcome of testing a [Relation].
+#[derive(Serialize, PartialEq)]
+pub(crate) struct RelationTest {
+    relation: Relation,
+    holds: bool,
+}
+
+/// How `compare` sets its exit status.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum CompareExit {
+    /// [ExitScheme::DualV1].
+    #[default]
+    Dual,
+    /// [ExitScheme::ThreeWayV1].
+    ThreeWay,
+    /// [ExitScheme::TestV1].
+    Test(Relation),
+    /// Failure when the versions differ in at least this component.
+    FailOnDiff(VersionDiff),
+    /// Failure when the versions aren't [cargo_compatible].
+    Incompatible,
+}
+
+/// The precedence rules of an ecosystem, which don't all agree.
+#[derive(ValueEnum, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum PrecedenceRules {
+    /// SemVer 2.0.0 §11: build metadata is ignored.
+    Spec,
+    /// The semver crate's `Ord` for `Version`, as Cargo uses it: build
+    /// metadata breaks ties.
+    Cargo,
+    /// node-semver's `compareBuild` (what its `sort` uses): build metadata
+    /// breaks ties, numeric identifiers are compared as JavaScript numbers.
+    Npm,
+    /// The version text, byte by byte, like a plain `sort`.
+    Lexical,
+    /// Every rule set, side by side.
+    #[serde(skip)]
+    All,
+}
+
+impl PrecedenceRules {
+    const EACH: [Self; 4] = [Self::Spec, Self::Cargo, Self::Npm, Self::Lexical];
+
+    /// The rule sets this stands for.
+    pub(crate) fn each(self) -> Vec<Self> {
+        match self {
+            Self::All => Self::EACH.to_vec(),
+            rules => vec![rules],
+        }
+    }
+
+    pub(crate) fn cmp(self, a: &Version, b: &Version) -> Ordering {
+        match self {
+            Self::Spec => a.cmp_precedence(b),
+            Self::Cargo => a.cmp(b),
+            Self::Npm => (a.major, a.minor, a.patch)
+                .cmp(&(b.major, b.minor, b.patch))
+                .then_with(|| match (a.pre.is_empty(), b.pre.is_empty()) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                    (false, false) => npm_compare_identifiers(&a.pre, &b.pre),
+                })
+                .then_with(|| npm_compare_identifiers(&a.build, &b.build)),
+            Self::Lexical => a.to_string().cmp(&b.to_string()),
+            Self::All => unreachable!("compare under each rule set instead"),
+        }
+    }
+}
+
+impl fmt::Display for PrecedenceRules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Spec => "spec",
+            Self::Cargo => "cargo",
+            Self::Npm => "npm",
+            Self::Lexical => "lexical",
+            Self::All => "all",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// node-semver's identifier loop, from `comparePre` and `compareBuild`.
+///
+/// It returns on the first identifier pair that isn't the same string, even
+/// when `compareIdentifiers` finds them equal (`01` and `1`).
+fn npm_compare_identifiers(a: &str, b: &str) -> Ordering {
+    let mut a_ids = a.split('.').filter(|id| !id.is_empty());
+    let mut b_ids = b.split('.').filter(|id| !id.is_empty());
+    loop {
+        match (a_ids.next(), b_ids.next()) {
+            (None, None) => return Ordering::Equal,
+            (Some(_), None) => return Ordering::Greater,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(a), Some(b)) if a == b => continue,
+            (Some(a), Some(b)) => {
+                let numeric = |id: &str| id.bytes().all(|c| c.is_ascii_digit());
+                return match (numeric(a), numeric(b)) {
+                    // Both become JavaScript numbers, so precision is lost
+                    // past 2^53.
+                    (true, true) => a
+                        .parse::<f64>()
+                        .ok()
+                        .partial_cmp(&b.parse::<f64>().ok())
+                        .unwrap_or(Ordering::Equal),
+                    (true, false) => Ordering::Less,
+                    (false, true) => Ordering::Greater,
+                    (false, false) => a.cmp(b),
+                };
+            }
+        }
+    }
+}
+
+/// The ordering of `a` against `b` under one set of [PrecedenceRules].
+#[derive(Serialize, PartialEq)]
+pub(crate) struct RulesOrdering {
+    rules: PrecedenceRules,
+    ordering: SerializableOrdering,
+}
+
+/// A statement about the comparison about 2 versions
+#[derive(Serialize, PartialEq)]
+pub(crate) struct ComparisonStatement {
+    semantic_ordering: SerializableOrdering,
+    lexical_ordering: SerializableOrdering,
+    diff: VersionDiff,
+    cargo_compatible: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trace: Option<PrecedenceTrace>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    relation: Option<RelationTest>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    rules: Vec<RulesOrdering>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rules_disagree: Option<bool>,
+    #[serde(skip)]
+    exit: CompareExit,
+}
+
+impl ComparisonStatement {
+    pub(crate) fn new(a: &Version, b: &Version) -> Self {
+        let a_no_build = version_without_build_metadata(a);
+        let b_no_build = version_without_build_metadata(b);
+
+        Self {
+            semantic_ordering: a_no_build.cmp(&b_no_build).into(),
+            lexical_ordering: a.cmp(b).into(),
+            diff: VersionDiff::between(a, b),
+            cargo_compatible: cargo_compatible(a, b),
+            trace: None,
+            relation: None,
+            rules: Vec::new(),
+            rules_disagree: None,
+            exit: CompareExit::default(),
+        }
+    }
+
+    /// Include the ordering under each of `rules`, and with more than one,
+    /// whether they disagree.
+    pub(crate) fn under_rules(mut self, a: &Version, b: &Version, rules: PrecedenceRules) -> Self {
+        self.rules = rules
+            .each()
+            .into_iter()
+            .map(|rules| RulesOrdering {
+                rules,
+                ordering: rules.cmp(a, b).into(),
+            })
+            .collect();
+        if self.rules.len() > 1 {
+            self.rules_disagree = Some(
+                self.rules
+                    .iter()
+                    .any(|rules| rules.ordering != self.rules[0].ordering),
+            );
+        }
+        self
+    }
+
+    /// Include the steps taken to decide precedence.
+    pub(crate) fn traced(mut self, a: &Version, b: &Version) -> Self {
+        self.trace = Some(PrecedenceTrace::new(a, b));
+        self
+    }
+
+    /// Which exit status to report, testing the relation if there is one.
+    pub(crate) fn exit_with(mut self, exit: CompareExit) -> Self {
+        if let CompareExit::Test(relation) = exit {
+            self.relation = Some(RelationTest {
+                relation,
+                holds: relation.holds((&self.semantic_ordering).into()),
+            });
+        }
+        self.exit = exit;
+        self
+    }
+
+    pub(crate) fn semantic_ordering(&self) -> &SerializableOrdering {
+        &self.semantic_ordering
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn lexical_ordering(&self) -> &SerializableOrdering {
+        &self.lexical_ordering
+    }
+}
+
+#[derive(Serialize, PartialEq)]
+pub(crate) struct GenerateResult {
+    #[serde(rename(serialize = "versions"))]
+    inner: Vec<String>,
+}
+
+impl GenerateResult {
+    pub(crate) fn new(small: bool, count: usize) -> Self {
+        let inner = if small {
+            generate_u64_safe_semver(count)
+        } else {
+            generate_any_valid_semver(count)
+        };
+        GenerateResult { inner }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn into_inner(self) -> Vec<String> {
+        self.inner
+    }
+}
+
+impl fmt::Display for GenerateResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln_items(&self.inner, f)
+    }
+}
+
+#[derive(Serialize, PartialEq)]
+pub(crate) struct VersionMutationResult {
+    pub(crate) mutated_version: Version,
+}
+
+/// Which end of the precedence-ordered version groups to select.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum BoundaryKind {
+    Min,
     Max,
 }
 
 /// Result of selecting min/max/latest from a version list.
 #[derive(Serialize, PartialEq)]
 pub(crate) struct BoundaryVersionResult {
-    pub(crate) versions: Vec<Version>,
+    pub(crate) versions: Vec<InputVersion>,
     pub(crate) potentially_ambiguous: bool,
     pub(crate) lexical_tiebreak_used: bool,
     pub(crate) stable_filter_applied: bool,
@@ -693,9 +2835,6 @@ impl VersionMutationResult {
         })
     }
 
-    // NOTE(canardleteer): We could actually do bumps on pre-release and
-    //                     build-metadata, if we supported a selector and
-    //                     confirmed the segment was numeric.
     pub(crate) fn bump(
         version: &Version,
         major: Option<u64>,
@@ -780,38 +2919,901 @@ impl VersionMutationResult {
             response.patch = 0;
         }
 
-        let clear_pre = normal_version_only || clear_pre_release;
-        let clear_build = normal_version_only || clear_build_metadata;
+        let clear_pre = normal_version_only || clear_pre_release;
+        let clear_build = normal_version_only || clear_build_metadata;
+
+        if clear_pre {
+            response.pre = Prerelease::EMPTY;
+        }
+        if clear_build {
+            response.build = BuildMetadata::EMPTY;
+        }
+
+        Ok(VersionMutationResult {
+            mutated_version: response,
+        })
+    }
+}
+
+/// Which identifier of a pre-release or build metadata to address.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum IdentifierSelector {
+    /// By 0-based position, `3` in `beta.3.build.7` is `7`.
+    Index(usize),
+    /// By the identifier before it, `build` in `beta.3.build.7` is `7`.
+    Label(String),
+}
+
+impl fmt::Display for IdentifierSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Index(index) => write!(f, "{index}"),
+            Self::Label(label) => write!(f, "after {label}"),
+        }
+    }
+}
+
+/// `SELECTOR[=AMOUNT]`, from `bump --bump-pre-release` and
+/// `--bump-build-metadata`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct IdentifierBump {
+    selector: IdentifierSelector,
+    amount: u64,
+}
+
+impl FromStr for IdentifierBump {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (selector, amount) = match s.split_once('=') {
+            Some((selector, amount)) => (
+                selector,
+                amount
+                    .parse()
+                    .map_err(|e| format!("amount {amount} is not a u64: {e}"))?,
+            ),
+            None => (s, 1),
+        };
+        let selector = match selector.parse() {
+            Ok(index) => IdentifierSelector::Index(index),
+            Err(_) if PreMetaSegment::from(selector).kind == SegmentType::Ascii => {
+                IdentifierSelector::Label(selector.to_string())
+            }
+            Err(e) => return Err(format!("{selector} is not an index: {e}")),
+        };
+        Ok(Self { selector, amount })
+    }
+}
+
+impl IdentifierBump {
+    /// Bump the selected identifier of `identifiers`, keeping any leading
+    /// zeros' width (which build metadata allows).
+    fn apply(&self, identifiers: &str, what: &str) -> Result<String, Box<dyn Error>> {
+        if identifiers.is_empty() {
+            return Err(format!("there is no {what} to bump").into());
+        }
+        let mut identifiers: Vec<String> = identifiers.split('.').map(String::from).collect();
+        let index = match &self.selector {
+            IdentifierSelector::Index(index) => *index,
+            IdentifierSelector::Label(label) => {
+                identifiers
+                    .iter()
+                    .position(|identifier| identifier == label)
+                    .ok_or_else(|| format!("there is no {label} identifier in the {what}"))?
+                    + 1
+            }
+        };
+        let identifier = identifiers
+            .get_mut(index)
+            .ok_or_else(|| format!("there is no {what} identifier {}", self.selector))?;
+
+        let segment = PreMetaSegment::from(identifier.as_str());
+        if segment.kind != SegmentType::Numeric {
+            return Err(format!(
+                "{what} identifier {} is {segment}, only numeric identifiers can be bumped",
+                self.selector
+            )
+            .into());
+        }
+        // The spec has no limit, but we bump within u64.
+        let bumped = segment
+            .value
+            .parse::<u64>()
+            .ok()
+            .and_then(|value| value.checked_add(self.amount))
+            .ok_or_else(|| {
+                format!(
+                    "{what} bump ({} + {}) overflows u64",
+                    segment.value, self.amount
+                )
+            })?;
+        *identifier = format!("{bumped:0width$}", width = segment.value.len());
+        Ok(identifiers.join("."))
+    }
+}
+
+/// A step in a version's pre-release lifecycle, as npm's `semver.inc`.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PreReleaseStep {
+    /// Start a pre-release of the next major version.
+    Premajor,
+    /// Start a pre-release of the next minor version.
+    Preminor,
+    /// Start a pre-release of the next patch version.
+    Prepatch,
+    /// Increment the pre-release counter, or switch channels (`--id`).
+    ///
+    /// Starts a pre-release of the next patch version, if there isn't one.
+    Prerelease,
+    /// Release the pre-release: `1.3.0-rc.5` becomes `1.3.0`.
+    Promote,
+}
+
+impl VersionMutationResult {
+    /// Bump a numeric identifier of the pre-release, build metadata, or both.
+    pub(crate) fn bump_identifiers(
+        version: &Version,
+        pre_release: Option<&IdentifierBump>,
+        build_metadata: Option<&IdentifierBump>,
+    ) -> Result<VersionMutationResult, Box<dyn Error>> {
+        let mut response = version.clone();
+        if let Some(bump) = pre_release {
+            response.pre = Prerelease::new(&bump.apply(version.pre.as_str(), "pre-release")?)?;
+        }
+        if let Some(bump) = build_metadata {
+            response.build =
+                BuildMetadata::new(&bump.apply(version.build.as_str(), "build metadata")?)?;
+        }
+        Ok(VersionMutationResult {
+            mutated_version: response,
+        })
+    }
+
+    /// The pre-release identifier `id` starts new pre-releases with, unless
+    /// one is given.
+    pub(crate) const DEFAULT_PRE_RELEASE_ID: &str = "rc";
+
+    /// Move `version` a step along its pre-release lifecycle.
+    ///
+    /// Pre-releases count from `{id}.0`. Within a channel, the trailing
+    /// numeric identifier is incremented; switching to another channel resets
+    /// the counter, and must not go backwards (`beta` to `alpha`). Build
+    /// metadata is dropped.
+    pub(crate) fn pre_release(
+        version: &Version,
+        step: PreReleaseStep,
+        id: Option<&str>,
+    ) -> Result<VersionMutationResult, Box<dyn Error>> {
+        let start = |id: Option<&str>| -> Result<Prerelease, Box<dyn Error>> {
+            let id = id.unwrap_or(Self::DEFAULT_PRE_RELEASE_ID);
+            Prerelease::new(&format!("{id}.0"))
+                .map_err(|e| format!("unable to use {id} as a pre-release identifier: {e}").into())
+        };
+
+        let mut response = version.clone();
+        response.build = BuildMetadata::EMPTY;
+        match step {
+            PreReleaseStep::Premajor | PreReleaseStep::Preminor => {
+                response = Self::bump_reset(
+                    &response,
+                    step == PreReleaseStep::Premajor,
+                    false,
+                    false,
+                    true,
+                )?
+                .mutated_version;
+                response.pre = start(id)?;
+            }
+            PreReleaseStep::Prepatch => {
+                response = Self::bump(&response, None, None, Some(1))?.mutated_version;
+                response.pre = start(id)?;
+            }
+            PreReleaseStep::Prerelease if version.pre.is_empty() => {
+                response = Self::bump(&response, None, None, Some(1))?.mutated_version;
+                response.pre = start(id)?;
+            }
+            PreReleaseStep::Prerelease => {
+                let current = version.pre.as_str();
+                let numeric = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+                let (channel, counter) = match current.rsplit_once('.') {
+                    Some((channel, counter)) if numeric(counter) => (channel, Some(counter)),
+                    None if numeric(current) => ("", Some(current)),
+                    _ => (current, None),
+                };
+                response.pre = match (id, counter) {
+                    (Some(id), _) if id != channel => {
+                        let switched = start(Some(id))?;
+                        if switched < version.pre {
+                            return Err(format!(
+                                "switching from {current} to {switched} would go backwards, \
+                                 start a new version with premajor, preminor or prepatch"
+                            )
+                            .into());
+                        }
+                        switched
+                    }
+                    (_, Some(counter)) => {
+                        let counter = counter
+                            .parse::<u64>()
+                            .ok()
+                            .and_then(|counter| counter.checked_add(1))
+                            .ok_or_else(|| {
+                                format!("pre-release counter ({counter} + 1) overflows u64")
+                            })?;
+                        if channel.is_empty() {
+                            Prerelease::new(&counter.to_string())?
+                        } else {
+                            Prerelease::new(&format!("{channel}.{counter}"))?
+                        }
+                    }
+                    (_, None) => start(Some(channel))?,
+                };
+            }
+            PreReleaseStep::Promote => {
+                if version.pre.is_empty() {
+                    return Err(format!("{version} is not a pre-release").into());
+                }
+                response.pre = Prerelease::EMPTY;
+            }
+        }
+
+        Ok(VersionMutationResult {
+            mutated_version: response,
+        })
+    }
+}
+
+impl fmt::Display for VersionMutationResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.mutated_version)
+    }
+}
+
+/// How much of a version a change bumps.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum BumpLevel {
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl fmt::Display for BumpLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::None => "none",
+            Self::Patch => "patch",
+            Self::Minor => "minor",
+            Self::Major => "major",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Which part of a version `next` changes.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum NextLevel {
+    /// Nothing called for a change.
+    #[value(skip)]
+    None,
+    /// The next patch version: `1.2.3` to `1.2.4`, `1.2.4-rc.1` to `1.2.4`.
+    Patch,
+    /// The next minor version: `1.2.3` to `1.3.0`, `1.3.0-rc.2` to `1.3.0`.
+    Minor,
+    /// The next major version: `1.2.3` to `2.0.0`, `2.0.0-rc.1` to `2.0.0`.
+    Major,
+    /// The release of a pre-release (`1.3.0-rc.2` to `1.3.0`), or the next
+    /// patch version of a release.
+    Release,
+}
+
+impl fmt::Display for NextLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::None => "none",
+            Self::Patch => "patch",
+            Self::Minor => "minor",
+            Self::Major => "major",
+            Self::Release => "release",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl From<BumpLevel> for NextLevel {
+    fn from(value: BumpLevel) -> Self {
+        match value {
+            BumpLevel::None => Self::None,
+            BumpLevel::Patch => Self::Patch,
+            BumpLevel::Minor => Self::Minor,
+            BumpLevel::Major => Self::Major,
+        }
+    }
+}
+
+/// The version that follows `base`, why, and the commits that called for it.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct NextVersionResult {
+    base: Version,
+    version: Version,
+    bump: NextLevel,
+    decisions: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    commits: Vec<ConventionalCommit>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped: Vec<Version>,
+    /// Versions sharing the base's precedence, which `max` would call
+    /// ambiguous.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ambiguous_bases: Vec<Version>,
+}
+
+impl NextVersionResult {
+    /// The next `level` version after `base`, by the usual SemVer rules.
+    ///
+    /// A pre-release comes before its release (SemVer §11), so the next
+    /// version of a pre-release is its own release, if that is the kind of
+    /// release asked for: `1.3.0-rc.2` is followed by minor `1.3.0`, but by
+    /// major `2.0.0`. Pre-release and build metadata are always dropped.
+    ///
+    /// Before 1.0.0, anything may change at any time (SemVer §4), so a major
+    /// change bumps minor, unless `leave_initial_development`.
+    pub(crate) fn next(
+        base: &Version,
+        level: NextLevel,
+        leave_initial_development: bool,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut decisions = Vec::new();
+        let mut bump = level;
+        if bump == NextLevel::Major && base.major == 0 {
+            if leave_initial_development {
+                decisions.push(format!(
+                    "{base} is in initial development (0.y.z), which a major change leaves for 1.0.0"
+                ));
+            } else {
+                decisions.push(format!(
+                    "{base} is in initial development (0.y.z), where a major change bumps minor (SemVer §4)"
+                ));
+                bump = NextLevel::Minor;
+            }
+        }
+
+        let is_pre = !base.pre.is_empty();
+        let (major, minor, patch) = (base.major, base.minor, base.patch);
+        let version = match bump {
+            NextLevel::None => {
+                decisions.push(format!("nothing calls for a change, so {base} is kept"));
+                return Ok(Self {
+                    base: base.clone(),
+                    version: base.clone(),
+                    bump,
+                    decisions,
+                    commits: Vec::new(),
+                    skipped: Vec::new(),
+                    ambiguous_bases: Vec::new(),
+                });
+            }
+            // Leaving initial development from 0.0.0-rc.1 is 1.0.0, not 0.0.0.
+            NextLevel::Major if is_pre && major > 0 && minor == 0 && patch == 0 => {
+                decisions.push(format!(
+                    "{base} is a pre-release of {major}.0.0, so its release is the next major version"
+                ));
+                Version::new(major, 0, 0)
+            }
+            NextLevel::Minor if is_pre && patch == 0 => {
+                decisions.push(format!(
+                    "{base} is a pre-release of {major}.{minor}.0, so its release is the next minor version"
+                ));
+                Version::new(major, minor, 0)
+            }
+            NextLevel::Patch | NextLevel::Release if is_pre => {
+                decisions.push(format!(
+                    "{base} is a pre-release of {major}.{minor}.{patch}, so its release is the next {bump} version"
+                ));
+                Version::new(major, minor, patch)
+            }
+            NextLevel::Major | NextLevel::Minor => {
+                decisions.push(format!(
+                    "{base} bumps {bump}, resetting the less significant components"
+                ));
+                VersionMutationResult::bump_reset(
+                    base,
+                    bump == NextLevel::Major,
+                    false,
+                    false,
+                    true,
+                )?
+                .mutated_version
+            }
+            NextLevel::Patch | NextLevel::Release => {
+                if bump == NextLevel::Release {
+                    decisions.push(format!(
+                        "{base} is already released, so the next release is a patch"
+                    ));
+                } else {
+                    decisions.push(format!("{base} bumps patch"));
+                }
+                let patched = VersionMutationResult::bump(base, None, None, Some(1))?;
+                Version::new(major, minor, patched.mutated_version.patch)
+            }
+        };
+        if is_pre || !base.build.is_empty() {
+            decisions.push("pre-release and build metadata are dropped".to_string());
+        }
+
+        Ok(Self {
+            base: base.clone(),
+            version,
+            bump,
+            decisions,
+            commits: Vec::new(),
+            skipped: Vec::new(),
+            ambiguous_bases: Vec::new(),
+        })
+    }
+
+    /// The next version after `base` that `commits` call for: the most
+    /// significant bump any of them calls for.
+    pub(crate) fn from_commits(
+        base: &Version,
+        commits: Vec<ConventionalCommit>,
+        bumps: &CommitBumps,
+        leave_initial_development: bool,
+    ) -> Result<Self, Box<dyn Error>> {
+        let wanted = commits
+            .iter()
+            .map(|commit| bumps.level(commit))
+            .max()
+            .unwrap_or(BumpLevel::None);
+        let commits: Vec<ConventionalCommit> = commits
+            .into_iter()
+            .filter(|commit| wanted != BumpLevel::None && bumps.level(commit) == wanted)
+            .collect();
+
+        let mut result = Self::next(base, wanted.into(), leave_initial_development)?;
+        let why = match commits.len() {
+            0 => "no commit calls for a bump".to_string(),
+            1 => format!("1 commit calls for a {wanted} bump"),
+            n => format!("{n} commits call for a {wanted} bump"),
+        };
+        result.decisions.insert(0, why);
+        result.commits = commits;
+        Ok(result)
+    }
+
+    /// Keep bumping until the version is not `published`, and is newer than
+    /// every published version of its line (the minor line of a patch bump,
+    /// the major line of a minor bump).
+    ///
+    /// A taken candidate is bumped again, and one behind its line starts
+    /// from the highest of the line. When nothing calls for a bump, a taken
+    /// pre-release counts up instead (`1.3.0-rc.2` to `1.3.0-rc.3`).
+    pub(crate) fn avoid_existing(
+        mut self,
+        published: &[Version],
+        leave_initial_development: bool,
+    ) -> Result<Self, Box<dyn Error>> {
+        let target = self.version.clone();
+        let line_max = published
+            .iter()
+            .filter(|version| match self.bump {
+                NextLevel::None => {
+                    (version.major, version.minor, version.patch)
+                        == (target.major, target.minor, target.patch)
+                }
+                NextLevel::Patch | NextLevel::Release => {
+                    (version.major, version.minor) == (target.major, target.minor)
+                }
+                NextLevel::Minor => version.major == target.major,
+                NextLevel::Major => true,
+            })
+            .max_by(|a, b| a.cmp_precedence(b));
+
+        loop {
+            let candidate = &self.version;
+            let taken = published
+                .iter()
+                .any(|version| version.cmp_precedence(candidate) == Ordering::Equal);
+            let from = match line_max {
+                _ if taken => {
+                    self.decisions
+                        .push(format!("{candidate} is already published"));
+                    candidate.clone()
+                }
+                Some(max) if candidate.cmp_precedence(max) != Ordering::Greater => {
+                    self.decisions.push(format!(
+                        "{candidate} is not newer than {max}, the highest published version of its line"
+                    ));
+                    max.clone()
+                }
+                _ => return Ok(self),
+            };
+            self.skipped.push(candidate.clone());
+            self.version = match self.bump {
+                NextLevel::None if from.pre.is_empty() => {
+                    return Err(super::misc::ApplicationError::FailedRequirementError {
+                        err: format!("nothing calls for a change, and {from} is already published"),
+                    }
+                    .into());
+                }
+                NextLevel::None => {
+                    VersionMutationResult::pre_release(&from, PreReleaseStep::Prerelease, None)?
+                        .mutated_version
+                }
+                bump => Self::next(&from, bump, leave_initial_development)?.version,
+            };
+        }
+    }
+
+    /// A snapshot (nightly) of the `level` version after the latest stable
+    /// version in `versions`: `1.5.0-nightly.20261018.0` after `1.4.2`.
+    ///
+    /// Snapshots of that version already in `versions` are counted, so the
+    /// counter for `date` continues after the highest of them, and the
+    /// snapshot must have precedence over all of them.
+    pub(crate) fn snapshot(
+        versions: &[Version],
+        level: NextLevel,
+        id: &str,
+        date: &str,
+        leave_initial_development: bool,
+    ) -> Result<Self, Box<dyn Error>> {
+        // Picked as `max --stable` would. Build metadata is dropped from the
+        // snapshot, so ties give the same version, but they are reported.
+        let stable = OrderedVersionMap::new(versions.to_vec(), &None, true, false, true);
+        if stable.inner.is_empty() {
+            return Err(super::misc::ApplicationError::FailedRequirementError {
+                err: "no stable version to take a snapshot after".to_string(),
+            }
+            .into());
+        }
+        let latest =
+            BoundaryVersionResult::boundary_versions(&stable, BoundaryKind::Max, true, true, true)?;
+        let base = latest.versions[0].version();
+        let mut result = Self::next(base, level, leave_initial_development)?;
+        result
+            .decisions
+            .insert(0, format!("{base} is the latest stable version"));
+        if latest.potentially_ambiguous {
+            let ties: Vec<String> = latest.versions.iter().map(ToString::to_string).collect();
+            result.decisions.insert(
+                1,
+                format!(
+                    "{} share its precedence (ambiguous under `max`), and differ only in the dropped build metadata",
+                    ties.join(", ")
+                ),
+            );
+            result.ambiguous_bases = latest
+                .versions
+                .iter()
+                .map(|input| input.version().clone())
+                .collect();
+        }
+
+        let target = &result.version;
+        // `--id` may itself be dotted (`nightly.build`), so snapshots are
+        // matched on it as a prefix, followed by `date.counter`.
+        let prefix = format!("{id}.");
+        let snapshots: Vec<&Version> = versions
+            .iter()
+            .filter(|version| {
+                (version.major, version.minor, version.patch)
+                    == (target.major, target.minor, target.patch)
+                    && (version.pre.as_str() == id || version.pre.starts_with(&prefix))
+            })
+            .collect();
+        let highest = snapshots
+            .iter()
+            .filter_map(|version| {
+                let (on, counter) = version.pre.strip_prefix(&prefix)?.split_once('.')?;
+                (on == date).then(|| counter.parse::<u64>().ok())?
+            })
+            .max();
+        let counter = match highest {
+            None => 0,
+            Some(highest) => highest
+                .checked_add(1)
+                .ok_or_else(|| format!("{id} snapshot counter ({highest} + 1) overflows u64"))?,
+        };
+        if counter > 0 {
+            result.decisions.push(format!(
+                "{id} snapshots of {target} on {date} already count up to {}",
+                counter - 1
+            ));
+        }
+
+        let pre = format!("{id}.{date}.{counter}");
+        let mut snapshot = target.clone();
+        snapshot.pre =
+            Prerelease::new(&pre).map_err(|e| super::misc::ApplicationError::InvalidArgument {
+                expected: "a pre-release identifier for --id".to_string(),
+                found: format!("{id}: {e}"),
+            })?;
+        if let Some(newer) = snapshots
+            .iter()
+            .find(|existing| snapshot.cmp_precedence(existing) != Ordering::Greater)
+        {
+            return Err(super::misc::ApplicationError::FailedRequirementError {
+                err: format!("{snapshot} would not be newer than the existing snapshot {newer}"),
+            }
+            .into());
+        }
+        result.version = snapshot;
+        Ok(result)
+    }
+}
+
+/// Only the version, so it can be captured by a script.
+impl fmt::Display for NextVersionResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.version)
+    }
+}
+
+/// Versions ordered under one set of [PrecedenceRules].
+#[derive(Serialize, PartialEq)]
+pub(crate) struct RulesSorted {
+    rules: PrecedenceRules,
+    versions: Vec<InputVersion>,
+}
+
+/// `sort --rules`: the same versions ordered under each rule set asked for.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct RulesSortResult {
+    orderings: Vec<RulesSorted>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rules_disagree: Option<bool>,
+}
+
+impl RulesSortResult {
+    /// Sorting is stable, so versions a rule set finds equal keep their
+    /// input order.
+    pub(crate) fn new(inputs: Vec<InputVersion>, rules: PrecedenceRules, reverse: bool) -> Self {
+        let orderings: Vec<RulesSorted> = rules
+            .each()
+            .into_iter()
+            .map(|rules| {
+                let mut versions = inputs.clone();
+                versions.sort_by(|a, b| {
+                    let ordering = rules.cmp(a.version(), b.version());
+                    if reverse {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                });
+                RulesSorted { rules, versions }
+            })
+            .collect();
+        let rules_disagree = (orderings.len() > 1)
+            .then(|| (0..inputs.len()).any(|i| Self::disagree_at(&orderings, i)));
+        Self {
+            orderings,
+            rules_disagree,
+        }
+    }
+
+    /// If the rule sets put different versions at `index`.
+    fn disagree_at(orderings: &[RulesSorted], index: usize) -> bool {
+        let first = &orderings[0].versions[index];
+        orderings[1..]
+            .iter()
+            .any(|sorted| sorted.versions[index] != *first)
+    }
+}
+
+impl fmt::Display for RulesSortResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let [sorted] = self.orderings.as_slice() {
+            return writeln_items(&sorted.versions, f);
+        }
+
+        // Side by side, marking rows where the rule sets disagree.
+        let rows = self.orderings[0].versions.len();
+        let columns: Vec<Vec<String>> = self
+            .orderings
+            .iter()
+            .map(|sorted| {
+                std::iter::once(sorted.rules.to_string())
+                    .chain(sorted.versions.iter().map(ToString::to_string))
+                    .collect()
+            })
+            .collect();
+        let widths: Vec<usize> = columns
+            .iter()
+            .map(|column| column.iter().map(String::len).max().unwrap_or(0))
+            .collect();
+        for row in 0..=rows {
+            let line = columns
+                .iter()
+                .zip(&widths)
+                .map(|(column, &width)| format!("{:<width$}", column[row]))
+                .collect::<Vec<_>>()
+                .join("  ");
+            if row > 0 && Self::disagree_at(&self.orderings, row - 1) {
+                writeln!(f, "{line}  *")?;
+            } else {
+                writeln!(f, "{}", line.trim_end())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How a [ComparisonMatrix] is rendered as text.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum MatrixFormat {
+    /// An aligned plain text table.
+    #[default]
+    Table,
+    /// A Markdown table.
+    Markdown,
+    /// Comma separated values, with a header row.
+    Csv,
+}
+
+/// One row of a [ComparisonMatrix]: `version` against each column version.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct MatrixRow {
+    version: Version,
+    semantic: Vec<SerializableOrdering>,
+    lexical: Vec<SerializableOrdering>,
+}
+
+/// A pair whose semantic and lexical orderings disagree.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct MatrixInconsistency {
+    a: Version,
+    b: Version,
+    semantic_ordering: SerializableOrdering,
+    lexical_ordering: SerializableOrdering,
+    /// What `compare -e a b` would exit with.
+    exit_code: u8,
+}
+
+/// Every version compared against every other, for `compare --matrix`.
+#[derive(Serialize, PartialEq)]
+pub(crate) struct ComparisonMatrix {
+    rows: Vec<MatrixRow>,
+    inconsistencies: Vec<MatrixInconsistency>,
+    #[serde(skip)]
+    format: MatrixFormat,
+}
 
-        if clear_pre {
-            response.pre = Prerelease::EMPTY;
-        }
-        if clear_build {
-            response.build = BuildMetadata::EMPTY;
+impl ComparisonMatrix {
+    pub(crate) fn new(versions: Vec<Version>, format: MatrixFormat) -> Self {
+        let mut inconsistencies = Vec::new();
+        let rows = versions
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                let (semantic, lexical) = versions
+                    .iter()
+                    .enumerate()
+                    .map(|(j, b)| {
+                        let sem = SerializableOrdering::from(a.cmp_precedence(b));
+                        let lex = SerializableOrdering::from(a.cmp(b));
+                        // Each pair is listed once, in input order.
+                        if sem != lex && i < j {
+                            inconsistencies.push(MatrixInconsistency {
+                                a: a.clone(),
+                                b: b.clone(),
+                                semantic_ordering: sem,
+                                lexical_ordering: lex,
+                                exit_code: simplify_exit_code(sem, lex),
+                            });
+                        }
+                        (sem, lex)
+                    })
+                    .unzip();
+                MatrixRow {
+                    version: a.clone(),
+                    semantic,
+                    lexical,
+                }
+            })
+            .collect();
+
+        Self {
+            rows,
+            inconsistencies,
+            format,
         }
+    }
 
-        Ok(VersionMutationResult {
-            mutated_version: response,
-        })
+    /// A cell is the semantic then lexical ordering symbol, marked with `*`
+    /// when they disagree.
+    fn cell(sem: SerializableOrdering, lex: SerializableOrdering) -> String {
+        let mark = if sem != lex { "*" } else { "" };
+        format!("{}{}{mark}", sem.symbol(), lex.symbol())
+    }
+
+    fn cells(&self) -> Vec<Vec<String>> {
+        let header = std::iter::once(String::new())
+            .chain(self.rows.iter().map(|row| row.version.to_string()))
+            .collect();
+        std::iter::once(header)
+            .chain(self.rows.iter().map(|row| {
+                std::iter::once(row.version.to_string())
+                    .chain(
+                        row.semantic
+                            .iter()
+                            .zip(&row.lexical)
+                            .map(|(&sem, &lex)| Self::cell(sem, lex)),
+                    )
+                    .collect()
+            }))
+            .collect()
     }
 }
 
-impl fmt::Display for VersionMutationResult {
+impl fmt::Display for ComparisonMatrix {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.mutated_version)
+        let cells = self.cells();
+        match self.format {
+            MatrixFormat::Csv => {
+                for row in &cells {
+                    writeln!(f, "{}", row.join(","))?;
+                }
+                return Ok(());
+            }
+            MatrixFormat::Table => {
+                let widths: Vec<usize> = (0..cells[0].len())
+                    .map(|column| cells.iter().map(|row| row[column].len()).max().unwrap_or(0))
+                    .collect();
+                for row in &cells {
+                    let line = row
+                        .iter()
+                        .zip(&widths)
+                        .map(|(cell, &width)| format!("{cell:<width$}"))
+                        .collect::<Vec<_>>()
+                        .join("  ");
+                    writeln!(f, "{}", line.trim_end())?;
+                }
+            }
+            MatrixFormat::Markdown => {
+                for (index, row) in cells.iter().enumerate() {
+                    writeln!(f, "| {} |", row.join(" | "))?;
+                    if index == 0 {
+                        writeln!(f, "|{}", "---|".repeat(row.len()))?;
+                    }
+                }
+            }
+        }
+
+        if !self.inconsistencies.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Inconsistencies:")?;
+            for i in &self.inconsistencies {
+                writeln!(
+                    f,
+                    "- {} vs {}: semantically {:?}, lexically {:?} ({})",
+                    i.a, i.b, i.semantic_ordering, i.lexical_ordering, i.exit_code
+                )?;
+            }
+        }
+        Ok(())
     }
 }
 
+/// The exit status is set by [CompareExit]:
+///
+/// - [ExitScheme::TestV1]: 0 when the relation holds, and 1 when it doesn't.
+/// - [ExitScheme::ThreeWayV1]: 0 when semantically Equal, 1 when Less, and
+///   2 when Greater.
+/// - `--fail-on-diff`: 1 when the versions differ in at least the given
+///   component, and 0 otherwise.
+/// - `--fail-if-incompatible`: 1 when the versions aren't
+///   [cargo_compatible], and 0 otherwise.
+/// - [ExitScheme::DualV1], the default once exit statuses are enabled, as
+///   below.
+///
 /// Invent a way to reasonably express a non-equivalent ComparisonStatement in
 /// a u8, but really, at that point, just use the YAML output.
 ///
 /// - Equivalent of both types, will always be ExitCode::SUCCESS.
-/// - All the rest should be considered UNSTABLE, until we can come up with a
-///   better plan.
-///
-/// I don't think there's a great way to do this, so I'm just going to
-/// enumerate them until something better comes along (I'm sure someone has
-/// already thought of a better way).
 ///
 /// For all cases where BOTH semantic and lexical ordering are not Equal:
 ///
@@ -829,9 +3831,28 @@ impl fmt::Display for VersionMutationResult {
 /// - (semantic: Greater, Lexical: Greater) = 122
 impl Termination for ComparisonStatement {
     fn report(self) -> ExitCode {
-        match (self.semantic_ordering, self.lexical_ordering) {
-            (SerializableOrdering::Equal, SerializableOrdering::Equal) => ExitCode::SUCCESS,
-            (sem, lex) => ExitCode::from(simplify_exit_code(sem, lex)),
+        let failed = match self.exit {
+            CompareExit::Dual => {
+                return match (self.semantic_ordering, self.lexical_ordering) {
+                    (SerializableOrdering::Equal, SerializableOrdering::Equal) => ExitCode::SUCCESS,
+                    (sem, lex) => ExitCode::from(simplify_exit_code(sem, lex)),
+                };
+            }
+            CompareExit::ThreeWay => {
+                return ExitCode::from(match self.semantic_ordering {
+                    SerializableOrdering::Equal => 0,
+                    SerializableOrdering::Less => 1,
+                    SerializableOrdering::Greater => 2,
+                });
+            }
+            CompareExit::Test(_) => !self.relation.is_some_and(|test| test.holds),
+            CompareExit::FailOnDiff(component) => self.diff >= component,
+            CompareExit::Incompatible => !self.cargo_compatible,
+        };
+        if failed {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
         }
     }
 }
@@ -844,20 +3865,43 @@ impl fmt::Display for ComparisonStatement {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Semantically: {:?}\nLexically: {:?}\n",
-            self.semantic_ordering, self.lexical_ordering
-        )
+            "Semantically: {:?}\nLexically: {:?}\nDiffers in: {}\nCargo compatible: {}\n",
+            self.semantic_ordering, self.lexical_ordering, self.diff, self.cargo_compatible
+        )?;
+        if let Some(test) = &self.relation {
+            writeln!(f, "Relation {}: {}", test.relation, test.holds)?;
+        }
+        for rules in &self.rules {
+            writeln!(f, "Under {}: {:?}", rules.rules, rules.ordering)?;
+        }
+        if let Some(disagree) = self.rules_disagree {
+            writeln!(f, "Rules disagree: {disagree}")?;
+        }
+        if let Some(trace) = &self.trace {
+            write!(f, "{trace}")?;
+        }
+        Ok(())
     }
 }
 
 /// Just a small reimplementation of std::Ordering with Serialization.
-#[derive(Debug, Serialize, PartialEq)]
+#[derive(Clone, Copy, Debug, Serialize, PartialEq)]
 pub(crate) enum SerializableOrdering {
     Less,
     Greater,
     Equal,
 }
 
+impl SerializableOrdering {
+    fn symbol(&self) -> &'static str {
+        match self {
+            SerializableOrdering::Less => "<",
+            SerializableOrdering::Equal => "=",
+            SerializableOrdering::Greater => ">",
+        }
+    }
+}
+
 impl From<SerializableOrdering> for u8 {
     fn from(value: SerializableOrdering) -> Self {
         match value {
@@ -878,6 +3922,16 @@ impl From<Ordering> for SerializableOrdering {
     }
 }
 
+impl From<&SerializableOrdering> for Ordering {
+    fn from(value: &SerializableOrdering) -> Self {
+        match value {
+            SerializableOrdering::Less => Ordering::Less,
+            SerializableOrdering::Equal => Ordering::Equal,
+            SerializableOrdering::Greater => Ordering::Greater,
+        }
+    }
+}
+
 pub fn version_without_build_metadata(version: &Version) -> Version {
     Version {
         major: version.major,
@@ -907,11 +3961,19 @@ pub fn ordering_pair_to_exit_code(sem: Ordering, lex: Ordering) -> i32 {
 
 impl_success_termination!(
     VersionExplanation,
+    NextVersionResult,
+    ExpandResult,
+    DistanceResult,
+    ComparisonMatrix,
+    RulesSortResult,
     FlatVersionsList,
     OrderedVersionMap,
     GenerateResult,
     BoundaryVersionResult,
     VersionMutationResult,
+    UniqResult,
+    KeyedLinesList,
+    VersionedFilesResult,
 );
 
 #[cfg(test)]
@@ -965,18 +4027,18 @@ mod tests {
     // Tests some simple static tests.
     #[test]
     fn test_ordered_version_map() {
-        let mut scaffold1 = ["99.0.0", "100.0.0", "0.0.1"]
+        let scaffold1 = ["99.0.0", "100.0.0", "0.0.1"]
             .iter()
             .map(|v| Version::parse(v).unwrap())
             .collect();
 
-        let test = OrderedVersionMap::new(&mut scaffold1, &None, false, false, false);
+        let test = OrderedVersionMap::new(scaffold1, &None, false, false, false);
         assert!(test.inner.contains_key(&Version::parse("99.0.0").unwrap()));
         assert!(test.inner.contains_key(&Version::parse("100.0.0").unwrap()));
         assert!(test.inner.contains_key(&Version::parse("0.0.1").unwrap()));
         assert!(!test.potentially_ambiguous);
 
-        let mut scaffold2: Vec<Version> = vec![
+        let scaffold2: Vec<Version> = vec![
             "0.0.0-alpha.0+metadata",
             "0.0.0-alpha.0+other.metadata",
             "0.0.0-alpha.0+other.metadata.3",
@@ -1003,7 +4065,7 @@ mod tests {
         .map(|v| Version::parse(v).unwrap())
         .collect();
 
-        let test = OrderedVersionMap::new(&mut scaffold2, &None, false, false, false);
+        let test = OrderedVersionMap::new(scaffold2.clone(), &None, false, false, false);
         let test_keys: Vec<Version> = test.inner.keys().cloned().collect();
         assert!(test_keys.len() == 12);
         assert!(test_keys[0] == Version::parse("0.0.0-alpha.0").unwrap());
@@ -1011,7 +4073,7 @@ mod tests {
         assert!(test.potentially_ambiguous);
 
         // Reverse of above test.
-        let test = OrderedVersionMap::new(&mut scaffold2, &None, false, true, false);
+        let test = OrderedVersionMap::new(scaffold2.clone(), &None, false, true, false);
         let test_keys: Vec<Version> = test.inner.keys().cloned().collect();
         assert!(test_keys.len() == 12);
         assert!(test_keys[test_keys.len() - 1] == Version::parse("0.0.0-alpha.0").unwrap());
@@ -1020,7 +4082,7 @@ mod tests {
 
         // Filter, this should exclude all versions with pre-releases
         let test = OrderedVersionMap::new(
-            &mut scaffold2,
+            scaffold2,
             &Some(VersionReq::parse("*").unwrap()),
             false,
             false,
@@ -1039,7 +4101,7 @@ mod tests {
     // Static test around the basic structure.
     #[test]
     fn flat_version_list() {
-        let mut scaffold: Vec<Version> = vec![
+        let scaffold: Vec<Version> = vec![
             "0.0.0-alpha.0+metadata",
             "0.0.0-alpha.0+other.metadata",
             "0.0.0-alpha.0+other.metadata.3",
@@ -1067,21 +4129,24 @@ mod tests {
         .collect();
 
         // lexical sorting
-        let mut test = OrderedVersionMap::new(&mut scaffold, &None, true, false, false);
+        let mut test = OrderedVersionMap::new(scaffold.clone(), &None, true, false, false);
         let test = FlatVersionsList::from(&mut test);
         assert!(test.versions.len() == 21);
-        assert!(test.versions[0] == Version::parse("0.0.0-alpha.0+metadata").unwrap());
-        assert!(test.versions[test.versions.len() - 1] == Version::parse("99.99.0-rc1.0").unwrap());
+        assert!(test.versions[0].version == Version::parse("0.0.0-alpha.0+metadata").unwrap());
+        assert!(
+            test.versions[test.versions.len() - 1].version
+                == Version::parse("99.99.0-rc1.0").unwrap()
+        );
 
         // lexical sorting, reversed
-        let mut test = OrderedVersionMap::new(&mut scaffold, &None, true, true, false);
+        let mut test = OrderedVersionMap::new(scaffold, &None, true, true, false);
         let test = FlatVersionsList::from(&mut test);
         assert!(test.versions.len() == 21);
         assert!(
-            test.versions[test.versions.len() - 1]
+            test.versions[test.versions.len() - 1].version
                 == Version::parse("0.0.0-alpha.0+metadata").unwrap()
         );
-        assert!(test.versions[0] == Version::parse("99.99.0-rc1.0").unwrap());
+        assert!(test.versions[0].version == Version::parse("99.99.0-rc1.0").unwrap());
 
         // Display Coverage
         let _ = format!("{}", test);
@@ -1251,13 +4316,147 @@ mod tests {
         assert_eq!(normal.mutated_version.to_string(), "1.3.0");
     }
 
+    #[test]
+    fn test_bump_identifiers() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let bump = |s: &str| s.parse::<IdentifierBump>().unwrap();
+        let cases = [
+            (
+                "1.0.0-beta.3.build.7",
+                Some("3"),
+                None,
+                "1.0.0-beta.3.build.8",
+            ),
+            (
+                "1.0.0-beta.3.build.7",
+                Some("build=5"),
+                None,
+                "1.0.0-beta.3.build.12",
+            ),
+            (
+                "1.0.0-beta.3.build.7",
+                Some("beta"),
+                None,
+                "1.0.0-beta.4.build.7",
+            ),
+            ("1.0.0+ci.41", None, Some("1"), "1.0.0+ci.42"),
+            (
+                "1.0.0-rc.1+ci.007",
+                Some("rc"),
+                Some("ci"),
+                "1.0.0-rc.2+ci.008",
+            ),
+            ("1.0.0-9", Some("0"), None, "1.0.0-10"),
+        ];
+        for (version, pre, build, expected) in cases {
+            let result = VersionMutationResult::bump_identifiers(
+                &v(version),
+                pre.map(bump).as_ref(),
+                build.map(bump).as_ref(),
+            )
+            .unwrap();
+            assert_eq!(result.mutated_version, v(expected), "{version}");
+        }
+
+        for (version, pre) in [
+            ("1.0.0", "0"),
+            ("1.0.0-beta.3", "0"),
+            ("1.0.0-beta.3", "2"),
+            ("1.0.0-beta.3", "rc"),
+            ("1.0.0-beta", "beta"),
+            ("1.0.0-beta.18446744073709551615", "beta"),
+        ] {
+            assert!(
+                VersionMutationResult::bump_identifiers(&v(version), Some(&bump(pre)), None)
+                    .is_err(),
+                "{version} {pre}"
+            );
+        }
+        assert!("1=x".parse::<IdentifierBump>().is_err());
+        assert!("-1".parse::<IdentifierBump>().is_ok());
+    }
+
+    #[test]
+    fn test_pre_release_steps() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let cases = [
+            ("1.2.3", PreReleaseStep::Premajor, None, "2.0.0-rc.0"),
+            ("1.2.3", PreReleaseStep::Preminor, None, "1.3.0-rc.0"),
+            (
+                "1.2.3+b",
+                PreReleaseStep::Prepatch,
+                Some("alpha"),
+                "1.2.4-alpha.0",
+            ),
+            ("1.2.3", PreReleaseStep::Prerelease, None, "1.2.4-rc.0"),
+            ("1.3.0-rc.4", PreReleaseStep::Prerelease, None, "1.3.0-rc.5"),
+            (
+                "1.3.0-rc.9+b",
+                PreReleaseStep::Prerelease,
+                Some("rc"),
+                "1.3.0-rc.10",
+            ),
+            (
+                "1.3.0-alpha.3",
+                PreReleaseStep::Prerelease,
+                Some("beta"),
+                "1.3.0-beta.0",
+            ),
+            (
+                "1.3.0-beta",
+                PreReleaseStep::Prerelease,
+                None,
+                "1.3.0-beta.0",
+            ),
+            (
+                "1.3.0-beta.1.2",
+                PreReleaseStep::Prerelease,
+                None,
+                "1.3.0-beta.1.3",
+            ),
+            ("1.3.0-0", PreReleaseStep::Prerelease, None, "1.3.0-1"),
+            (
+                "1.3.0-0",
+                PreReleaseStep::Prerelease,
+                Some("rc"),
+                "1.3.0-rc.0",
+            ),
+            ("1.3.0-rc.5", PreReleaseStep::Promote, None, "1.3.0"),
+            ("1.3.0-rc.5", PreReleaseStep::Preminor, None, "1.4.0-rc.0"),
+        ];
+        for (version, step, id, expected) in cases {
+            let result = VersionMutationResult::pre_release(&v(version), step, id).unwrap();
+            assert_eq!(
+                result.mutated_version,
+                v(expected),
+                "{version} {step:?} {id:?}"
+            );
+        }
+
+        for (version, step, id) in [
+            ("1.3.0-beta.2", PreReleaseStep::Prerelease, Some("alpha")),
+            ("1.3.0", PreReleaseStep::Promote, None),
+            ("1.3.0", PreReleaseStep::Preminor, Some("no spaces")),
+            (
+                "1.3.0-rc.18446744073709551615",
+                PreReleaseStep::Prerelease,
+                None,
+            ),
+        ] {
+            assert!(
+                VersionMutationResult::pre_release(&v(version), step, id).is_err(),
+                "{version} {step:?} {id:?}"
+            );
+        }
+    }
+
     #[test]
     fn test_boundary_versions() {
-        let mut versions: Vec<Version> = ["1.0.0", "2.0.0+bm", "2.0.0+bm2"]
+        let versions: Vec<Version> = ["1.0.0", "2.0.0+bm", "2.0.0+bm2"]
             .iter()
             .map(|s| Version::parse(s).unwrap())
             .collect();
-        let map = OrderedVersionMap::new(&mut versions, &None, false, false, false);
+        let map = OrderedVersionMap::new(versions, &None, false, false, false);
 
         let max =
             BoundaryVersionResult::boundary_versions(&map, BoundaryKind::Max, false, false, false);
@@ -1275,19 +4474,432 @@ mod tests {
         assert_eq!(min.versions[0].to_string(), "1.0.0");
     }
 
+    #[test]
+    fn test_input_version_provenance() {
+        assert!(InputVersion::parse("v1.2.3", &Arc::from("stdin"), 1, false).is_err());
+
+        let inputs: Vec<InputVersion> = ["v1.2.3", "  V2.0.0 ", "1.10.0"]
+            .iter()
+            .enumerate()
+            .map(|(i, t)| InputVersion::parse(t, &Arc::from("stdin"), i + 1, true).unwrap())
+            .collect();
+        let map = OrderedVersionMap::from_inputs(inputs, &None, false, false, false);
+        let max =
+            BoundaryVersionResult::boundary_versions(&map, BoundaryKind::Max, false, false, false)
+                .unwrap();
+        let winner = max.versions[0].clone();
+        assert_eq!(winner.version, Version::parse("2.0.0").unwrap());
+        assert_eq!(winner.to_string(), "2.0.0");
+        assert_eq!(
+            winner
+                .clone()
+                .rendered(VersionRendering::Original)
+                .to_string(),
+            "V2.0.0"
+        );
+
+        // Tokens that round-trip aren't stored twice.
+        assert!(max.versions[0].original.is_some());
+        assert!(
+            map.inner[&Version::parse("1.10.0").unwrap()][0]
+                .original
+                .is_none()
+        );
+
+        let provenance = winner.rendered(VersionRendering::Provenance);
+        assert_eq!(provenance.to_string(), "V2.0.0\tstdin:2");
+        assert_eq!(
+            serde_json::to_string(&provenance).unwrap(),
+            r#"{"version":"2.0.0","original":"V2.0.0","source":"stdin","line":2}"#
+        );
+    }
+
+    #[test]
+    fn test_sort_check_and_merge() {
+        let parse = |source: &str, tokens: &[&str]| -> Vec<InputVersion> {
+            tokens
+                .iter()
+                .enumerate()
+                .map(|(i, t)| InputVersion::parse(t, &Arc::from(source), i + 1, false).unwrap())
+                .collect()
+        };
+
+        let input = parse("stdin", &["1.0.0", "1.2.0", "1.1.0", "2.0.0", "0.1.0"]);
+        let first = SortCheckResult::check(&input, SortCheck::First, false, false);
+        assert!(!first.sorted);
+        assert_eq!(first.disorders.len(), 1);
+        assert_eq!(
+            first.disorders[0].to_string(),
+            "stdin:3: disorder: 1.1.0 after 1.2.0"
+        );
+        let all = SortCheckResult::check(&input, SortCheck::All, false, false);
+        assert_eq!(all.disorders.len(), 2);
+        assert_eq!(all.disorders[1].line, 5);
+
+        let reversed = parse("stdin", &["2.0.0", "1.0.0", "1.0.0-rc.1"]);
+        assert!(SortCheckResult::check(&reversed, SortCheck::All, false, true).sorted);
+        assert!(!SortCheckResult::check(&reversed, SortCheck::All, false, false).sorted);
+
+        // Build metadata is only ordered when lexically sorting.
+        let ties = parse("stdin", &["1.0.0+b", "1.0.0+a"]);
+        assert!(SortCheckResult::check(&ties, SortCheck::First, false, false).sorted);
+        assert!(!SortCheckResult::check(&ties, SortCheck::First, true, false).sorted);
+
+        let merged = OrderedVersionMap::merge(
+            vec![
+                parse("a", &["0.1.0", "1.0.0", "1.1.0"]),
+                parse("b", &["0.5.0", "1.0.0+build", "3.0.0"]),
+            ],
+            &None,
+            true,
+            false,
+            false,
+        )
+        .unwrap();
+        let keys: Vec<String> = merged.inner.keys().map(|k| k.to_string()).collect();
+        assert_eq!(keys, ["0.1.0", "0.5.0", "1.0.0", "1.1.0", "3.0.0"]);
+        assert!(merged.potentially_ambiguous);
+
+        let unsorted = OrderedVersionMap::merge(
+            vec![parse("a", &["1.0.0"]), parse("b", &["2.0.0", "1.0.0"])],
+            &None,
+            false,
+            false,
+            false,
+        );
+        assert!(unsorted.is_err());
+    }
+
     #[test]
     fn test_ordered_version_map_stable() {
-        let mut versions: Vec<Version> = ["1.0.0-alpha", "1.0.0", "2.0.0"]
+        let versions: Vec<Version> = ["1.0.0-alpha", "1.0.0", "2.0.0"]
             .iter()
             .map(|s| Version::parse(s).unwrap())
             .collect();
-        let map = OrderedVersionMap::new(&mut versions, &None, false, false, true);
+        let map = OrderedVersionMap::new(versions, &None, false, false, true);
         assert_eq!(map.inner.len(), 2);
         for key in map.inner.keys() {
             assert!(key.pre.is_empty());
         }
     }
 
+    #[test]
+    fn test_uniq() {
+        let versions: Vec<Version> = ["1.2.3+a", "1.2.3", "1.2.4", "0.1.0", "1.2.3+b", "0.1.9"]
+            .iter()
+            .map(|s| Version::parse(s).unwrap())
+            .collect();
+
+        let test = UniqResult::uniq(&versions, UniqBy::Precedence, UniqKeep::First, true);
+        let emitted: Vec<String> = test
+            .versions
+            .iter()
+            .map(|e| e.version.to_string())
+            .collect();
+        assert_eq!(emitted, vec!["1.2.3+a", "1.2.4", "0.1.0", "0.1.9"]);
+        assert_eq!(test.versions[0].count, Some(3));
+
+        let test = UniqResult::uniq(&versions, UniqBy::Exact, UniqKeep::First, false);
+        assert_eq!(test.versions.len(), 6);
+        assert!(test.versions.iter().all(|e| e.count.is_none()));
+
+        let test = UniqResult::uniq(&versions, UniqBy::Minor, UniqKeep::Last, false);
+        assert_eq!(test.versions[0].version.to_string(), "1.2.3+b");
+        assert_eq!(test.versions[1].version.to_string(), "0.1.9");
+
+        let test = UniqResult::uniq(&versions, UniqBy::Compat, UniqKeep::Max, false);
+        assert_eq!(test.versions.len(), 2);
+        assert_eq!(test.versions[0].version.to_string(), "1.2.4");
+
+        // Precedence ties keep the earliest occurrence.
+        let test = UniqResult::uniq(&versions, UniqBy::Major, UniqKeep::Min, true);
+        assert_eq!(test.versions[0].version.to_string(), "1.2.3+a");
+        assert_eq!(test.versions[0].count, Some(4));
+
+        // Display Coverage
+        let _ = format!("{}", test);
+    }
+
+    #[test]
+    fn test_gaps() {
+        let versions: Vec<Version> = [
+            "1.2.0",
+            "1.2.1",
+            "1.2.3+bm",
+            "1.3.7",
+            "1.5.0",
+            "2.0.0-rc.1",
+            "2.0.0-rc.3",
+            "2.0.0-rc.3.x",
+            "2.0.0-beta.9",
+            "4.0.0",
+        ]
+        .iter()
+        .map(|s| Version::parse(s).unwrap())
+        .collect();
+        let map = OrderedVersionMap::new(versions, &None, false, false, false);
+        let test = GapsResult::gaps(&map, true);
+
+        let kinds: Vec<GapKind> = test.gaps.iter().map(|g| g.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                GapKind::Patch,
+                GapKind::Minor,
+                GapKind::PreRelease,
+                GapKind::Major
+            ]
+        );
+        assert_eq!(test.gaps[0].first_missing.to_string(), "1.2.2");
+        assert_eq!(test.gaps[1].after.to_string(), "1.3.7");
+        assert_eq!(test.gaps[1].first_missing.to_string(), "1.4.0");
+        assert_eq!(test.gaps[2].first_missing.to_string(), "2.0.0-rc.2");
+        assert_eq!(test.gaps[3].after.to_string(), "2.0.0-rc.3.x");
+        assert_eq!(test.gaps[3].first_missing.to_string(), "3.0.0");
+        assert_eq!(test.report(), ExitCode::FAILURE);
+
+        let versions: Vec<Version> = ["1.0.0-1", "1.0.0-4", "1.0.0"]
+            .iter()
+            .map(|s| Version::parse(s).unwrap())
+            .collect();
+        let map = OrderedVersionMap::new(versions.clone(), &None, false, false, false);
+        let test = GapsResult::gaps(&map, false);
+        assert_eq!(test.gaps.len(), 1);
+        assert_eq!(test.gaps[0].first_missing.to_string(), "1.0.0-2");
+        assert_eq!(test.gaps[0].last_missing.to_string(), "1.0.0-3");
+        assert_eq!(test.gaps[0].missing_count, 2);
+
+        // No gaps once pre-releases are excluded.
+        let map = OrderedVersionMap::new(versions, &None, false, false, true);
+        let test = GapsResult::gaps(&map, true);
+        assert!(test.gaps.is_empty());
+        assert_eq!(test.report(), ExitCode::SUCCESS);
+    }
+
+    #[test]
+    fn test_keyed_lines_list() {
+        let lines: Vec<String> = [
+            "nginx:1.10.0",
+            "nginx:latest",
+            "nginx:1.2.0",
+            "nginx:1.2.0-rc.1",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let key = LineKey::Field {
+            index: 2,
+            separator: Some(":".to_string()),
+        };
+
+        let test = KeyedLinesList::new(
+            lines.clone(),
+            &key,
+            UnkeyedLines::Last,
+            &None,
+            false,
+            false,
+            false,
+        );
+        assert_eq!(
+            test.lines,
+            vec![
+                "nginx:1.2.0-rc.1",
+                "nginx:1.2.0",
+                "nginx:1.10.0",
+                "nginx:latest"
+            ]
+        );
+        assert!(!test.potentially_ambiguous);
+
+        let test = KeyedLinesList::new(
+            lines.clone(),
+            &key,
+            UnkeyedLines::First,
+            &None,
+            false,
+            true,
+            true,
+        );
+        assert_eq!(
+            test.lines,
+            vec!["nginx:latest", "nginx:1.10.0", "nginx:1.2.0"]
+        );
+
+        let test = KeyedLinesList::new(
+            lines,
+            &key,
+            UnkeyedLines::Drop,
+            &Some(VersionReq::parse(">=1.3").unwrap()),
+            false,
+            false,
+            false,
+        );
+        assert_eq!(test.lines, vec!["nginx:1.10.0"]);
+
+        let key = LineKey::Regex(Regex::new(r"-(?P<version>[0-9]+\.[0-9]+\.[0-9]+)\.tar").unwrap());
+        assert_eq!(
+            key.version("tool-1.4.2.tar.gz"),
+            Some(Version::new(1, 4, 2))
+        );
+        assert_eq!(key.version("tool-latest.tar.gz"), None);
+
+        let key = LineKey::Field {
+            index: 1,
+            separator: None,
+        };
+        assert_eq!(key.version("  1.0.0  x"), Some(Version::new(1, 0, 0)));
+
+        // Display Coverage
+        let _ = format!("{}", test);
+    }
+
+    #[test]
+    fn test_versioned_files_result() {
+        let paths: Vec<PathBuf> = [
+            "dist/mytool-1.4.2-x86_64-linux.tar.gz",
+            "dist/mytool-1.4.2-aarch64-linux.tar.gz",
+            "dist/mytool-1.10.0-rc.1-x86_64-linux.tar.gz",
+            "dist/mytool-1.3.0+b1-x86_64-linux.tar.gz",
+            "dist/mytool-1.3.0+b2-x86_64-linux.tar.gz",
+            "dist/README.md",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+        let key = LineKey::from_template("mytool-{version}-*-linux.tar.gz").unwrap();
+
+        let max = VersionedFilesResult::new(
+            paths.clone(),
+            &key,
+            FilesPick::Max,
+            &None,
+            false,
+            false,
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(max.files.len(), 1);
+        assert_eq!(max.files[0].version.to_string(), "1.10.0-rc.1");
+
+        // Every file of the same release is emitted, and is not ambiguous.
+        let stable = VersionedFilesResult::new(
+            paths.clone(),
+            &key,
+            FilesPick::Max,
+            &None,
+            false,
+            false,
+            true,
+            false,
+        )
+        .unwrap();
+        assert_eq!(stable.files.len(), 2);
+        assert!(!stable.potentially_ambiguous);
+
+        let min = VersionedFilesResult::new(
+            paths.clone(),
+            &key,
+            FilesPick::Min,
+            &None,
+            false,
+            false,
+            false,
+            false,
+        );
+        assert!(min.is_err());
+
+        let min = VersionedFilesResult::new(
+            paths.clone(),
+            &key,
+            FilesPick::Min,
+            &None,
+            true,
+            false,
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            min.files[0].path,
+            "dist/mytool-1.3.0+b1-x86_64-linux.tar.gz"
+        );
+        assert!(min.lexical_tiebreak_used);
+
+        let all = VersionedFilesResult::new(
+            paths,
+            &key,
+            FilesPick::All,
+            &Some(VersionReq::parse(">=1.4").unwrap()),
+            false,
+            false,
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(all.files.len(), 2);
+
+        assert!(LineKey::from_template("mytool.tar.gz").is_err());
+        assert!(LineKey::from_template("{version}-{version}").is_err());
+        let key = LineKey::from_template("v{version}?.zip").unwrap();
+        assert_eq!(key.version("v1.2.3a.zip"), Some(Version::new(1, 2, 3)));
+        assert_eq!(key.version("xv1.2.3a.zip"), None);
+
+        // Display Coverage
+        let _ = format!("{}", all);
+    }
+
+    #[test]
+    fn test_diff() {
+        let left: Vec<Version> = ["1.0.0", "1.1.0+a", "2.0.0", "1.0.0"]
+            .iter()
+            .map(|s| Version::parse(s).unwrap())
+            .collect();
+        let right: Vec<Version> = ["2.0.0", "1.1.0+b", "3.0.0"]
+            .iter()
+            .map(|s| Version::parse(s).unwrap())
+            .collect();
+
+        let test = DiffResult::diff(&left, &right, DiffBy::Exact);
+        assert_eq!(test.only_left.len(), 2);
+        assert_eq!(test.only_right.len(), 2);
+        assert_eq!(test.both, vec![Version::parse("2.0.0").unwrap()]);
+        assert!(test.differs());
+
+        let test = DiffResult::diff(&left, &right, DiffBy::Precedence);
+        assert_eq!(test.only_left, vec![Version::parse("1.0.0").unwrap()]);
+        assert_eq!(test.only_right, vec![Version::parse("3.0.0").unwrap()]);
+        assert_eq!(
+            test.both,
+            vec![
+                Version::parse("1.1.0").unwrap(),
+                Version::parse("2.0.0").unwrap()
+            ]
+        );
+
+        let test = DiffResult::diff(&left, &left, DiffBy::Exact);
+        assert!(!test.differs());
+        assert_eq!(test.report(), ExitCode::SUCCESS);
+
+        // Precedence order, with build metadata breaking ties.
+        let unordered: Vec<Version> = ["1.0.0+b", "1.0.0-rc.10", "1.0.0-rc.9", "1.0.0+a", "1.0.0"]
+            .iter()
+            .map(|s| Version::parse(s).unwrap())
+            .collect();
+        let test = DiffResult::diff(&unordered, &[], DiffBy::Exact);
+        assert_eq!(
+            test.only_left
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["1.0.0-rc.9", "1.0.0-rc.10", "1.0.0", "1.0.0+a", "1.0.0+b"]
+        );
+
+        // Display Coverage
+        let _ = format!("{}", DiffResult::diff(&left, &right, DiffBy::Exact));
+    }
+
     use proptest::prelude::*;
     use proptest_semver::*;
     use std::collections::HashMap;
@@ -1362,10 +4974,9 @@ mod tests {
             allow_ambiguous: bool,
             kind_max: bool,
             filter in arb_optional_version_req(0.5, 2),
-            mut versions in arb_vec_versions(16),
+            versions in arb_vec_versions(16),
         ) {
-            let map = OrderedVersionMap::new(
-                &mut versions,
+            let map = OrderedVersionMap::new(versions,
                 &filter,
                 lexical_sorting,
                 reverse,
@@ -1392,7 +5003,7 @@ mod tests {
                 let filtered: Vec<Version> = map
                     .inner
                     .values()
-                    .flat_map(|group| group.iter().cloned())
+                    .flat_map(|group| group.iter().map(|i| i.version.clone()))
                     .collect();
                 let ambiguous = prop_boundary_ambiguous(&filtered, kind_max);
                 let expected_key = prop_boundary_precedence_key(&filtered, kind_max);
@@ -1404,24 +5015,123 @@ mod tests {
                     if allow_ambiguous && ambiguous {
                         prop_assert!(ok.versions.len() > 1);
                         for v in &ok.versions {
-                            prop_assert_eq!(prop_version_without_build(v), expected_key.clone());
+                            prop_assert_eq!(
+                                prop_version_without_build(&v.version),
+                                expected_key.clone()
+                            );
                         }
                     } else if lexical_sorting && ambiguous {
                         prop_assert_eq!(ok.versions.len(), 1);
                         prop_assert_eq!(
-                            ok.versions[0].clone(),
+                            ok.versions[0].version.clone(),
                             prop_expected_lexical_pick(&filtered, kind_max, reverse)
                         );
                     } else {
                         prop_assert_eq!(ok.versions.len(), 1);
                         prop_assert_eq!(
-                            prop_version_without_build(&ok.versions[0]),
+                            prop_version_without_build(&ok.versions[0].version),
                             expected_key
                         );
                     }
                 }
             }
         }
+
+        #[test]
+        fn prop_flat_versions_list(
+            reverse: bool,
+            stable: bool,
+            filter in arb_optional_version_req(0.5, 2),
+            versions in arb_vec_versions(32),
+        ) {
+            let inputs: Vec<InputVersion> = versions
+                .iter()
+                .enumerate()
+                .map(|(i, v)| InputVersion::parse(&v.to_string(), &Arc::from("input"), i + 1, false).unwrap())
+                .collect();
+
+            // Lexical sorting, so neither shuffles ties.
+            let flat = FlatVersionsList::new(inputs.clone(), &filter, true, reverse, stable);
+            let mut map = OrderedVersionMap::from_inputs(inputs, &filter, true, reverse, stable);
+            prop_assert!(flat == FlatVersionsList::from(&mut map));
+        }
+
+        #[test]
+        fn prop_sort_check_and_merge(
+            reverse: bool,
+            lexical_sorting: bool,
+            versions in arb_vec_versions(16),
+            split in 0usize..=16,
+        ) {
+            let inputs: Vec<InputVersion> = versions
+                .iter()
+                .enumerate()
+                .map(|(i, v)| InputVersion::parse(&v.to_string(), &Arc::from("input"), i + 1, false).unwrap())
+                .collect();
+            let (left, right) = inputs.split_at(split.min(inputs.len()));
+            let sorted = |inputs: &[InputVersion]| {
+                let mut map = OrderedVersionMap::from_inputs(
+                    inputs.to_vec(),
+                    &None,
+                    lexical_sorting,
+                    reverse,
+                    false,
+                );
+                FlatVersionsList::from(&mut map).versions
+            };
+            let (left, right) = (sorted(left), sorted(right));
+
+            for half in [&left, &right] {
+                let check = SortCheckResult::check(half, SortCheck::All, lexical_sorting, reverse);
+                prop_assert!(check.sorted);
+            }
+
+            let merged = OrderedVersionMap::merge(
+                vec![left, right],
+                &None,
+                lexical_sorting,
+                reverse,
+                false,
+            )
+            .expect("sorted inputs merge");
+            let expected = OrderedVersionMap::from_inputs(
+                inputs.clone(),
+                &None,
+                lexical_sorting,
+                reverse,
+                false,
+            );
+            prop_assert_eq!(
+                merged.inner.keys().collect::<Vec<_>>(),
+                expected.inner.keys().collect::<Vec<_>>()
+            );
+            prop_assert_eq!(
+                merged.inner.values().map(Vec::len).sum::<usize>(),
+                inputs.len()
+            );
+        }
+
+        #[test]
+        fn prop_precedence_rules(a in arb_version(), b in arb_version()) {
+            for rules in PrecedenceRules::EACH {
+                prop_assert_eq!(rules.cmp(&a, &b), rules.cmp(&b, &a).reverse());
+                prop_assert_eq!(rules.cmp(&a, &a), Ordering::Equal);
+            }
+            if a.cmp_precedence(&b).is_ne() {
+                prop_assert_eq!(PrecedenceRules::Cargo.cmp(&a, &b), a.cmp_precedence(&b));
+            }
+        }
+
+        #[test]
+        fn prop_precedence_trace(a in arb_version(), b in arb_version()) {
+            let trace = PrecedenceTrace::new(&a, &b);
+            prop_assert_eq!(Ordering::from(&trace.ordering), a.cmp_precedence(&b));
+            let last = trace.steps.last().expect("at least one step");
+            prop_assert_eq!(&last.ordering, &trace.ordering);
+            if trace.ordering != SerializableOrdering::Equal {
+                prop_assert_eq!(last.rule, trace.decided_by);
+            }
+        }
     }
 
     // ComparisonStatement
@@ -1467,4 +5177,799 @@ mod tests {
         // Display Coverage
         let _ = format!("{}", test);
     }
+
+    #[test]
+    fn test_precedence_trace() {
+        let cases = [
+            (
+                "1.0.0-rc.10",
+                "1.0.0-rc.9",
+                Ordering::Greater,
+                SpecRule::NumericIdentifiers,
+            ),
+            (
+                "1.0.0-alpha",
+                "1.0.0",
+                Ordering::Less,
+                SpecRule::PreReleaseLower,
+            ),
+            (
+                "1.0.0-alpha.1",
+                "1.0.0-alpha",
+                Ordering::Greater,
+                SpecRule::MoreIdentifiers,
+            ),
+            (
+                "1.0.0-1",
+                "1.0.0-a",
+                Ordering::Less,
+                SpecRule::NumericBeforeAscii,
+            ),
+            (
+                "1.0.0-beta",
+                "1.0.0-alpha",
+                Ordering::Greater,
+                SpecRule::AsciiIdentifiers,
+            ),
+            (
+                "2.0.0",
+                "1.9.9",
+                Ordering::Greater,
+                SpecRule::NumericVersionCore,
+            ),
+            (
+                "1.0.0+a",
+                "1.0.0+b",
+                Ordering::Equal,
+                SpecRule::BuildMetadataIgnored,
+            ),
+            ("1.0.0", "1.0.0", Ordering::Equal, SpecRule::PrecedenceOrder),
+            (
+                "1.0.0-99999999999999999999999",
+                "1.0.0-100000000000000000000000",
+                Ordering::Less,
+                SpecRule::NumericIdentifiers,
+            ),
+        ];
+        for (a, b, ordering, rule) in cases {
+            let trace =
+                PrecedenceTrace::new(&Version::parse(a).unwrap(), &Version::parse(b).unwrap());
+            assert_eq!(trace.ordering, ordering.into(), "{a} {b}");
+            assert_eq!(trace.decided_by, rule, "{a} {b}");
+        }
+
+        let trace = PrecedenceTrace::new(
+            &Version::parse("1.0.0-rc.10").unwrap(),
+            &Version::parse("1.0.0-rc.9").unwrap(),
+        );
+        assert_eq!(trace.steps.len(), 6);
+        assert_eq!(trace.steps[5].a_kind, Some(SegmentType::Numeric));
+        assert_eq!(
+            trace.steps[5].to_string(),
+            "§11.4.1 pre-release identifier 2: 10 (Numeric) > 9 (Numeric)"
+        );
+    }
+
+    #[test]
+    fn test_comparison_statement_exit_schemes() {
+        let report = |a: &str, b: &str, exit| {
+            ComparisonStatement::new(&Version::parse(a).unwrap(), &Version::parse(b).unwrap())
+                .exit_with(exit)
+                .report()
+        };
+
+        assert_eq!(report("1.0.0", "1.0.0+a", CompareExit::ThreeWay), 0.into());
+        assert_eq!(report("1.0.0", "2.0.0", CompareExit::ThreeWay), 1.into());
+        assert_eq!(report("2.0.0", "1.0.0", CompareExit::ThreeWay), 2.into());
+        assert_eq!(report("1.0.0", "1.0.0+a", CompareExit::Dual), 110.into());
+
+        let relations = [
+            (Relation::Lt, [true, false, false]),
+            (Relation::Le, [true, true, false]),
+            (Relation::Eq, [false, true, false]),
+            (Relation::Ne, [true, false, true]),
+            (Relation::Ge, [false, true, true]),
+            (Relation::Gt, [false, false, true]),
+        ];
+        for (relation, expected) in relations {
+            for ((a, b), holds) in [("1.0.0", "2.0.0"), ("1.0.0", "1.0.0+a"), ("2.0.0", "1.0.0")]
+                .into_iter()
+                .zip(expected)
+            {
+                let test = ComparisonStatement::new(
+                    &Version::parse(a).unwrap(),
+                    &Version::parse(b).unwrap(),
+                )
+                .exit_with(CompareExit::Test(relation));
+                assert_eq!(
+                    test.relation.as_ref().map(|test| test.holds),
+                    Some(holds),
+                    "{a} {relation} {b}"
+                );
+                let expected = if holds {
+                    ExitCode::SUCCESS
+                } else {
+                    ExitCode::FAILURE
+                };
+                assert_eq!(test.report(), expected, "{a} {relation} {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_distance_result() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let releases = OrderedVersionMap::new(
+            [
+                "1.2.3",
+                "1.2.4",
+                "1.3.0",
+                "1.4.0-rc.1",
+                "1.4.0",
+                "1.4.0+b",
+                "1.5.0",
+            ]
+            .iter()
+            .map(|s| v(s))
+            .collect(),
+            &None,
+            false,
+            false,
+            false,
+        );
+        assert_eq!(releases.highest(), Some(&v("1.5.0")));
+
+        let cases = [
+            ("1.2.3", "1.5.0", DistanceDirection::Behind, (0, 3, 0), 5),
+            ("1.5.0", "1.2.3", DistanceDirection::Ahead, (0, 3, 0), 5),
+            ("1.2.3", "1.2.4", DistanceDirection::Behind, (0, 0, 1), 1),
+            ("0.9.9", "1.4.0", DistanceDirection::Behind, (1, 4, 0), 5),
+            (
+                "1.4.0-rc.1",
+                "1.4.0+b",
+                DistanceDirection::Behind,
+                (0, 0, 0),
+                1,
+            ),
+            ("1.4.0", "1.4.0+b", DistanceDirection::Level, (0, 0, 0), 0),
+        ];
+        for (from, to, direction, (major, minor, patch), between) in cases {
+            let result = DistanceResult::distance(&v(from), &v(to), Some(&releases)).unwrap();
+            assert_eq!(result.direction, direction, "{from} {to}");
+            assert_eq!(
+                (result.major, result.minor, result.patch),
+                (major, minor, patch),
+                "{from} {to}"
+            );
+            assert_eq!(result.releases_between, Some(between), "{from} {to}");
+        }
+
+        let result =
+            DistanceResult::distance(&v("0.0.0"), &v(&format!("{}.0.0", u64::MAX)), None).unwrap();
+        assert_eq!(result.major, u64::MAX);
+        assert_eq!(result.releases_between, None);
+        let _ = result.to_string();
+    }
+
+    #[test]
+    fn test_next_version_from_commits() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let bumps = CommitBumps::new(vec![]);
+        let cases = [
+            ("1.2.3", "fix: a\nchore: b\n", "1.2.4", BumpLevel::Patch, 1),
+            (
+                "1.2.3",
+                "fix: a\nfeat: b\nfeat: c\n",
+                "1.3.0",
+                BumpLevel::Minor,
+                2,
+            ),
+            (
+                "1.2.3",
+                "fix: a\n\nBREAKING CHANGE: b\n",
+                "2.0.0",
+                BumpLevel::Major,
+                1,
+            ),
+            ("0.2.3", "feat!: a\nfeat: b\n", "0.3.0", BumpLevel::Minor, 1),
+            ("1.2.3-rc.1+b", "fix: a\n", "1.2.3", BumpLevel::Patch, 1),
+            ("1.2.3", "docs: a\nmerged\n", "1.2.3", BumpLevel::None, 0),
+            ("1.2.3", "", "1.2.3", BumpLevel::None, 0),
+        ];
+        for (base, messages, expected, bump, drivers) in cases {
+            let result = NextVersionResult::from_commits(
+                &v(base),
+                ConventionalCommit::parse_all(messages),
+                &bumps,
+                false,
+            )
+            .unwrap();
+            assert_eq!(result.version, v(expected), "{base} {messages:?}");
+            assert_eq!(result.bump, bump.into(), "{base} {messages:?}");
+            assert_eq!(result.commits.len(), drivers, "{base} {messages:?}");
+            assert_eq!(result.to_string(), format!("{expected}\n"));
+        }
+
+        let overflow = NextVersionResult::from_commits(
+            &v(&format!("{}.0.0", u64::MAX)),
+            ConventionalCommit::parse_all("feat!: a"),
+            &bumps,
+            false,
+        );
+        assert!(overflow.is_err());
+    }
+
+    #[test]
+    fn test_next_version() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let cases = [
+            ("1.2.3", NextLevel::Patch, false, "1.2.4"),
+            ("1.2.3", NextLevel::Minor, false, "1.3.0"),
+            ("1.2.3+b", NextLevel::Major, false, "2.0.0"),
+            ("1.2.3", NextLevel::Release, false, "1.2.4"),
+            ("1.2.4-rc.1", NextLevel::Patch, false, "1.2.4"),
+            ("1.3.0-rc.2", NextLevel::Patch, false, "1.3.0"),
+            ("1.3.0-rc.2", NextLevel::Minor, false, "1.3.0"),
+            ("1.3.1-rc.2", NextLevel::Minor, false, "1.4.0"),
+            ("1.3.0-rc.2", NextLevel::Major, false, "2.0.0"),
+            ("2.0.0-rc.1", NextLevel::Major, false, "2.0.0"),
+            ("1.3.0-rc.2+b", NextLevel::Release, false, "1.3.0"),
+            ("0.3.1", NextLevel::Major, false, "0.4.0"),
+            ("0.3.1", NextLevel::Major, true, "1.0.0"),
+            ("0.0.0-rc.1", NextLevel::Major, true, "1.0.0"),
+            ("0.0.0-rc.1", NextLevel::Major, false, "0.0.0"),
+            ("0.3.1", NextLevel::Minor, false, "0.4.0"),
+            ("1.2.3-rc.1", NextLevel::None, false, "1.2.3-rc.1"),
+        ];
+        for (base, level, leave, expected) in cases {
+            let result = NextVersionResult::next(&v(base), level, leave).unwrap();
+            assert_eq!(result.version, v(expected), "{base} {level}");
+            assert!(result.version.cmp_precedence(&v(base)).is_ge());
+            assert!(!result.decisions.is_empty());
+        }
+
+        let max = u64::MAX;
+        for level in [NextLevel::Patch, NextLevel::Minor, NextLevel::Major] {
+            assert!(
+                NextVersionResult::next(&v(&format!("{max}.{max}.{max}")), level, false).is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_guard_result() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let published = OrderedVersionMap::new(
+            ["1.2.0", "1.2.1", "1.3.0", "1.3.1+a", "2.0.0-rc.1"]
+                .iter()
+                .map(|s| v(s))
+                .collect(),
+            &None,
+            false,
+            false,
+            false,
+        );
+        let failed = |candidate: &str| {
+            GuardResult::guard(&v(candidate), &published)
+                .checks
+                .into_iter()
+                .filter(|check| !check.passed)
+                .map(|check| check.rule)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(failed("1.3.2"), []);
+        assert_eq!(failed("1.2.2"), []);
+        assert_eq!(failed("1.4.0-rc.1"), []);
+        assert_eq!(failed("2.0.0-rc.2"), []);
+        assert_eq!(failed("2.0.0"), []);
+        assert_eq!(
+            failed("1.3.1+b"),
+            [GuardRule::NewerThanLine, GuardRule::UniquePrecedence]
+        );
+        assert_eq!(failed("1.3.3"), [GuardRule::NoSkippedNumbers]);
+        assert_eq!(failed("1.5.0"), [GuardRule::NoSkippedNumbers]);
+        assert_eq!(failed("3.0.0"), [GuardRule::NoSkippedNumbers]);
+        assert_eq!(
+            failed("1.3.0-rc.1"),
+            [GuardRule::NewerThanLine, GuardRule::NotAlreadyReleased]
+        );
+
+        let nothing = OrderedVersionMap::new(Vec::new(), &None, false, false, false);
+        assert!(GuardResult::guard(&v("0.1.0"), &nothing).passed);
+    }
+
+    #[test]
+    fn test_next_avoid_existing() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let avoid = |result: NextVersionResult, published: &[&str]| {
+            let published: Vec<Version> = published.iter().map(|s| v(s)).collect();
+            result
+                .avoid_existing(&published, false)
+                .map(|result| (result.version.to_string(), result.skipped.len()))
+        };
+
+        let next = |base: &str, level| NextVersionResult::next(&v(base), level, false).unwrap();
+        assert_eq!(
+            avoid(next("1.2.3", NextLevel::Patch), &[]).unwrap(),
+            ("1.2.4".to_string(), 0)
+        );
+        assert_eq!(
+            avoid(
+                next("1.2.3", NextLevel::Patch),
+                &["1.2.4", "1.2.5", "1.2.7"]
+            )
+            .unwrap(),
+            ("1.2.8".to_string(), 3)
+        );
+        assert_eq!(
+            avoid(next("1.2.3", NextLevel::Release), &["1.2.4+b"]).unwrap(),
+            ("1.2.5".to_string(), 1)
+        );
+
+        // Nothing calls for a bump: a published pre-release counts up.
+        let none = |base: &str| {
+            NextVersionResult::from_commits(
+                &v(base),
+                Vec::new(),
+                &CommitBumps::new(Vec::new()),
+                false,
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            avoid(none("1.3.0-rc.2"), &["1.3.0-rc.2", "1.3.0-rc.4"]).unwrap(),
+            ("1.3.0-rc.5".to_string(), 2)
+        );
+        assert!(avoid(none("1.3.0"), &["1.3.0"]).is_err());
+        assert!(avoid(none("1.3.0-rc.2"), &["1.3.0"]).is_err());
+    }
+
+    #[test]
+    fn test_next_snapshot() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let snapshot = |versions: &[&str], level, id| {
+            let versions: Vec<Version> = versions.iter().map(|s| v(s)).collect();
+            NextVersionResult::snapshot(&versions, level, id, "20261018", false)
+                .map(|result| result.version.to_string())
+        };
+
+        let releases = ["1.4.2", "1.0.0", "1.5.0-rc.1", "0.9.0"];
+        assert_eq!(
+            snapshot(&releases, NextLevel::Minor, "nightly").unwrap(),
+            "1.5.0-nightly.20261018.0"
+        );
+        assert_eq!(
+            snapshot(&releases, NextLevel::Patch, "snapshot").unwrap(),
+            "1.4.3-snapshot.20261018.0"
+        );
+        assert_eq!(
+            snapshot(
+                &[
+                    "1.4.2",
+                    "1.5.0-nightly.20261017.9",
+                    "1.5.0-nightly.20261018.3",
+                    "1.5.0-nightly.20261018.1",
+                    "1.4.3-nightly.20261018.7",
+                ],
+                NextLevel::Minor,
+                "nightly"
+            )
+            .unwrap(),
+            "1.5.0-nightly.20261018.4"
+        );
+
+        // Never older than a snapshot already published.
+        assert!(
+            snapshot(
+                &["1.4.2", "1.5.0-nightly.20261019.0"],
+                NextLevel::Minor,
+                "nightly"
+            )
+            .is_err()
+        );
+        assert!(snapshot(&["1.5.0-rc.1"], NextLevel::Minor, "nightly").is_err());
+
+        // A base tied on precedence is reported, as `max` would.
+        let versions: Vec<Version> = ["1.4.2+b", "1.4.2+a", "1.0.0"]
+            .iter()
+            .map(|s| v(s))
+            .collect();
+        let result =
+            NextVersionResult::snapshot(&versions, NextLevel::Minor, "nightly", "20261018", false)
+                .unwrap();
+        assert_eq!(result.version, v("1.5.0-nightly.20261018.0"));
+        assert_eq!(result.base, v("1.4.2+a"));
+        assert_eq!(result.ambiguous_bases, [v("1.4.2+a"), v("1.4.2+b")]);
+        assert!(
+            NextVersionResult::snapshot(
+                &versions[2..],
+                NextLevel::Minor,
+                "nightly",
+                "20261018",
+                false
+            )
+            .unwrap()
+            .ambiguous_bases
+            .is_empty()
+        );
+        assert!(
+            snapshot(
+                &["1.4.2", "1.5.0-nightly.20261018.18446744073709551615"],
+                NextLevel::Minor,
+                "nightly"
+            )
+            .is_err()
+        );
+        assert_eq!(
+            snapshot(
+                &["1.4.2", "1.5.0-nightly.build.20261018.0"],
+                NextLevel::Minor,
+                "nightly.build"
+            )
+            .unwrap(),
+            "1.5.0-nightly.build.20261018.1"
+        );
+        // `build` has precedence over any date, so these would be older.
+        assert!(
+            snapshot(
+                &["1.4.2", "1.5.0-nightly.build.20261018.0"],
+                NextLevel::Minor,
+                "nightly"
+            )
+            .is_err()
+        );
+        assert!(snapshot(&["1.4.2"], NextLevel::Minor, "a_b").is_err());
+    }
+
+    #[test]
+    fn test_expand_result() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let listed = |result: ExpandResult| {
+            result
+                .versions
+                .iter()
+                .map(|version| version.to_string())
+                .collect::<Vec<_>>()
+        };
+        let range = |s: &str| s.parse::<VersionRange>().unwrap();
+        let limit = |s: &str| s.parse::<LineLimit>().unwrap();
+
+        assert!("1.2.0".parse::<VersionRange>().is_err());
+        assert!("1.4.0..1.2.0".parse::<VersionRange>().is_err());
+        assert!("1.2.0-rc.1..1.4.0".parse::<VersionRange>().is_err());
+        assert!("1.2".parse::<LineLimit>().is_err());
+        assert!("1.x=3".parse::<LineLimit>().is_err());
+        assert_eq!(
+            limit("1.2=7"),
+            LineLimit {
+                major: 1,
+                minor: Some(2),
+                max: 7
+            }
+        );
+
+        let result = ExpandResult::expand(
+            &range("1.2.1..1.4.1"),
+            ExpandStep::Patch,
+            &[limit("1.2=2"), limit("1.3=0")],
+            None,
+            &None,
+        )
+        .unwrap();
+        assert_eq!(
+            listed(result),
+            ["1.2.1", "1.2.2", "1.3.0", "1.4.0", "1.4.1"]
+        );
+
+        let result = ExpandResult::expand(
+            &range("1.2.1..2.1.3"),
+            ExpandStep::Minor,
+            &[limit("1=3")],
+            None,
+            &None,
+        )
+        .unwrap();
+        assert_eq!(listed(result), ["1.2.1", "1.3.0", "2.0.0", "2.1.0"]);
+
+        // A missing limit is an error, not a guess.
+        assert!(
+            ExpandResult::expand(&range("1.2.0..1.3.0"), ExpandStep::Patch, &[], None, &None)
+                .is_err()
+        );
+
+        let releases = OrderedVersionMap::new(
+            ["1.2.0", "1.2.1", "1.3.0", "1.3.1", "2.0.0"]
+                .iter()
+                .map(|s| v(s))
+                .collect(),
+            &None,
+            false,
+            false,
+            false,
+        );
+        let result = ExpandResult::expand(
+            &range("1.2.0..2.0.1"),
+            ExpandStep::Patch,
+            &[limit("1.3=2")],
+            Some(&releases),
+            &Some(VersionReq::parse("<2.0.1").unwrap()),
+        )
+        .unwrap();
+        assert_eq!(
+            listed(result),
+            ["1.2.0", "1.2.1", "1.3.0", "1.3.1", "1.3.2", "2.0.0"]
+        );
+
+        let result = ExpandResult::published(&releases);
+        assert_eq!(
+            listed(result),
+            ["1.2.0", "1.2.1", "1.3.0", "1.3.1", "2.0.0"]
+        );
+
+        // Neither end of the integer range may overflow.
+        let max = u64::MAX;
+        let result = ExpandResult::expand(
+            &range(&format!("{max}.{max}.{}..{max}.{max}.{max}", max - 1)),
+            ExpandStep::Patch,
+            &[],
+            None,
+            &None,
+        )
+        .unwrap();
+        assert_eq!(result.versions.len(), 2);
+
+        // Too many versions is an error, not an allocation failure.
+        assert!(
+            ExpandResult::expand(
+                &range("1.0.0..1.0.100000000000"),
+                ExpandStep::Patch,
+                &[],
+                None,
+                &None
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_precedence_rules() {
+        let cases = [
+            (
+                "1.0.0+a",
+                "1.0.0",
+                [
+                    Ordering::Equal,
+                    Ordering::Greater,
+                    Ordering::Greater,
+                    Ordering::Greater,
+                ],
+            ),
+            (
+                "1.0.0+01",
+                "1.0.0+1",
+                [
+                    Ordering::Equal,
+                    Ordering::Greater,
+                    Ordering::Equal,
+                    Ordering::Less,
+                ],
+            ),
+            (
+                "1.0.0+01.b",
+                "1.0.0+1.a",
+                [
+                    Ordering::Equal,
+                    Ordering::Greater,
+                    Ordering::Equal,
+                    Ordering::Less,
+                ],
+            ),
+            (
+                "1.10.0",
+                "1.9.0",
+                [
+                    Ordering::Greater,
+                    Ordering::Greater,
+                    Ordering::Greater,
+                    Ordering::Less,
+                ],
+            ),
+            (
+                "1.0.0-rc.1",
+                "1.0.0",
+                [
+                    Ordering::Less,
+                    Ordering::Less,
+                    Ordering::Less,
+                    Ordering::Greater,
+                ],
+            ),
+            (
+                "1.0.0-1",
+                "1.0.0-a",
+                [
+                    Ordering::Less,
+                    Ordering::Less,
+                    Ordering::Less,
+                    Ordering::Less,
+                ],
+            ),
+            (
+                "1.0.0-9007199254740993",
+                "1.0.0-9007199254740992",
+                [
+                    Ordering::Greater,
+                    Ordering::Greater,
+                    Ordering::Equal,
+                    Ordering::Greater,
+                ],
+            ),
+        ];
+        for (a, b, expected) in cases {
+            let (a, b) = (Version::parse(a).unwrap(), Version::parse(b).unwrap());
+            for (rules, ordering) in PrecedenceRules::EACH.into_iter().zip(expected) {
+                assert_eq!(rules.cmp(&a, &b), ordering, "{a} {rules} {b}");
+            }
+        }
+
+        let test = ComparisonStatement::new(
+            &Version::parse("1.0.0+a").unwrap(),
+            &Version::parse("1.0.0").unwrap(),
+        );
+        let one = ComparisonStatement::new(
+            &Version::parse("1.0.0+a").unwrap(),
+            &Version::parse("1.0.0").unwrap(),
+        )
+        .under_rules(
+            &Version::parse("1.0.0+a").unwrap(),
+            &Version::parse("1.0.0").unwrap(),
+            PrecedenceRules::Npm,
+        );
+        assert_eq!(one.rules.len(), 1);
+        assert_eq!(one.rules_disagree, None);
+        let all = test.under_rules(
+            &Version::parse("1.0.0+a").unwrap(),
+            &Version::parse("1.0.0").unwrap(),
+            PrecedenceRules::All,
+        );
+        assert_eq!(all.rules.len(), 4);
+        assert_eq!(all.rules_disagree, Some(true));
+    }
+
+    #[test]
+    fn test_rules_sort_result() {
+        let source: Arc<str> = Arc::from("argument");
+        let inputs: Vec<InputVersion> = ["1.10.0", "1.0.0+b", "1.9.0", "1.0.0"]
+            .iter()
+            .enumerate()
+            .map(|(i, v)| InputVersion::parse(v, &source, i + 1, false).unwrap())
+            .collect();
+        let sorted = |rules, reverse| {
+            RulesSortResult::new(inputs.clone(), rules, reverse).orderings[0]
+                .versions
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        // Stable: 1.0.0+b stays ahead of 1.0.0 under spec.
+        assert_eq!(
+            sorted(PrecedenceRules::Spec, false),
+            ["1.0.0+b", "1.0.0", "1.9.0", "1.10.0"]
+        );
+        assert_eq!(
+            sorted(PrecedenceRules::Cargo, false),
+            ["1.0.0", "1.0.0+b", "1.9.0", "1.10.0"]
+        );
+        assert_eq!(
+            sorted(PrecedenceRules::Lexical, true),
+            ["1.9.0", "1.10.0", "1.0.0+b", "1.0.0"]
+        );
+
+        let all = RulesSortResult::new(inputs.clone(), PrecedenceRules::All, false);
+        assert_eq!(all.orderings.len(), 4);
+        assert_eq!(all.rules_disagree, Some(true));
+        let text = all.to_string();
+        assert!(text.starts_with("spec     cargo    npm      lexical\n"));
+        assert!(text.contains("\n1.9.0    1.9.0    1.9.0    1.10.0   *\n"));
+
+        let agree = RulesSortResult::new(inputs[2..3].to_vec(), PrecedenceRules::All, false);
+        assert_eq!(agree.rules_disagree, Some(false));
+        assert_eq!(
+            RulesSortResult::new(inputs, PrecedenceRules::Npm, false).rules_disagree,
+            None
+        );
+    }
+
+    #[test]
+    fn test_comparison_matrix() {
+        let versions: Vec<Version> = ["1.0.0", "1.0.0+b", "2.0.0", "1.0.0+a"]
+            .iter()
+            .map(|v| Version::parse(v).unwrap())
+            .collect();
+        let matrix = ComparisonMatrix::new(versions.clone(), MatrixFormat::Table);
+
+        assert_eq!(matrix.rows.len(), 4);
+        for (i, row) in matrix.rows.iter().enumerate() {
+            assert_eq!(row.version, versions[i]);
+            for j in 0..versions.len() {
+                assert_eq!(
+                    Ordering::from(&row.semantic[j]),
+                    versions[i].cmp_precedence(&versions[j])
+                );
+                assert_eq!(
+                    Ordering::from(&row.lexical[j]),
+                    versions[i].cmp(&versions[j])
+                );
+            }
+        }
+
+        // Every pair of 1.0.0 variants, once each.
+        let pairs: Vec<(String, String, u8)> = matrix
+            .inconsistencies
+            .iter()
+            .map(|i| (i.a.to_string(), i.b.to_string(), i.exit_code))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("1.0.0".to_string(), "1.0.0+b".to_string(), 110),
+                ("1.0.0".to_string(), "1.0.0+a".to_string(), 110),
+                ("1.0.0+b".to_string(), "1.0.0+a".to_string(), 112),
+            ]
+        );
+
+        let table = matrix.to_string();
+        assert!(table.starts_with("         1.0.0  1.0.0+b  2.0.0  1.0.0+a\n"));
+        assert!(table.contains("\n1.0.0    ==     =<*      <<     =<*\n"));
+
+        let csv = ComparisonMatrix::new(versions, MatrixFormat::Csv).to_string();
+        assert_eq!(csv.lines().next(), Some(",1.0.0,1.0.0+b,2.0.0,1.0.0+a"));
+        assert!(!csv.contains("Inconsistencies"));
+    }
+
+    #[test]
+    fn test_comparison_statement_diff() {
+        let cases = [
+            ("1.2.3", "2.0.0", VersionDiff::Major, false),
+            ("1.2.3", "1.3.0", VersionDiff::Minor, true),
+            ("1.2.3", "1.2.4", VersionDiff::Patch, true),
+            ("1.2.3", "1.2.2", VersionDiff::Patch, false),
+            ("0.2.3", "0.3.0", VersionDiff::Minor, false),
+            ("0.2.3", "0.2.4", VersionDiff::Patch, true),
+            ("0.0.3", "0.0.4", VersionDiff::Patch, false),
+            ("1.2.3-rc.1", "1.2.3-rc.2", VersionDiff::Prerelease, true),
+            ("1.2.3-rc.1", "1.2.3", VersionDiff::Prerelease, true),
+            ("1.2.3", "1.2.4-rc.1", VersionDiff::Patch, false),
+            ("1.2.3+a", "1.2.3+b", VersionDiff::Build, true),
+            ("1.2.3", "1.2.3", VersionDiff::None, true),
+        ];
+        for (a, b, diff, compatible) in cases {
+            let test =
+                ComparisonStatement::new(&Version::parse(a).unwrap(), &Version::parse(b).unwrap());
+            assert_eq!(test.diff, diff, "{a} {b}");
+            assert_eq!(test.cargo_compatible, compatible, "{a} {b}");
+        }
+
+        let a = Version::parse("1.2.3").unwrap();
+        let b = Version::parse("1.3.0").unwrap();
+        let fail_on = |component| {
+            ComparisonStatement::new(&a, &b)
+                .exit_with(CompareExit::FailOnDiff(component))
+                .report()
+        };
+        assert_eq!(fail_on(VersionDiff::Major), ExitCode::SUCCESS);
+        assert_eq!(fail_on(VersionDiff::Minor), ExitCode::FAILURE);
+        assert_eq!(fail_on(VersionDiff::Patch), ExitCode::FAILURE);
+
+        let incompatible = |a: &str, b: &str| {
+            ComparisonStatement::new(&Version::parse(a).unwrap(), &Version::parse(b).unwrap())
+                .exit_with(CompareExit::Incompatible)
+                .report()
+        };
+        assert_eq!(incompatible("1.2.3", "1.3.0"), ExitCode::SUCCESS);
+        assert_eq!(incompatible("0.4.2", "0.5.0"), ExitCode::FAILURE);
+        assert_eq!(incompatible("1.2.3", "2.0.0"), ExitCode::FAILURE);
+    }
 }
@@ -0,0 +1,386 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+//!
+//! Sorting more versions than we want to hold in memory, for
+//! `sort --flatten --buffer-size`.
+//!
+//! Versions are buffered until the budget is reached, then sorted and written
+//! to a temporary "run" file. The runs are merged while the output is being
+//! written, so neither the input nor the output is ever held in full.
+use std::{
+    cell::Cell,
+    cmp::Ordering,
+    error::Error,
+    fmt,
+    fs::{self, DirBuilder, File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Lines, Write},
+    path::PathBuf,
+    process::{ExitCode, Termination},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering as AtomicOrdering},
+    },
+};
+
+use rand::{prelude::*, rngs::ThreadRng};
+use semver::{Version, VersionReq};
+use serde::{Serialize, Serializer, ser::Error as _, ser::SerializeStruct};
+
+use crate::misc::SubcommandResult;
+use crate::results::{FlatVersionsList, InputVersion, VersionRendering, retain_wanted};
+
+static RUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Temporary run files, removed when dropped.
+///
+/// They live in a directory only we can use, created on the first spill, so
+/// other users of the temp dir can't predict or replace them.
+#[derive(Default)]
+struct Runs {
+    dir: Option<PathBuf>,
+    files: Vec<PathBuf>,
+}
+
+impl Runs {
+    fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Create a new, empty run file.
+    fn create(&mut self) -> Result<File, Box<dyn Error>> {
+        let dir = match &self.dir {
+            Some(dir) => dir,
+            None => self.dir.insert(private_dir()?),
+        };
+        let path = dir.join(format!(
+            "{}.run",
+            RUN_COUNTER.fetch_add(1, AtomicOrdering::SeqCst)
+        ));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .inspect_err(|e| {
+                eprintln!("unable to create {}: {e}", path.display());
+            })?;
+        self.files.push(path);
+        Ok(file)
+    }
+}
+
+impl Drop for Runs {
+    fn drop(&mut self) {
+        // Nothing useful to do if these fail, it's in the temp dir.
+        for run in &self.files {
+            let _ = fs::remove_file(run);
+        }
+        if let Some(dir) = &self.dir {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+}
+
+/// A freshly created directory under the temp dir, readable only by us
+/// where the platform allows it. Never reuses an existing one.
+fn private_dir() -> Result<PathBuf, Box<dyn Error>> {
+    let mut builder = DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+
+    let mut rng = rand::rng();
+    loop {
+        let path = std::env::temp_dir().join(format!(
+            "sem-tool-{}-{:016x}",
+            std::process::id(),
+            rng.random::<u64>()
+        ));
+        match builder.create(&path) {
+            Ok(()) => return Ok(path),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                eprintln!("unable to create {}: {e}", path.display());
+                return Err(e.into());
+            }
+        }
+    }
+}
+
+/// How versions are read back from runs, and ordered.
+struct RunFormat {
+    source: Arc<str>,
+    allow_v_prefix: bool,
+    render: VersionRendering,
+    lexical_sorting: bool,
+    reverse: bool,
+}
+
+impl RunFormat {
+    /// The same total order `sort` starts from, before ties are shuffled.
+    fn cmp(&self, a: &Version, b: &Version) -> Ordering {
+        if self.reverse { b.cmp(a) } else { a.cmp(b) }
+    }
+}
+
+/// Buffers versions, spilling sorted runs to disk past `buffer_size`.
+pub(crate) struct Spiller {
+    buffer: Vec<InputVersion>,
+    buffer_size: usize,
+    runs: Runs,
+    filter: Option<VersionReq>,
+    stable: bool,
+    format: RunFormat,
+}
+
+impl Spiller {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        source: Arc<str>,
+        buffer_size: usize,
+        filter: Option<VersionReq>,
+        lexical_sorting: bool,
+        reverse: bool,
+        stable: bool,
+        allow_v_prefix: bool,
+        render: VersionRendering,
+    ) -> Self {
+        Self {
+            buffer: Vec::new(),
+            buffer_size,
+            runs: Runs::default(),
+            filter,
+            stable,
+            format: RunFormat {
+                source,
+                allow_v_prefix,
+                render,
+                lexical_sorting,
+                reverse,
+            },
+        }
+    }
+
+    pub(crate) fn push(&mut self, mut batch: Vec<InputVersion>) -> Result<(), Box<dyn Error>> {
+        retain_wanted(&mut batch, &self.filter, self.stable);
+        self.buffer.append(&mut batch);
+        if self.buffer.len() >= self.buffer_size {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Write the buffer out as a sorted run, one `line<TAB>original` per line.
+    fn spill(&mut self) -> Result<(), Box<dyn Error>> {
+        let format = &self.format;
+        self.buffer
+            .sort_by(|a, b| format.cmp(a.version(), b.version()));
+
+        let mut run = BufWriter::new(self.runs.create()?);
+        for input in self.buffer.drain(..) {
+            writeln!(run, "{}\t{}", input.line(), input.original())?;
+        }
+        run.flush()?;
+        Ok(())
+    }
+
+    /// Finish reading: without any runs spilled, this is just an in-memory
+    /// [FlatVersionsList].
+    pub(crate) fn finish(mut self) -> Result<SubcommandResult, Box<dyn Error>> {
+        if self.runs.is_empty() {
+            return Ok(FlatVersionsList::new(
+                self.buffer,
+                &None,
+                self.format.lexical_sorting,
+                self.format.reverse,
+                false,
+            )
+            .into());
+        }
+
+        if !self.buffer.is_empty() {
+            self.spill()?;
+        }
+        Ok(SpilledVersionsList {
+            runs: self.runs,
+            format: self.format,
+            potentially_ambiguous: Cell::new(false),
+        }
+        .into())
+    }
+}
+
+/// A flat list of versions, merged from sorted runs as it is written.
+///
+/// Serializes the same as [FlatVersionsList]. `potentially_ambiguous` is only
+/// known once every version has been written, which is why it comes last.
+pub(crate) struct SpilledVersionsList {
+    runs: Runs,
+    format: RunFormat,
+    potentially_ambiguous: Cell<bool>,
+}
+
+impl SpilledVersionsList {
+    fn merged(&self) -> Result<RunMerger<'_>, Box<dyn Error>> {
+        let mut runs = Vec::with_capacity(self.runs.files.len());
+        for path in &self.runs.files {
+            let mut lines = BufReader::new(File::open(path)?).lines();
+            let head = read_run_line(&mut lines, &self.format)?;
+            runs.push((lines, head));
+        }
+
+        Ok(RunMerger {
+            list: self,
+            runs,
+            pending: None,
+            rng: rand::rng(),
+        })
+    }
+}
+
+fn read_run_line(
+    lines: &mut Lines<BufReader<File>>,
+    format: &RunFormat,
+) -> Result<Option<InputVersion>, Box<dyn Error>> {
+    let Some(line) = lines.next().transpose()? else {
+        return Ok(None);
+    };
+    let (line_no, original) = line
+        .split_once('\t')
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed run file"))?;
+    let input = InputVersion::parse(
+        original,
+        &format.source,
+        line_no.parse()?,
+        format.allow_v_prefix,
+    )?;
+    Ok(Some(input.rendered(format.render)))
+}
+
+/// Merges runs, yielding each group of versions sharing precedence, ordered
+/// the way `sort` orders them.
+struct RunMerger<'a> {
+    list: &'a SpilledVersionsList,
+    runs: Vec<(Lines<BufReader<File>>, Option<InputVersion>)>,
+    pending: Option<InputVersion>,
+    rng: ThreadRng,
+}
+
+impl RunMerger<'_> {
+    fn next_version(&mut self) -> Result<Option<InputVersion>, Box<dyn Error>> {
+        let format = &self.list.format;
+        // Few runs are expected, so a scan of their heads will do.
+        let Some(index) = self
+            .runs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (_, head))| head.as_ref().map(|head| (index, head)))
+            .min_by(|(_, a), (_, b)| format.cmp(a.version(), b.version()))
+            .map(|(index, _)| index)
+        else {
+            return Ok(None);
+        };
+
+        let (lines, head) = &mut self.runs[index];
+        let next = read_run_line(lines, format)?;
+        Ok(std::mem::replace(head, next))
+    }
+
+    fn next_group(&mut self) -> Result<Option<Vec<InputVersion>>, Box<dyn Error>> {
+        let Some(first) = self
+            .pending
+            .take()
+            .map_or_else(|| self.next_version(), |v| Ok(Some(v)))?
+        else {
+            return Ok(None);
+        };
+
+        let mut group = vec![first];
+        while let Some(next) = self.next_version()? {
+            if next.version().cmp_precedence(group[0].version()).is_eq() {
+                group.push(next);
+            } else {
+                self.pending = Some(next);
+                break;
+            }
+        }
+
+        if group.len() > 1 {
+            self.list.potentially_ambiguous.set(true);
+            if !self.list.format.lexical_sorting {
+                // Same as in memory: reverse is silently ignored here.
+                group.shuffle(&mut self.rng);
+            }
+        }
+        Ok(Some(group))
+    }
+}
+
+impl Iterator for RunMerger<'_> {
+    type Item = Result<Vec<InputVersion>, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_group().transpose()
+    }
+}
+
+/// The versions of a [SpilledVersionsList], serialized lazily.
+struct MergedVersions<'a>(&'a SpilledVersionsList);
+
+impl Serialize for MergedVersions<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let merged = self.0.merged().map_err(S::Error::custom)?;
+        let mut error = None;
+        let versions = merged
+            .map_while(|group| group.map_err(|e| error = Some(e)).ok())
+            .flatten();
+        let result = serializer.collect_seq(versions);
+        match error {
+            Some(e) => Err(S::Error::custom(e)),
+            None => result,
+        }
+    }
+}
+
+impl Serialize for SpilledVersionsList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("FlatVersionsList", 2)?;
+        state.serialize_field("versions", &MergedVersions(self))?;
+        state.serialize_field("potentially_ambiguous", &self.potentially_ambiguous.get())?;
+        state.end()
+    }
+}
+
+impl fmt::Display for SpilledVersionsList {
+    // fmt::Error carries nothing, so this is the only place to say why.
+    #[allow(clippy::print_in_format_impl)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let log = |e: Box<dyn Error>| {
+            eprintln!("unable to merge sorted runs: {e}");
+            fmt::Error
+        };
+
+        for group in self.merged().map_err(log)? {
+            for version in group.map_err(log)? {
+                writeln!(f, "{version}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Termination for SpilledVersionsList {
+    fn report(self) -> ExitCode {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,380 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+//!
+//! Stamping build metadata from a template, for `stamp`.
+//!
+//! A template is literal build metadata with `{placeholders}`:
+//!
+//! - `{sha}`, `{sha:7}`: the commit, from `--sha`, a CI variable, or the
+//!   `.git` directory above the working directory.
+//! - `{date}`, `{date:%Y%m%d%H%M}`: the UTC date (default `%Y%m%d`), from
+//!   `--timestamp`, `SOURCE_DATE_EPOCH`, or the clock.
+//! - `{env:NAME}`: an environment variable.
+//! - `{NAME}`: a `--value NAME=VALUE`.
+//!
+//! Values may hold anything, so they're sanitized to the identifier alphabet
+//! (`[0-9A-Za-z-]`, SemVer §10) by replacing anything else with `-`.
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    fmt, fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use semver::{BuildMetadata, Version};
+use serde::Serialize;
+
+use crate::misc::ApplicationError;
+
+/// CI variables holding the commit being built, checked in order.
+const SHA_VARIABLES: [&str; 3] = ["GITHUB_SHA", "CI_COMMIT_SHA", "GIT_COMMIT"];
+
+/// Where placeholder values come from, besides the environment.
+#[derive(Default)]
+pub(crate) struct StampSources {
+    pub(crate) sha: Option<String>,
+    pub(crate) timestamp: Option<u64>,
+    pub(crate) values: HashMap<String, String>,
+}
+
+impl StampSources {
+    fn sha(&self) -> Result<String, Box<dyn Error>> {
+        if let Some(sha) = &self.sha {
+            return Ok(sha.clone());
+        }
+        if let Some(sha) = SHA_VARIABLES
+            .iter()
+            .find_map(|name| env::var(name).ok().filter(|sha| !sha.is_empty()))
+        {
+            return Ok(sha);
+        }
+        let cwd = env::current_dir()?;
+        git_head(&cwd)?.ok_or_else(|| {
+            ApplicationError::FailedRequirementError {
+                err: format!(
+                    "no --sha, {} or .git directory to read a commit from",
+                    SHA_VARIABLES.join(", ")
+                ),
+            }
+            .into()
+        })
+    }
+}
+
+/// `explicit`, or `SOURCE_DATE_EPOCH`, or the clock, in seconds since the
+/// epoch.
+pub(crate) fn timestamp(explicit: Option<u64>) -> Result<u64, Box<dyn Error>> {
+    if let Some(timestamp) = explicit {
+        return Ok(timestamp);
+    }
+    // Reproducible builds set this to pin dates in build output.
+    if let Ok(epoch) = env::var("SOURCE_DATE_EPOCH") {
+        return epoch.trim().parse().map_err(|e| {
+            ApplicationError::InvalidArgument {
+                expected: "SOURCE_DATE_EPOCH to be seconds since the epoch".to_string(),
+                found: format!("{epoch}: {e}"),
+            }
+            .into()
+        });
+    }
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+}
+
+/// The commit `HEAD` points at, in the first `.git` at or above `start`.
+fn git_head(start: &Path) -> Result<Option<String>, Box<dyn Error>> {
+    let Some(git_dir) = start.ancestors().find_map(|dir| {
+        let dot_git = dir.join(".git");
+        if dot_git.is_dir() {
+            Some(dot_git)
+        } else {
+            // Worktrees and submodules point at their git directory.
+            let pointer = fs::read_to_string(&dot_git).ok()?;
+            Some(dir.join(pointer.trim().strip_prefix("gitdir:")?.trim()))
+        }
+    }) else {
+        return Ok(None);
+    };
+
+    let head = fs::read_to_string(git_dir.join("HEAD"))?;
+    let Some(reference) = head.trim().strip_prefix("ref:").map(str::trim) else {
+        return Ok(Some(head.trim().to_string()));
+    };
+    // Worktrees keep branches in the repository they were added to.
+    let common_dir = fs::read_to_string(git_dir.join("commondir"))
+        .map(|common| git_dir.join(common.trim()))
+        .unwrap_or_else(|_| git_dir.clone());
+    for dir in [&git_dir, &common_dir] {
+        if let Ok(sha) = fs::read_to_string(dir.join(reference)) {
+            return Ok(Some(sha.trim().to_string()));
+        }
+        let packed = fs::read_to_string(dir.join("packed-refs")).unwrap_or_default();
+        if let Some(sha) = packed.lines().find_map(|line| {
+            let (sha, name) = line.split_once(' ')?;
+            (name == reference).then(|| sha.to_string())
+        }) {
+            return Ok(Some(sha));
+        }
+    }
+    Err(format!(
+        "{} points at {reference}, which has no commit yet",
+        PathBuf::from(&git_dir).join("HEAD").display()
+    )
+    .into())
+}
+
+/// Days since 1970-01-01 as a proleptic Gregorian (year, month, day).
+///
+/// See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Format a UTC timestamp with a few `strftime` conversions.
+pub(crate) fn format_date(timestamp: u64, format: &str) -> Result<String, ApplicationError> {
+    let (year, month, day) = civil_from_days(timestamp / 86_400);
+    let seconds = timestamp % 86_400;
+    let mut formatted = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            formatted.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => formatted.push_str(&format!("{year:04}")),
+            Some('y') => formatted.push_str(&format!("{:02}", year % 100)),
+            Some('m') => formatted.push_str(&format!("{month:02}")),
+            Some('d') => formatted.push_str(&format!("{day:02}")),
+            Some('H') => formatted.push_str(&format!("{:02}", seconds / 3_600)),
+            Some('M') => formatted.push_str(&format!("{:02}", seconds / 60 % 60)),
+            Some('S') => formatted.push_str(&format!("{:02}", seconds % 60)),
+            Some('s') => formatted.push_str(&timestamp.to_string()),
+            other => {
+                return Err(ApplicationError::InvalidArgument {
+                    expected: "one of %Y, %y, %m, %d, %H, %M, %S or %s in a date format"
+                        .to_string(),
+                    found: format!("%{}", other.map(String::from).unwrap_or_default()),
+                });
+            }
+        }
+    }
+    Ok(formatted)
+}
+
+/// A placeholder value that had to be changed to fit in build metadata.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub(crate) struct Sanitized {
+    placeholder: String,
+    value: String,
+    sanitized: String,
+}
+
+/// Replace anything outside of the identifier alphabet with `-`.
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// A version stamped with build metadata rendered from a template.
+#[derive(Serialize, PartialEq, Debug)]
+pub(crate) struct StampResult {
+    version: Version,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    sanitized: Vec<Sanitized>,
+}
+
+impl StampResult {
+    /// Render `template` as the build metadata of `version`, or after its
+    /// existing build metadata if `append`.
+    pub(crate) fn stamp(
+        version: &Version,
+        template: &str,
+        sources: &StampSources,
+        append: bool,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut rendered = String::new();
+        let mut sanitized = Vec::new();
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            rendered.push_str(&rest[..open]);
+            let close =
+                rest[open..]
+                    .find('}')
+                    .ok_or_else(|| ApplicationError::InvalidArgument {
+                        expected: "a closing '}' in the template".to_string(),
+                        found: template.to_string(),
+                    })?
+                    + open;
+            let placeholder = &rest[open + 1..close];
+            let value = match placeholder.split_once(':') {
+                None if placeholder == "sha" => sources.sha()?,
+                Some(("sha", length)) => {
+                    let length: usize =
+                        length
+                            .parse()
+                            .map_err(|e| ApplicationError::InvalidArgument {
+                                expected: "a length in {sha:N}".to_string(),
+                                found: format!("{length}: {e}"),
+                            })?;
+                    sources.sha()?.chars().take(length).collect()
+                }
+                None if placeholder == "date" => {
+                    format_date(timestamp(sources.timestamp)?, "%Y%m%d")?
+                }
+                Some(("date", format)) => format_date(timestamp(sources.timestamp)?, format)?,
+                Some(("env", name)) => {
+                    env::var(name).map_err(|e| ApplicationError::InvalidArgument {
+                        expected: format!("the environment variable {name}"),
+                        found: e.to_string(),
+                    })?
+                }
+                None => sources.values.get(placeholder).cloned().ok_or_else(|| {
+                    ApplicationError::InvalidArgument {
+                        expected: format!(
+                            "{{{placeholder}}} to be sha, date, env:NAME, or given with --value"
+                        ),
+                        found: "nothing".to_string(),
+                    }
+                })?,
+                Some(_) => {
+                    return Err(ApplicationError::InvalidArgument {
+                        expected: "one of {sha:N}, {date:FORMAT} or {env:NAME}".to_string(),
+                        found: format!("{{{placeholder}}}"),
+                    }
+                    .into());
+                }
+            };
+            if value.is_empty() {
+                return Err(ApplicationError::FailedRequirementError {
+                    err: format!("{{{placeholder}}} is empty"),
+                }
+                .into());
+            }
+            let clean = sanitize(&value);
+            if clean != value {
+                sanitized.push(Sanitized {
+                    placeholder: format!("{{{placeholder}}}"),
+                    value,
+                    sanitized: clean.clone(),
+                });
+            }
+            rendered.push_str(&clean);
+            rest = &rest[close + 1..];
+        }
+        rendered.push_str(rest);
+
+        if append && !version.build.is_empty() {
+            rendered = format!("{}.{rendered}", version.build);
+        }
+        let build =
+            BuildMetadata::new(&rendered).map_err(|e| ApplicationError::InvalidArgument {
+                expected: format!("the template {template} to render build metadata"),
+                found: format!("{rendered}: {e}"),
+            })?;
+        let mut version = version.clone();
+        version.build = build;
+        Ok(Self { version, sanitized })
+    }
+}
+
+/// Only the version, so it can be captured by a script.
+impl fmt::Display for StampResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_date() {
+        // 2024-02-29T13:05:09Z
+        let leap_day = 1_709_211_909;
+        assert_eq!(
+            format_date(leap_day, "%Y%m%d.%H%M%S").unwrap(),
+            "20240229.130509"
+        );
+        assert_eq!(format_date(0, "%y-%m-%d %s").unwrap(), "70-01-01 0");
+        assert_eq!(format_date(951_782_400, "%Y%m%d").unwrap(), "20000229");
+        assert!(format_date(0, "%Q").is_err());
+        assert!(format_date(0, "%").is_err());
+    }
+
+    #[test]
+    fn test_stamp() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let sources = StampSources {
+            sha: Some("0123456789abcdef".to_string()),
+            timestamp: Some(1_709_211_909),
+            values: HashMap::from([("branch".to_string(), "feature/a_b".to_string())]),
+        };
+
+        let result = StampResult::stamp(&v("1.2.3"), "{sha:7}.{date}", &sources, false).unwrap();
+        assert_eq!(result.version, v("1.2.3+0123456.20240229"));
+        assert!(result.sanitized.is_empty());
+
+        let result =
+            StampResult::stamp(&v("1.2.3-rc.1+ci"), "{branch}.{sha}", &sources, true).unwrap();
+        assert_eq!(
+            result.version,
+            v("1.2.3-rc.1+ci.feature-a-b.0123456789abcdef")
+        );
+        assert_eq!(
+            result.sanitized,
+            [Sanitized {
+                placeholder: "{branch}".to_string(),
+                value: "feature/a_b".to_string(),
+                sanitized: "feature-a-b".to_string(),
+            }]
+        );
+
+        for template in [
+            "{sha",
+            "{nope}",
+            "{sha:x}",
+            "{date:%Q}",
+            "{sha:7}..{date}",
+            "{sha}_x",
+            "{what:ever}",
+            "{sha:0}",
+        ] {
+            assert!(
+                StampResult::stamp(&v("1.2.3"), template, &sources, false).is_err(),
+                "{template}"
+            );
+        }
+    }
+}
@@ -32,10 +32,10 @@ fn cli_all_sub_commands() {
         // All subcommands with no input, should fail, except those that do
         // something else reasonable.
         //
-        // 'sort' & 'generate' have behaviors that reasonably allow them to
-        // pass.
+        // 'sort', 'uniq', 'gaps' & 'generate' have behaviors that reasonably
+        // allow them to pass.
         match sub {
-            "sort" | "generate" => {
+            "sort" | "uniq" | "gaps" | "generate" => {
                 common_cmd().arg(sub).assert().success();
             }
             _ => {
@@ -42,6 +42,67 @@ fn cli_validate_basic_cases() {
         .success();
 }
 
+#[test]
+fn cli_bump_identifier_cases() {
+    for (args, expected) in [
+        (
+            vec!["1.0.0-beta.3.build.7", "--bump-pre-release", "3"],
+            "1.0.0-beta.3.build.8",
+        ),
+        (
+            vec!["1.0.0-beta.3.build.7", "--bump-pre-release", "build=2"],
+            "1.0.0-beta.3.build.9",
+        ),
+        (
+            vec!["1.0.0+ci.41", "--bump-build-metadata", "1"],
+            "1.0.0+ci.42",
+        ),
+        (
+            vec![
+                "1.0.0+ci.41",
+                "--bump-build-metadata",
+                "ci",
+                "--bump-minor",
+                "1",
+            ],
+            "1.1.0+ci.42",
+        ),
+    ] {
+        let assert = common_cmd()
+            .arg("-o")
+            .arg("text")
+            .arg(COMMAND_BUMP)
+            .args(&args)
+            .assert();
+        assert
+            .append_context(COMMAND_BUMP, format!("{args:?}"))
+            .stdout(expected)
+            .success();
+    }
+
+    for (args, context) in [
+        (
+            vec!["1.0.0-beta.3", "--bump-pre-release", "0"],
+            "not numeric",
+        ),
+        (
+            vec!["1.0.0-beta.3", "--bump-pre-release", "5"],
+            "no such index",
+        ),
+        (
+            vec!["1.0.0-beta.3", "--bump-pre-release", "rc"],
+            "no such label",
+        ),
+        (
+            vec!["1.0.0", "--bump-build-metadata", "0"],
+            "no build metadata",
+        ),
+    ] {
+        let assert = common_cmd().arg(COMMAND_BUMP).args(&args).assert();
+        assert.append_context(COMMAND_BUMP, context).failure();
+    }
+}
+
 proptest! {
     #![proptest_config(ProptestConfig {
         // Setting both fork and timeout is redundant since timeout implies
@@ -38,8 +38,303 @@ fn cli_compare_invalid_input() {
         .failure();
 }
 
-/// NOTE(canardleteer): Since these codes are considered unstable for now,
-///                     be prepared to make changes in here.
+#[test]
+fn cli_compare_fail_on_diff_cases() {
+    let cases = [
+        ("major", "1.2.3", "2.0.0", 1),
+        ("major", "1.2.3", "1.9.0", 0),
+        ("minor", "1.2.3", "1.9.0", 1),
+        ("patch", "1.2.3", "1.2.3-rc.1", 0),
+        ("prerelease", "1.2.3", "1.2.3-rc.1", 1),
+        ("build", "1.2.3+a", "1.2.3+b", 1),
+        ("build", "1.2.3", "1.2.3", 0),
+    ];
+    for (component, a, b, code) in cases {
+        let assert = common_cmd()
+            .arg(COMMAND_COMPARE)
+            .arg("--fail-on-diff")
+            .arg(component)
+            .arg(a)
+            .arg(b)
+            .assert();
+        assert
+            .append_context(
+                COMMAND_COMPARE,
+                format!("--fail-on-diff {component} {a} {b}"),
+            )
+            .code(code);
+    }
+
+    let assert = common_cmd()
+        .arg(COMMAND_COMPARE)
+        .arg("--fail-on-diff")
+        .arg("none")
+        .arg("1.2.3")
+        .arg("1.2.3")
+        .assert();
+    assert
+        .append_context(COMMAND_COMPARE, "none is not a component to fail on")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_COMPARE)
+        .arg("-e")
+        .arg("--fail-on-diff")
+        .arg("major")
+        .arg("1.2.3")
+        .arg("2.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_COMPARE, "conflicts with -e")
+        .code(2);
+}
+
+#[test]
+fn cli_compare_fail_if_incompatible_cases() {
+    let cases = [
+        ("1.2.3", "2.0.0", 1),
+        ("1.2.3", "1.9.0", 0),
+        ("0.4.2", "0.5.0", 1),
+        ("0.4.2", "0.4.3", 0),
+        ("0.0.3", "0.0.4", 1),
+        ("1.2.3", "1.2.2", 1),
+    ];
+    for (a, b, code) in cases {
+        let assert = common_cmd()
+            .arg(COMMAND_COMPARE)
+            .arg("--fail-if-incompatible")
+            .arg(a)
+            .arg(b)
+            .assert();
+        assert
+            .append_context(COMMAND_COMPARE, format!("--fail-if-incompatible {a} {b}"))
+            .code(code);
+    }
+
+    let assert = common_cmd()
+        .arg(COMMAND_COMPARE)
+        .arg("--fail-if-incompatible")
+        .arg("--fail-on-diff")
+        .arg("major")
+        .arg("1.2.3")
+        .arg("2.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_COMPARE, "conflicts with --fail-on-diff")
+        .code(2);
+}
+
+#[test]
+fn cli_compare_trace_cases() {
+    let cases = [
+        ("1.0.0-rc.10", "1.0.0-rc.9", "11.4.1"),
+        ("1.0.0-alpha", "1.0.0", "11.3"),
+        ("1.0.0+a", "1.0.0+b", "10"),
+    ];
+    for (a, b, rule) in cases {
+        let assert = common_cmd()
+            .args(["-o", "json", COMMAND_COMPARE, "--trace", a, b])
+            .assert();
+        let output = assert
+            .append_context(COMMAND_COMPARE, format!("--trace {a} {b}"))
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let value: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(value["trace"]["decided_by"], rule, "{a} {b}");
+    }
+
+    // Tracing doesn't change the exit status.
+    let assert = common_cmd()
+        .args([COMMAND_COMPARE, "-e", "--trace", "1.2.3", "4.5.6"])
+        .assert();
+    assert
+        .append_context(COMMAND_COMPARE, "--trace with -e")
+        .code(100);
+}
+
+#[test]
+fn cli_compare_matrix_cases() {
+    let assert = common_cmd()
+        .args([
+            "-o",
+            "text",
+            COMMAND_COMPARE,
+            "--matrix-format",
+            "csv",
+            "--matrix",
+        ])
+        .write_stdin("1.0.0\n1.0.0+a\n2.0.0\n")
+        .assert();
+    assert
+        .append_context(COMMAND_COMPARE, "--matrix from stdin")
+        .success()
+        .stdout(",1.0.0,1.0.0+a,2.0.0\n1.0.0,==,=<*,<<\n1.0.0+a,=>*,==,<<\n2.0.0,>>,>>,==\n");
+
+    let assert = common_cmd()
+        .args([
+            "-o",
+            "json",
+            COMMAND_COMPARE,
+            "--matrix",
+            "1.0.0",
+            "1.0.0+a",
+        ])
+        .assert();
+    let output = assert
+        .append_context(COMMAND_COMPARE, "--matrix json")
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let value: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(value["inconsistencies"][0]["exit_code"], 110);
+    assert_eq!(value["rows"][1]["lexical"][0], "Greater");
+
+    let assert = common_cmd()
+        .args([COMMAND_COMPARE, "--matrix-format", "csv", "1.0.0", "2.0.0"])
+        .assert();
+    assert
+        .append_context(COMMAND_COMPARE, "--matrix-format without --matrix")
+        .failure();
+
+    for args in [
+        vec![COMMAND_COMPARE, "--matrix", "1.0.0", "-e"],
+        vec![COMMAND_COMPARE, "1.0.0"],
+    ] {
+        let assert = common_cmd().args(&args).assert();
+        assert
+            .append_context(COMMAND_COMPARE, format!("invalid {args:?}"))
+            .code(2);
+    }
+}
+
+#[test]
+fn cli_compare_exit_scheme_cases() {
+    let cases = [
+        (vec!["--ge", "1.2.0", "1.1.9"], 0),
+        (vec!["--lt", "1.2.0", "1.1.9"], 1),
+        (vec!["--eq", "1.2.0", "1.2.0+build"], 0),
+        (
+            vec!["--exit-scheme", "test-v1", "--ne", "1.2.0", "1.2.0+build"],
+            1,
+        ),
+        (
+            vec!["--exit-scheme", "test", "--le", "1.2.0-rc.1", "1.2.0"],
+            0,
+        ),
+        (
+            vec!["--exit-scheme", "three-way-v1", "1.2.0", "1.2.0+build"],
+            0,
+        ),
+        (vec!["--exit-scheme", "three-way", "1.2.0", "2.0.0"], 1),
+        (vec!["--exit-scheme", "three-way-v1", "2.0.0", "1.2.0"], 2),
+        (
+            vec!["--exit-scheme", "dual-v1", "1.2.0", "1.2.0+build"],
+            110,
+        ),
+        (vec!["--exit-scheme", "dual", "1.2.3", "4.5.6"], 100),
+    ];
+    for (args, code) in cases {
+        let assert = common_cmd().arg(COMMAND_COMPARE).args(&args).assert();
+        assert
+            .append_context(COMMAND_COMPARE, format!("{args:?}"))
+            .code(code);
+    }
+
+    // Usage errors from clap.
+    for args in [
+        vec!["--ge", "--lt", "1.2.0", "1.1.9"],
+        vec!["-e", "--exit-scheme", "three-way", "1.2.0", "1.1.9"],
+        vec!["--fail-on-diff", "major", "--ge", "1.2.0", "1.1.9"],
+        vec!["--exit-scheme", "three-way-v2", "1.2.0", "1.1.9"],
+        vec!["--ne", "-s", "1.0.0", "1.0.0"],
+        vec!["--exit-scheme", "test-v1", "-s", "--lt", "1.0.0", "1.0.0+a"],
+        vec!["--exit-scheme", "dual", "-s", "1.0.0", "1.0.0+a"],
+    ] {
+        let assert = common_cmd().arg(COMMAND_COMPARE).args(&args).assert();
+        assert
+            .append_context(COMMAND_COMPARE, format!("{args:?}"))
+            .code(2);
+    }
+
+    // A relation only makes sense with the test scheme.
+    for args in [
+        vec!["--exit-scheme", "test-v1", "1.2.0", "1.1.9"],
+        vec!["--exit-scheme", "three-way", "--ge", "1.2.0", "1.1.9"],
+    ] {
+        let assert = common_cmd().arg(COMMAND_COMPARE).args(&args).assert();
+        assert
+            .append_context(COMMAND_COMPARE, format!("{args:?}"))
+            .code(1);
+    }
+}
+
+#[test]
+fn cli_compare_rules_cases() {
+    let output = common_cmd()
+        .args([
+            "-o",
+            "json",
+            COMMAND_COMPARE,
+            "--rules",
+            "all",
+            "1.0.0+01",
+            "1.0.0+1",
+        ])
+        .output()
+        .expect("run compare --rules all");
+    assert!(output.status.success());
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(value["rules_disagree"], true);
+    let orderings: Vec<(&str, &str)> = value["rules"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["rules"].as_str().unwrap(),
+                r["ordering"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        orderings,
+        [
+            ("spec", "Equal"),
+            ("cargo", "Greater"),
+            ("npm", "Equal"),
+            ("lexical", "Less")
+        ]
+    );
+
+    let output = common_cmd()
+        .args([
+            "-o",
+            "json",
+            COMMAND_COMPARE,
+            "--rules",
+            "npm",
+            "1.0.0",
+            "2.0.0",
+        ])
+        .output()
+        .expect("run compare --rules npm");
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(value["rules"][0]["ordering"], "Less");
+    assert!(value.get("rules_disagree").is_none());
+
+    let assert = common_cmd()
+        .args([COMMAND_COMPARE, "--rules", "all", "--matrix", "1.0.0"])
+        .assert();
+    assert
+        .append_context(COMMAND_COMPARE, "--rules with --matrix")
+        .code(2);
+}
+
+/// `-e` sets the codes of `--exit-scheme dual`, which follows the latest
+/// `dual-vN`; these cases pin them down.
 #[test]
 fn cli_compare_basic_cases() {
     let assert = common_cmd()
@@ -197,6 +492,29 @@ proptest! {
         }
     }
 
+    #[test]
+    fn prop_compare_relations(version_a in arb_version(), version_b in arb_version()) {
+        let ordering = version_a.cmp_precedence(&version_b);
+        for (flag, holds) in [
+            ("--lt", ordering.is_lt()),
+            ("--le", ordering.is_le()),
+            ("--eq", ordering.is_eq()),
+            ("--ne", ordering.is_ne()),
+            ("--ge", ordering.is_ge()),
+            ("--gt", ordering.is_gt()),
+        ] {
+            let assert = common_cmd()
+                .arg(COMMAND_COMPARE)
+                .arg(flag)
+                .arg(version_a.to_string())
+                .arg(version_b.to_string())
+                .assert();
+            assert
+                .append_context(COMMAND_COMPARE, "property test relation")
+                .code(if holds { 0 } else { 1 });
+        }
+    }
+
     #[test]
     fn prop_compare_semantic_exit_without_e(version_a in arb_version(), version_b in arb_version()) {
         let assert = common_cmd()
@@ -0,0 +1,168 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+use proptest::prelude::*;
+use proptest_semver::*;
+use semver::Version;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod common;
+use common::subcommands::*;
+
+use crate::common::common_cmd;
+
+const SHORT_GOOD_VERSIONS: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/example-data/short-good-versions.txt"
+);
+const MISC_BAD_VERSIONS: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/example-data/misc-bad-versions.txt"
+);
+
+#[test]
+fn cli_diff_invalid_input() {
+    let assert = common_cmd()
+        .arg(COMMAND_DIFF)
+        .arg(SHORT_GOOD_VERSIONS)
+        .assert();
+    assert.append_context(COMMAND_DIFF, "one source").code(2);
+
+    let assert = common_cmd().arg(COMMAND_DIFF).arg("-").arg("-").assert();
+    assert.append_context(COMMAND_DIFF, "both stdin").code(2);
+
+    let assert = common_cmd()
+        .arg(COMMAND_DIFF)
+        .arg(SHORT_GOOD_VERSIONS)
+        .arg(MISC_BAD_VERSIONS)
+        .assert();
+    // Trouble is 2, so it isn't mistaken for the sets differing (1).
+    assert.append_context(COMMAND_DIFF, "bad semver").code(2);
+
+    let assert = common_cmd()
+        .arg(COMMAND_DIFF)
+        .arg(SHORT_GOOD_VERSIONS)
+        .arg("does-not-exist.txt")
+        .assert();
+    assert.append_context(COMMAND_DIFF, "missing file").code(2);
+}
+
+#[test]
+fn cli_diff_basic_cases() {
+    let assert = common_cmd()
+        .arg(COMMAND_DIFF)
+        .arg(SHORT_GOOD_VERSIONS)
+        .arg(SHORT_GOOD_VERSIONS)
+        .assert();
+    assert.append_context(COMMAND_DIFF, "identical").success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_DIFF)
+        .arg(SHORT_GOOD_VERSIONS)
+        .arg("-")
+        .write_stdin("0.0.1\n0.0.2\n0.2.0\n0.3.0\n")
+        .assert();
+    assert
+        .append_context(COMMAND_DIFF, "exact")
+        .stdout(
+            "< 0.0.0-alpha.0+metadata\n\
+             < 1.0.0-rc-2+aaaaaa\n\
+             < 1.0.0-rc-2.0+aaa.0\n\
+             < 1.0.0-rc-2.0+dddddd\n\
+             < 99.99.0-rc1.0\n\
+             > 0.3.0\n\
+             = 0.0.1\n\
+             = 0.0.2\n\
+             = 0.2.0\n",
+        )
+        .code(1);
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_DIFF)
+        .arg("--by")
+        .arg("precedence")
+        .arg("-")
+        .arg(SHORT_GOOD_VERSIONS)
+        .write_stdin(
+            "0.0.0-alpha.0\n0.0.1+bm\n0.0.2\n0.2.0\n1.0.0-rc-2\n1.0.0-rc-2.0+x\n99.99.0-rc1.0\n",
+        )
+        .assert();
+    assert
+        .append_context(COMMAND_DIFF, "precedence, same set")
+        .success();
+}
+
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        fork: true,
+        cases: 256,
+        .. ProptestConfig::default()
+    })]
+    #[test]
+    fn prop_diff(left in arb_vec_versions(16), right in arb_vec_versions(16), precedence: bool) {
+        let path = std::env::temp_dir().join(format!(
+            "sem-tool-cli-diff-{}-{}.txt",
+            std::process::id(),
+            TEMP_FILE_COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        let right_lines: Vec<String> = right.iter().map(|v| v.to_string()).collect();
+        std::fs::write(&path, right_lines.join("\n")).expect("write temp file");
+
+        let mut cmd = common_cmd();
+        cmd.arg("-o").arg("json").arg(COMMAND_DIFF);
+        if precedence {
+            cmd.arg("--by").arg("precedence");
+        }
+        let left_lines: Vec<String> = left.iter().map(|v| v.to_string()).collect();
+        cmd.arg("-").arg(&path).write_stdin(left_lines.join("\n"));
+
+        let output = cmd.output().expect("run diff");
+        std::fs::remove_file(&path).expect("remove temp file");
+
+        let key = |v: &Version| {
+            if precedence {
+                Version {
+                    build: semver::BuildMetadata::EMPTY,
+                    ..v.clone()
+                }
+            } else {
+                v.clone()
+            }
+        };
+        let l: BTreeSet<Version> = left.iter().map(key).collect();
+        let r: BTreeSet<Version> = right.iter().map(key).collect();
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("json output");
+        let parse = |field: &str| -> Vec<Version> {
+            json[field]
+                .as_array()
+                .expect("array field")
+                .iter()
+                .map(|v| Version::parse(v.as_str().expect("string")).expect("valid semver"))
+                .collect()
+        };
+
+        prop_assert_eq!(parse("only_left"), l.difference(&r).cloned().collect::<Vec<_>>());
+        prop_assert_eq!(parse("only_right"), r.difference(&l).cloned().collect::<Vec<_>>());
+        prop_assert_eq!(parse("both"), l.intersection(&r).cloned().collect::<Vec<_>>());
+        prop_assert_eq!(output.status.success(), l == r);
+    }
+}
@@ -0,0 +1,171 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+use proptest::prelude::*;
+
+mod common;
+use common::subcommands::*;
+
+use crate::common::common_cmd;
+
+const RELEASES: &str = "1.2.3\n1.2.4\n1.3.0\n1.4.0-rc.1\n1.4.0\n1.4.0+b\n1.5.0\n1.5.1\n1.5.2\n";
+
+#[test]
+fn cli_distance_invalid_input() {
+    let assert = common_cmd()
+        .arg(COMMAND_DISTANCE)
+        .arg("a.b.c")
+        .arg("1.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_DISTANCE, "bad semver")
+        .failure();
+
+    let assert = common_cmd().arg(COMMAND_DISTANCE).arg("1.0.0").assert();
+    assert
+        .append_context(COMMAND_DISTANCE, "no TO and no --releases")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_DISTANCE)
+        .arg("--releases")
+        .arg("-")
+        .arg("1.0.0")
+        .write_stdin("")
+        .assert();
+    assert
+        .append_context(COMMAND_DISTANCE, "no releases to measure against")
+        .failure();
+}
+
+#[test]
+fn cli_distance_basic_cases() {
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_DISTANCE)
+        .arg("1.2.3")
+        .arg("1.5.2")
+        .assert();
+    assert
+        .append_context(COMMAND_DISTANCE, "minor behind")
+        .stdout("1.2.3 is behind 1.5.2 by 0 major, 3 minor, 2 patch\nDiffers in: minor\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_DISTANCE)
+        .arg("2.1.0")
+        .arg("1.9.4")
+        .assert();
+    assert
+        .append_context(COMMAND_DISTANCE, "major ahead")
+        .stdout("2.1.0 is ahead of 1.9.4 by 1 major, 1 minor, 0 patch\nDiffers in: major\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_DISTANCE)
+        .arg("--releases")
+        .arg("-")
+        .arg("1.2.3")
+        .write_stdin(RELEASES)
+        .assert();
+    assert
+        .append_context(COMMAND_DISTANCE, "against the latest release")
+        .stdout(
+            "1.2.3 is behind 1.5.2 by 0 major, 3 minor, 2 patch\nDiffers in: minor\n\
+             Published releases between: 7\n",
+        )
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("json")
+        .arg(COMMAND_DISTANCE)
+        .arg("--releases")
+        .arg("-")
+        .arg("--stable")
+        .arg("1.3.0")
+        .arg("1.4.0+b")
+        .write_stdin(RELEASES)
+        .assert();
+    let stdout = assert.get_output().stdout.clone();
+    assert
+        .append_context(COMMAND_DISTANCE, "stable releases between")
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+    assert_eq!(json["releases_between"], 1);
+    assert_eq!(json["direction"], "behind");
+    assert_eq!(json["diff"], "minor");
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("json")
+        .arg(COMMAND_DISTANCE)
+        .arg("1.0.0+a")
+        .arg("1.0.0+b")
+        .assert();
+    let stdout = assert.get_output().stdout.clone();
+    assert
+        .append_context(COMMAND_DISTANCE, "level, build metadata only")
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+    assert_eq!(json["direction"], "level");
+    assert_eq!(json["diff"], "build");
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        fork: true,
+        cases: 256,
+        .. ProptestConfig::default()
+    })]
+    // The newer version must be reachable from the older by its deltas.
+    #[test]
+    fn prop_distance_deltas(from in (0u64..4, 0u64..4, 0u64..4), to in (0u64..4, 0u64..4, 0u64..4)) {
+        let from_version = format!("{}.{}.{}", from.0, from.1, from.2);
+        let to_version = format!("{}.{}.{}", to.0, to.1, to.2);
+        let assert = common_cmd()
+            .arg("-o")
+            .arg("json")
+            .arg(COMMAND_DISTANCE)
+            .arg(&from_version)
+            .arg(&to_version)
+            .assert();
+        let stdout = assert.get_output().stdout.clone();
+        assert.append_context(COMMAND_DISTANCE, "prop test").success();
+
+        let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+        let delta = |name: &str| json[name].as_u64().expect("delta");
+        let (major, minor, patch) = (delta("major"), delta("minor"), delta("patch"));
+        let (older, newer) = if from <= to { (from, to) } else { (to, from) };
+        let reached = if major > 0 {
+            (older.0 + major, minor, patch)
+        } else if minor > 0 {
+            (older.0, older.1 + minor, patch)
+        } else {
+            (older.0, older.1, older.2 + patch)
+        };
+        prop_assert_eq!(reached, newer);
+        let direction = match from.cmp(&to) {
+            std::cmp::Ordering::Less => "behind",
+            std::cmp::Ordering::Equal => "level",
+            std::cmp::Ordering::Greater => "ahead",
+        };
+        prop_assert_eq!(json["direction"].as_str(), Some(direction));
+    }
+}
@@ -0,0 +1,136 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+use proptest::prelude::*;
+
+mod common;
+use common::subcommands::*;
+
+use crate::common::common_cmd;
+
+#[test]
+fn cli_eval_invalid_input() {
+    for (expression, context) in [
+        ("new >", "incomplete comparison"),
+        ("1.0.0 < 2.0.0 < 3.0.0", "chained comparison"),
+        ("1.0.0.major", "unknown component"),
+        ("1.0.0 ~ 1", "not a requirement"),
+        ("\"a\" < 1", "mismatched types"),
+        ("SEM_TOOL_TEST_UNDEFINED", "undefined variable"),
+    ] {
+        let assert = common_cmd().arg(COMMAND_EVAL).arg(expression).assert();
+        assert.append_context(COMMAND_EVAL, context).failure();
+    }
+
+    let assert = common_cmd()
+        .arg(COMMAND_EVAL)
+        .arg("--var")
+        .arg("no-equals")
+        .arg("true")
+        .assert();
+    assert
+        .append_context(COMMAND_EVAL, "malformed binding")
+        .failure();
+
+    let assert = common_cmd().arg(COMMAND_ASSERT).arg("1.0.0").assert();
+    assert
+        .append_context(COMMAND_ASSERT, "not a boolean")
+        .failure();
+}
+
+#[test]
+fn cli_eval_basic_cases() {
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_EVAL)
+        .arg("--var")
+        .arg("new=1.4.0+build.5")
+        .arg("new.build")
+        .assert();
+    assert
+        .append_context(COMMAND_EVAL, "component access")
+        .stdout("build.5\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_EVAL)
+        .arg("--list")
+        .arg("published=-")
+        .arg("max(published)")
+        .write_stdin("1.2.0\n1.10.0\n1.9.0\n")
+        .assert();
+    assert
+        .append_context(COMMAND_EVAL, "max of a list")
+        .stdout("1.10.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_ASSERT)
+        .arg("new ~ \">=1.2, <2\" && new > OLD_RELEASE")
+        .env("new", "1.4.0")
+        .env("OLD_RELEASE", "1.3.9")
+        .assert();
+    assert
+        .append_context(COMMAND_ASSERT, "variables from the environment")
+        .stdout("true\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("json")
+        .arg(COMMAND_ASSERT)
+        .arg("--var")
+        .arg("new=1.4.0")
+        .arg("--var")
+        .arg("old=1.4.0+other")
+        .arg("new ~ ^1 && new > old")
+        .assert();
+    let stdout = assert.get_output().stdout.clone();
+    assert
+        .append_context(COMMAND_ASSERT, "equal precedence is not greater")
+        .failure();
+    let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+    assert_eq!(json["value"], false);
+    assert_eq!(json["failed"], "new > old");
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        fork: true,
+        cases: 256,
+        .. ProptestConfig::default()
+    })]
+    // Comparisons must agree with `compare`'s precedence.
+    #[test]
+    fn prop_eval_comparisons(a in (0u64..3, 0u64..3, 0u64..3), b in (0u64..3, 0u64..3, 0u64..3)) {
+        let a_version = format!("{}.{}.{}", a.0, a.1, a.2);
+        let b_version = format!("{}.{}.{}", b.0, b.1, b.2);
+        for (op, holds) in [("<", a < b), ("==", a == b), (">=", a >= b)] {
+            let assert = common_cmd()
+                .arg(COMMAND_EVAL)
+                .arg(format!("{a_version} {op} {b_version}"))
+                .assert();
+            if holds {
+                assert.append_context(COMMAND_EVAL, "prop test").success();
+            } else {
+                assert.append_context(COMMAND_EVAL, "prop test").code(1);
+            }
+        }
+    }
+}
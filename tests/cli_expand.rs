@@ -0,0 +1,166 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+use proptest::prelude::*;
+
+mod common;
+use common::subcommands::*;
+
+use crate::common::common_cmd;
+
+const RELEASES: &str = "1.2.0\n1.2.1\n1.3.0\n1.3.1\n1.4.0-rc.1\n1.4.0\n2.0.0\n";
+
+#[test]
+fn cli_expand_invalid_input() {
+    for (range, context) in [
+        ("1.2.0", "not a range"),
+        ("1.4.0..1.2.0", "backwards range"),
+        ("1.2.0-rc.1..1.3.0", "pre-release bound"),
+        ("a.b.c..1.3.0", "bad semver"),
+    ] {
+        let assert = common_cmd().arg(COMMAND_EXPAND).arg(range).assert();
+        assert.append_context(COMMAND_EXPAND, context).failure();
+    }
+
+    let assert = common_cmd()
+        .arg(COMMAND_EXPAND)
+        .arg("1.2.0..1.3.0")
+        .assert();
+    assert
+        .append_context(COMMAND_EXPAND, "no limit for the 1.2 line")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_EXPAND)
+        .arg("--limit")
+        .arg("1.2")
+        .arg("1.2.0..1.3.0")
+        .assert();
+    assert
+        .append_context(COMMAND_EXPAND, "malformed limit")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_EXPAND)
+        .arg("--filter")
+        .arg(">1")
+        .assert();
+    assert
+        .append_context(COMMAND_EXPAND, "no range and no releases")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_EXPAND)
+        .arg("1.0.0..1.0.100000000000")
+        .assert();
+    assert
+        .append_context(COMMAND_EXPAND, "too many versions")
+        .failure();
+}
+
+#[test]
+fn cli_expand_basic_cases() {
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_EXPAND)
+        .arg("--releases")
+        .arg("-")
+        .arg("1.2.0..2.0.1")
+        .write_stdin(RELEASES)
+        .assert();
+    assert
+        .append_context(COMMAND_EXPAND, "limits from releases")
+        .stdout("1.2.0\n1.2.1\n1.3.0\n1.3.1\n1.4.0\n2.0.0\n2.0.1\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_EXPAND)
+        .arg("--releases")
+        .arg("-")
+        .arg("--limit")
+        .arg("1.3=3")
+        .arg("--step")
+        .arg("patch")
+        .arg("--filter")
+        .arg(">=1.3.2")
+        .arg("1.2.0..1.4.0")
+        .write_stdin(RELEASES)
+        .assert();
+    assert
+        .append_context(COMMAND_EXPAND, "explicit limit wins, filtered")
+        .stdout("1.3.2\n1.3.3\n1.4.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_EXPAND)
+        .arg("--releases")
+        .arg("-")
+        .arg("--filter")
+        .arg(">=1.3, <2")
+        .write_stdin(RELEASES)
+        .assert();
+    assert
+        .append_context(COMMAND_EXPAND, "published versions matching")
+        .stdout("1.3.0\n1.3.1\n1.4.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("json")
+        .arg(COMMAND_EXPAND)
+        .arg("1.0.0..1.0.2")
+        .assert();
+    let stdout = assert.get_output().stdout.clone();
+    assert
+        .append_context(COMMAND_EXPAND, "json output")
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+    assert_eq!(
+        json["versions"],
+        serde_json::json!(["1.0.0", "1.0.1", "1.0.2"])
+    );
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        fork: true,
+        cases: 256,
+        .. ProptestConfig::default()
+    })]
+    // With every line limited to the same patch, a patch expansion lists each
+    // line's patches in order, and nothing outside of the bounds.
+    #[test]
+    fn prop_expand_patch_counts(minors in (0u64..4, 0u64..4), max in 0u64..4) {
+        let (first, last) = if minors.0 <= minors.1 { minors } else { (minors.1, minors.0) };
+        let mut cmd = common_cmd();
+        cmd.arg("-o").arg("json").arg(COMMAND_EXPAND);
+        for minor in first..last {
+            cmd.arg("--limit").arg(format!("1.{minor}={max}"));
+        }
+        let assert = cmd.arg(format!("1.{first}.0..1.{last}.{max}")).assert();
+        let stdout = assert.get_output().stdout.clone();
+        assert.append_context(COMMAND_EXPAND, "prop test").success();
+
+        let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+        let versions = json["versions"].as_array().expect("versions");
+        prop_assert_eq!(versions.len() as u64, (last - first + 1) * (max + 1));
+        prop_assert_eq!(&versions[0], &format!("1.{first}.0"));
+        prop_assert_eq!(&versions[versions.len() - 1], &format!("1.{last}.{max}"));
+    }
+}
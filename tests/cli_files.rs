@@ -0,0 +1,194 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+use proptest::prelude::*;
+use semver::Version;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod common;
+use common::subcommands::*;
+
+use crate::common::common_cmd;
+
+static TEMP_DIR_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A fresh directory holding empty files with the given names.
+fn release_dir(names: &[String]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "sem-tool-cli-files-{}-{}",
+        std::process::id(),
+        TEMP_DIR_COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    for name in names {
+        std::fs::write(dir.join(name), "").expect("create temp file");
+    }
+    dir
+}
+
+#[test]
+fn cli_files_invalid_input() {
+    let assert = common_cmd().arg(COMMAND_FILES).arg(".").assert();
+    assert
+        .append_context(COMMAND_FILES, "no template")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_FILES)
+        .arg("--template")
+        .arg("mytool.tar.gz")
+        .arg(".")
+        .assert();
+    assert
+        .append_context(COMMAND_FILES, "template without version")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_FILES)
+        .arg("--template")
+        .arg("mytool-{version}.tar.gz")
+        .arg("README.md")
+        .assert();
+    assert
+        .append_context(COMMAND_FILES, "nothing matches")
+        .failure();
+}
+
+#[test]
+fn cli_files_basic_cases() {
+    let names: Vec<String> = [
+        "mytool-1.4.2-x86_64-linux.tar.gz",
+        "mytool-1.10.0-rc.1-x86_64-linux.tar.gz",
+        "mytool-1.3.0-x86_64-linux.tar.gz",
+        "README.md",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    let dir = release_dir(&names);
+    let path = |name: &str| format!("{}\n", dir.join(name).display());
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_FILES)
+        .arg("-T")
+        .arg("mytool-{version}-x86_64-linux.tar.gz")
+        .arg(&dir)
+        .assert();
+    assert
+        .append_context(COMMAND_FILES, "max")
+        .stdout(path("mytool-1.10.0-rc.1-x86_64-linux.tar.gz"))
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_LATEST_FILE)
+        .arg("-T")
+        .arg("mytool-{version}-x86_64-linux.tar.gz")
+        .arg("--stable")
+        .arg(&dir)
+        .assert();
+    assert
+        .append_context(COMMAND_LATEST_FILE, "stable")
+        .stdout(path("mytool-1.4.2-x86_64-linux.tar.gz"))
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_FILES)
+        .arg("-T")
+        .arg("mytool-{version}-x86_64-linux.tar.gz")
+        .arg("--pick")
+        .arg("all")
+        .arg("--reverse")
+        .arg(&dir)
+        .assert();
+    assert
+        .append_context(COMMAND_FILES, "all, reverse")
+        .stdout(format!(
+            "{}{}{}",
+            path("mytool-1.10.0-rc.1-x86_64-linux.tar.gz"),
+            path("mytool-1.4.2-x86_64-linux.tar.gz"),
+            path("mytool-1.3.0-x86_64-linux.tar.gz")
+        ))
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_FILES)
+        .arg("--regex")
+        .arg("^mytool-([0-9.]+)-")
+        .arg("--pick")
+        .arg("min")
+        .write_stdin(names.join("\n"))
+        .assert();
+    assert
+        .append_context(COMMAND_FILES, "regex, min, stdin")
+        .stdout("mytool-1.3.0-x86_64-linux.tar.gz\n")
+        .success();
+
+    std::fs::remove_dir_all(&dir).expect("remove temp dir");
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        fork: true,
+        cases: 64,
+        .. ProptestConfig::default()
+    })]
+    // Arbitrary versions quickly outgrow file name limits, so these are
+    // kept short, and without build metadata (ambiguity is covered by the
+    // `max` tests).
+    #[test]
+    fn prop_files_max(versions in prop::collection::vec(
+        (0u64..20, 0u64..20, 0u64..20, prop::option::of("[a-z]{1,4}(\\.[1-9][0-9]?)?")),
+        1..16,
+    )) {
+        let versions: Vec<Version> = versions
+            .into_iter()
+            .map(|(major, minor, patch, pre)| {
+                let mut v = Version::new(major, minor, patch);
+                if let Some(pre) = pre {
+                    v.pre = semver::Prerelease::new(&pre).unwrap();
+                }
+                v
+            })
+            .collect();
+        let names: Vec<String> = versions.iter().map(|v| format!("pkg_{v}.zip")).collect();
+        let dir = release_dir(&names);
+
+        let assert = common_cmd()
+            .arg("-o")
+            .arg("json")
+            .arg(COMMAND_FILES)
+            .arg("-T")
+            .arg("pkg_{version}.zip")
+            .arg(&dir)
+            .assert();
+        let stdout = assert.get_output().stdout.clone();
+        std::fs::remove_dir_all(&dir).expect("remove temp dir");
+        assert.append_context(COMMAND_FILES, "prop test").success();
+
+        let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+        let files = json["files"].as_array().expect("files array");
+        prop_assert_eq!(files.len(), 1);
+        let picked = Version::parse(files[0]["version"].as_str().unwrap()).unwrap();
+        prop_assert_eq!(&picked, versions.iter().max().unwrap());
+    }
+}
@@ -0,0 +1,138 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+use proptest::prelude::*;
+use semver::Version;
+
+mod common;
+use common::subcommands::*;
+
+use crate::common::common_cmd;
+
+#[test]
+fn cli_gaps_invalid_input() {
+    let assert = common_cmd().arg(COMMAND_GAPS).arg("a.b.c").assert();
+    assert.append_context(COMMAND_GAPS, "bad semver").failure();
+}
+
+#[test]
+fn cli_gaps_basic_cases() {
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_GAPS)
+        .arg("1.2.0")
+        .arg("1.2.1")
+        .arg("1.2.3")
+        .assert();
+    assert
+        .append_context(COMMAND_GAPS, "patch gap")
+        .stdout("patch: 1.2.1 -> 1.2.3: missing 1.2.2\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_GAPS)
+        .write_stdin("1.5.0\n1.3.9\n")
+        .assert();
+    assert
+        .append_context(COMMAND_GAPS, "minor gap, stdin")
+        .stdout("minor: 1.3.9 -> 1.5.0: missing 1.4.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_GAPS)
+        .arg("--stable")
+        .arg("2.0.0-rc.1")
+        .arg("2.0.0-rc.3")
+        .arg("2.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_GAPS, "stable drops pre-release gaps")
+        .stdout("")
+        .success();
+
+    let assert = common_cmd()
+        .arg(COMMAND_GAPS)
+        .arg("--fail-if-found")
+        .arg("2.0.0-rc.1")
+        .arg("2.0.0-rc.3")
+        .assert();
+    assert
+        .append_context(COMMAND_GAPS, "fail if found")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_GAPS)
+        .arg("--fail-if-found")
+        .arg("1.0.0")
+        .arg("1.0.1")
+        .arg("1.1.0")
+        .arg("2.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_GAPS, "fail if found, contiguous")
+        .success();
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        fork: true,
+        cases: 256,
+        .. ProptestConfig::default()
+    })]
+    // Removing patch releases from a contiguous line must report exactly the
+    // removed runs.
+    #[test]
+    fn prop_gaps_patch_line(major in 0u64..100, minor in 0u64..100, keep in prop::collection::vec(any::<bool>(), 2..32)) {
+        let mut keep = keep;
+        keep[0] = true;
+        let last = keep.len() - 1;
+        keep[last] = true;
+
+        let versions: Vec<String> = keep
+            .iter()
+            .enumerate()
+            .filter(|(_, k)| **k)
+            .map(|(patch, _)| format!("{major}.{minor}.{patch}"))
+            .collect();
+
+        let assert = common_cmd()
+            .arg("-o")
+            .arg("json")
+            .arg(COMMAND_GAPS)
+            .args(&versions)
+            .assert();
+        let stdout = assert.get_output().stdout.clone();
+        assert.append_context(COMMAND_GAPS, "prop test").success();
+
+        let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+        let reported: u64 = json["gaps"]
+            .as_array()
+            .expect("gaps array")
+            .iter()
+            .map(|g| {
+                prop_assert_eq!(g["kind"].as_str(), Some("patch"));
+                let first = Version::parse(g["first_missing"].as_str().unwrap()).unwrap();
+                prop_assert!(!keep[first.patch as usize]);
+                Ok(g["missing_count"].as_u64().expect("count"))
+            })
+            .sum::<Result<u64, TestCaseError>>()?;
+
+        prop_assert_eq!(reported as usize, keep.iter().filter(|k| !**k).count());
+    }
+}
@@ -0,0 +1,120 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+use proptest::prelude::*;
+
+mod common;
+use common::subcommands::*;
+
+use crate::common::common_cmd;
+
+const PUBLISHED: &str = "1.2.0\n1.2.1\n1.3.0\n1.3.1+a\n";
+
+#[test]
+fn cli_guard_invalid_input() {
+    let assert = common_cmd().arg(COMMAND_GUARD).assert();
+    assert
+        .append_context(COMMAND_GUARD, "no candidate")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_GUARD)
+        .arg("a.b.c")
+        .write_stdin(PUBLISHED)
+        .assert();
+    assert
+        .append_context(COMMAND_GUARD, "bad semver candidate")
+        .failure();
+}
+
+#[test]
+fn cli_guard_basic_cases() {
+    for (candidate, failed) in [
+        ("1.3.2", vec![]),
+        ("1.2.2", vec![]),
+        ("1.4.0-rc.1", vec![]),
+        ("1.3.1+b", vec!["newer-than-line", "unique-precedence"]),
+        ("1.5.0", vec!["no-skipped-numbers"]),
+        (
+            "1.3.0-rc.1",
+            vec!["newer-than-line", "not-already-released"],
+        ),
+    ] {
+        let assert = common_cmd()
+            .arg("-o")
+            .arg("json")
+            .arg(COMMAND_GUARD)
+            .arg(candidate)
+            .write_stdin(PUBLISHED)
+            .assert();
+        let stdout = assert.get_output().stdout.clone();
+        let assert = assert.append_context(COMMAND_GUARD, candidate);
+        if failed.is_empty() {
+            assert.success();
+        } else {
+            assert.failure();
+        }
+
+        let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+        assert_eq!(json["passed"], failed.is_empty());
+        let checks = json["checks"].as_array().expect("checks");
+        assert_eq!(checks.len(), 4);
+        let failing: Vec<&str> = checks
+            .iter()
+            .filter(|check| check["passed"] == false)
+            .map(|check| check["rule"].as_str().expect("rule"))
+            .collect();
+        assert_eq!(failing, failed, "{candidate}");
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        fork: true,
+        cases: 256,
+        ..ProptestConfig::default()
+    })]
+
+    /// The next patch, minor or major release of the latest passes.
+    #[test]
+    fn prop_guard_next_release_passes(
+        (major, minor, patch) in (0u64..100, 0u64..100, 0u64..100),
+        level in 0usize..3,
+    ) {
+        let latest = format!("{major}.{minor}.{patch}");
+        let candidate = match level {
+            0 => format!("{major}.{minor}.{}", patch + 1),
+            1 => format!("{major}.{}.0", minor + 1),
+            _ => format!("{}.0.0", major + 1),
+        };
+        let assert = common_cmd()
+            .arg(COMMAND_GUARD)
+            .arg(&candidate)
+            .arg(&latest)
+            .assert();
+        assert
+            .append_context(COMMAND_GUARD, format!("{latest} -> {candidate}"))
+            .success();
+
+        // Publishing it again never passes.
+        let assert = common_cmd()
+            .arg(COMMAND_GUARD)
+            .arg(&latest)
+            .arg(&latest)
+            .assert();
+        assert
+            .append_context(COMMAND_GUARD, format!("{latest} again"))
+            .failure();
+    }
+}
@@ -48,6 +48,17 @@ fn cli_insta() {
 
     // Sort Tests
     insta_targets.insert("sort.unary.1", vec![COMMAND_SORT, "0.1.2-rc0"]);
+    insta_targets.insert(
+        "sort.check.1",
+        vec![
+            COMMAND_SORT,
+            "--check=all",
+            "1.0.0",
+            "0.1.0",
+            "2.0.0",
+            "1.5.0",
+        ],
+    );
     insta_targets.insert(
         "sort.complex.1",
         vec![
@@ -173,6 +184,86 @@ fn cli_insta() {
         "compare.semantic-exit-status.1",
         vec![COMMAND_COMPARE, "-s", "1.2.4+0", "1.2.3+1"],
     );
+    insta_targets.insert(
+        "compare.fail-on-diff.1",
+        vec![COMMAND_COMPARE, "--fail-on-diff", "major", "1.4.2", "2.0.0"],
+    );
+    insta_targets.insert(
+        "compare.fail-on-diff.2",
+        vec![
+            "-o",
+            "text",
+            COMMAND_COMPARE,
+            "--fail-on-diff",
+            "major",
+            "0.4.2",
+            "0.5.0",
+        ],
+    );
+    insta_targets.insert(
+        "compare.trace.1",
+        vec![
+            "-o",
+            "text",
+            COMMAND_COMPARE,
+            "--trace",
+            "1.0.0-rc.10",
+            "1.0.0-rc.9",
+        ],
+    );
+    insta_targets.insert(
+        "compare.trace.2",
+        vec![COMMAND_COMPARE, "--trace", "1.0.0-alpha", "1.0.0"],
+    );
+    insta_targets.insert(
+        "compare.matrix.1",
+        vec![
+            "-o",
+            "text",
+            COMMAND_COMPARE,
+            "--matrix",
+            "1.0.0",
+            "1.0.0+build.1",
+            "1.0.0-rc.1",
+            "2.0.0",
+        ],
+    );
+    insta_targets.insert(
+        "compare.matrix.2",
+        vec![
+            "-o",
+            "text",
+            COMMAND_COMPARE,
+            "--matrix-format",
+            "markdown",
+            "--matrix",
+            "1.0.0",
+            "1.0.0+build.1",
+            "2.0.0",
+        ],
+    );
+    insta_targets.insert(
+        "compare.matrix.3",
+        vec![COMMAND_COMPARE, "--matrix", "1.0.0", "1.0.0+build.1"],
+    );
+    insta_targets.insert(
+        "compare.relation.1",
+        vec![COMMAND_COMPARE, "--ge", "1.2.0", "1.1.9"],
+    );
+    insta_targets.insert(
+        "compare.relation.2",
+        vec!["-o", "text", COMMAND_COMPARE, "--lt", "1.2.0", "1.1.9"],
+    );
+    insta_targets.insert(
+        "compare.exit-scheme.1",
+        vec![
+            COMMAND_COMPARE,
+            "--exit-scheme",
+            "three-way-v1",
+            "2.0.0",
+            "1.0.0",
+        ],
+    );
     insta_targets.insert(
         "bump.simple.1",
         vec!["-o", "text", COMMAND_BUMP, "1.1.1", "--bump-major=1"],
@@ -260,6 +351,18 @@ fn cli_insta() {
         "latest.alias.1",
         vec!["-o", "text", COMMAND_LATEST, "1.0.0", "2.0.0"],
     );
+    insta_targets.insert(
+        "max.provenance.1",
+        vec![
+            COMMAND_MAX,
+            "--allow-v-prefix",
+            "--render",
+            "provenance",
+            "v1.0.0",
+            "v2.0.0",
+            "1.5.0",
+        ],
+    );
     insta_targets.insert(
         "max.stable.1",
         vec![
@@ -273,6 +376,238 @@ fn cli_insta() {
         ],
     );
 
+    insta_targets.insert(
+        "uniq.simple.1",
+        vec![
+            "-o",
+            "text",
+            COMMAND_UNIQ,
+            "1.0.0",
+            "1.0.0+bm0",
+            "2.0.0",
+            "1.0.0",
+        ],
+    );
+    insta_targets.insert(
+        "uniq.count.1",
+        vec![
+            COMMAND_UNIQ,
+            "--by",
+            "minor",
+            "--keep",
+            "max",
+            "--count",
+            "1.2.0",
+            "1.2.3",
+            "1.3.0",
+            "1.2.1",
+        ],
+    );
+
+    insta_targets.insert(
+        "diff.identical.1",
+        vec![
+            COMMAND_DIFF,
+            "example-data/short-good-versions.txt",
+            "example-data/short-good-versions.txt",
+        ],
+    );
+
+    insta_targets.insert(
+        "gaps.simple.1",
+        vec![
+            COMMAND_GAPS,
+            "1.2.0",
+            "1.2.1",
+            "1.2.3",
+            "1.3.0",
+            "1.5.0",
+            "2.0.0-rc.1",
+            "2.0.0-rc.3",
+        ],
+    );
+    insta_targets.insert(
+        "distance.simple.1",
+        vec![COMMAND_DISTANCE, "1.2.3", "1.5.2"],
+    );
+    insta_targets.insert(
+        "distance.text.1",
+        vec!["-o", "text", COMMAND_DISTANCE, "2.1.0", "1.9.4"],
+    );
+    insta_targets.insert(
+        "expand.patch.1",
+        vec![
+            COMMAND_EXPAND,
+            "1.2.1..1.4.1",
+            "--limit",
+            "1.2=2",
+            "--limit",
+            "1.3=0",
+        ],
+    );
+    insta_targets.insert(
+        "expand.minor.1",
+        vec![
+            "-o",
+            "text",
+            COMMAND_EXPAND,
+            "--step",
+            "minor",
+            "--limit",
+            "1=3",
+            "1.2.1..2.1.0",
+        ],
+    );
+    insta_targets.insert(
+        "eval.guard.1",
+        vec![
+            COMMAND_EVAL,
+            "--var",
+            "new=1.4.0",
+            "--var",
+            "old=1.3.2",
+            "new > old && new ~ ^1 && new.pre == \"\"",
+        ],
+    );
+    insta_targets.insert(
+        "assert.failed.1",
+        vec![
+            "-o",
+            "text",
+            COMMAND_ASSERT,
+            "--var",
+            "new=1.4.0-rc.1",
+            "new ~ ^1 && new.pre == \"\"",
+        ],
+    );
+    insta_targets.insert("eval.invalid.1", vec![COMMAND_EVAL, "1.0.0 > > 2.0.0"]);
+    insta_targets.insert(
+        "guard.pass.1",
+        vec![COMMAND_GUARD, "1.3.2", "1.2.0", "1.3.0", "1.3.1+a"],
+    );
+    insta_targets.insert(
+        "guard.fail.1",
+        vec![COMMAND_GUARD, "1.3.1+b", "1.2.0", "1.3.0", "1.3.1+a"],
+    );
+    insta_targets.insert(
+        "snapshot.nightly.1",
+        vec![
+            COMMAND_SNAPSHOT,
+            "--timestamp",
+            "1792324800",
+            "1.4.2",
+            "1.5.0-nightly.20261018.0",
+            "1.5.0-rc.1",
+        ],
+    );
+    insta_targets.insert(
+        "stamp.template.1",
+        vec![
+            COMMAND_STAMP,
+            "1.2.3-rc.1",
+            "-T",
+            "{sha:7}.{date:%Y%m%d}.{branch}",
+            "--sha",
+            "0a1b2c3d4e5f",
+            "--timestamp",
+            "1709211909",
+            "--value",
+            "branch=feature/x",
+        ],
+    );
+    insta_targets.insert(
+        "pre-release.preminor.1",
+        vec![COMMAND_PRE_RELEASE, "preminor", "1.2.3"],
+    );
+    insta_targets.insert(
+        "pre-release.backwards.1",
+        vec![
+            COMMAND_PRE_RELEASE,
+            "prerelease",
+            "--id",
+            "alpha",
+            "1.3.0-beta.2",
+        ],
+    );
+    insta_targets.insert(
+        "bump.pre-release.1",
+        vec![
+            COMMAND_BUMP,
+            "1.0.0-beta.3.build.7",
+            "--bump-pre-release",
+            "build",
+        ],
+    );
+    insta_targets.insert(
+        "bump.pre-release.2",
+        vec![COMMAND_BUMP, "1.0.0-beta.3", "--bump-pre-release", "0"],
+    );
+    insta_targets.insert("next.minor.1", vec![COMMAND_NEXT, "minor", "1.3.0-rc.2"]);
+    insta_targets.insert("next.major.1", vec![COMMAND_NEXT, "major", "0.3.1+build"]);
+    insta_targets.insert(
+        "gaps.text.1",
+        vec!["-o", "text", COMMAND_GAPS, "1.0.0", "1.0.4", "4.0.0"],
+    );
+
+    insta_targets.insert(
+        "sort.rules.1",
+        vec![
+            "-o",
+            "text",
+            COMMAND_SORT,
+            "--rules",
+            "all",
+            "1.10.0",
+            "1.9.0",
+            "1.0.0+b",
+            "1.0.0+a",
+            "1.0.0",
+            "1.0.0-rc.1",
+        ],
+    );
+    insta_targets.insert(
+        "compare.rules.1",
+        vec![
+            "-o",
+            "text",
+            COMMAND_COMPARE,
+            "--rules",
+            "all",
+            "1.0.0+01",
+            "1.0.0+1",
+        ],
+    );
+
+    insta_targets.insert(
+        "sort.key.1",
+        vec![COMMAND_SORT, "--key", "1", "--key-regex", "x"],
+    );
+
+    insta_targets.insert(
+        "files.simple.1",
+        vec![
+            COMMAND_FILES,
+            "--template",
+            "mytool-{version}-*.tar.gz",
+            "dist/mytool-1.4.2-x86_64-linux.tar.gz",
+            "dist/mytool-1.10.0-x86_64-linux.tar.gz",
+            "dist/mytool-1.10.0-aarch64-linux.tar.gz",
+            "dist/README.md",
+        ],
+    );
+    insta_targets.insert(
+        "latest-file.alias.1",
+        vec![
+            "-o",
+            "text",
+            COMMAND_LATEST_FILE,
+            "-T",
+            "mytool-{version}.zip",
+            "mytool-1.0.0.zip",
+            "mytool-2.0.0.zip",
+        ],
+    );
+
     for (key, args) in insta_targets.iter() {
         assert_cmd_snapshot!(*key, cli().args(args));
     }
@@ -0,0 +1,138 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod common;
+use common::subcommands::*;
+
+use crate::common::common_cmd;
+
+static TEMP_DIR_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+const POLICY: &str = r#"
+[lint]
+pre-release-channels = ["alpha", "beta", "rc"]
+max-identifiers = 2
+build-metadata = "forbidden"
+initial-development = false
+pre-release-counter = true
+
+[lint.severity]
+build-metadata = "warning"
+"#;
+
+/// A fresh directory holding `.sem-tool.toml`, and a `nested` directory.
+fn policy_dir(policy: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "sem-tool-cli-lint-{}-{}",
+        std::process::id(),
+        TEMP_DIR_COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    std::fs::create_dir_all(dir.join("nested")).expect("create temp dir");
+    std::fs::write(dir.join(".sem-tool.toml"), policy).expect("write policy");
+    dir
+}
+
+#[test]
+fn cli_lint_invalid_input() {
+    let dir = policy_dir("[lint]\nunknown-rule = true\n");
+    let assert = common_cmd()
+        .current_dir(&dir)
+        .arg(COMMAND_LINT)
+        .arg("1.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_LINT, "unknown policy key")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_LINT)
+        .arg("--policy")
+        .arg(dir.join("missing.toml"))
+        .arg("1.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_LINT, "missing policy file")
+        .failure();
+
+    let assert = common_cmd()
+        .current_dir(&dir)
+        .arg(COMMAND_LINT)
+        .arg("--policy")
+        .arg(dir.join(".sem-tool.toml"))
+        .arg("a.b.c")
+        .assert();
+    assert.append_context(COMMAND_LINT, "bad semver").failure();
+
+    std::fs::remove_dir_all(&dir).expect("remove temp dir");
+}
+
+#[test]
+fn cli_lint_basic_cases() {
+    let dir = policy_dir(POLICY);
+    for (versions, passed, findings) in [
+        ("1.2.3\n1.3.0-rc.1\n", true, vec![]),
+        ("1.3.0+ci\n", true, vec!["build-metadata"]),
+        ("1.3.0-nightly.1\n", false, vec!["pre-release-channel"]),
+        ("1.3.0-rc.1.2\n", false, vec!["max-identifiers"]),
+        ("1.3.0-rc\n", false, vec!["pre-release-counter"]),
+        ("0.1.0\n1.0.0\n0.9.1\n", false, vec!["initial-development"]),
+    ] {
+        // The policy is found above the working directory.
+        let assert = common_cmd()
+            .current_dir(dir.join("nested"))
+            .arg("-o")
+            .arg("json")
+            .arg(COMMAND_LINT)
+            .write_stdin(versions)
+            .assert();
+        let stdout = assert.get_output().stdout.clone();
+        let assert = assert.append_context(COMMAND_LINT, format!("{versions:?}"));
+        if passed {
+            assert.success();
+        } else {
+            assert.failure();
+        }
+
+        let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+        assert_eq!(json["passed"], passed);
+        let rules: Vec<&str> = json["findings"]
+            .as_array()
+            .expect("findings")
+            .iter()
+            .map(|finding| finding["rule"].as_str().expect("rule"))
+            .collect();
+        assert_eq!(rules, findings, "{versions:?}");
+    }
+
+    let assert = common_cmd()
+        .current_dir(&dir)
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_LINT)
+        .arg("1.3.0-nightly+ci")
+        .assert();
+    assert
+        .append_context(COMMAND_LINT, "text findings")
+        .stdout(
+            "error: 1.3.0-nightly+ci: pre-release-channel: nightly is not one of alpha, beta, rc\n\
+             warning: 1.3.0-nightly+ci: build-metadata: build metadata is forbidden\n\
+             error: 1.3.0-nightly+ci: pre-release-counter: the last pre-release identifier, nightly, is not numeric\n",
+        )
+        .failure();
+
+    std::fs::remove_dir_all(&dir).expect("remove temp dir");
+}
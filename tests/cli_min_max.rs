@@ -25,7 +25,14 @@ use crate::common::common_cmd;
 #[test]
 fn cli_max_invalid_input() {
     let assert = common_cmd().arg(COMMAND_MAX).arg("a.b.c").assert();
-    assert.append_context(COMMAND_MAX, "bad semver").failure();
+    assert.append_context(COMMAND_MAX, "bad semver").code(2);
+
+    let assert = common_cmd()
+        .arg(COMMAND_MIN)
+        .arg("1.0.0")
+        .arg("a.b.c")
+        .assert();
+    assert.append_context(COMMAND_MIN, "bad semver").code(2);
 }
 
 #[test]
@@ -89,6 +96,34 @@ fn cli_max_basic_cases() {
         .append_context(COMMAND_LATEST, "latest alias")
         .stdout("2.0.0\n")
         .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_LATEST)
+        .arg("--allow-v-prefix")
+        .arg("--render")
+        .arg("original")
+        .write_stdin("v1.0.0\nv2.0.0\nv1.5.0\n")
+        .assert();
+    assert
+        .append_context(COMMAND_LATEST, "--render original")
+        .stdout("v2.0.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_MIN)
+        .arg("--render")
+        .arg("provenance")
+        .arg("2.0.0")
+        .arg("1.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_MIN, "--render provenance")
+        .stdout("1.0.0\targument:2\n")
+        .success();
 }
 
 fn version_without_build(v: &Version) -> Version {
@@ -0,0 +1,356 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+use proptest::prelude::*;
+
+mod common;
+use common::subcommands::*;
+
+use crate::common::common_cmd;
+
+const COMMITS: &str = "feat(cli): add next\0\
+                       fix: off by one\n\nLonger description.\n\0\
+                       chore: release\0\
+                       Merge branch 'main'\0";
+
+#[test]
+fn cli_next_invalid_input() {
+    let assert = common_cmd()
+        .arg(COMMAND_NEXT)
+        .arg("--from-commits")
+        .arg("a.b.c")
+        .write_stdin(COMMITS)
+        .assert();
+    assert
+        .append_context(COMMAND_NEXT, "bad semver base")
+        .failure();
+
+    let assert = common_cmd().arg(COMMAND_NEXT).arg("1.0.0").assert();
+    assert
+        .append_context(COMMAND_NEXT, "nothing to compute from")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_NEXT)
+        .arg("minor")
+        .arg("1.0.0")
+        .arg("--type-bump")
+        .arg("perf=patch")
+        .assert();
+    assert
+        .append_context(COMMAND_NEXT, "type bumps without commits")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_NEXT)
+        .arg("minor")
+        .arg("--from-commits")
+        .arg("1.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_NEXT, "a level and commits")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_NEXT)
+        .arg("--from-commits")
+        .arg("1.0.0")
+        .arg("--type-bump")
+        .arg("perf=huge")
+        .write_stdin(COMMITS)
+        .assert();
+    assert
+        .append_context(COMMAND_NEXT, "unknown bump level")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_NEXT)
+        .arg("--from-commits")
+        .arg("1.0.0")
+        .arg("--avoid-existing")
+        .arg("-")
+        .write_stdin(COMMITS)
+        .assert();
+    assert
+        .append_context(COMMAND_NEXT, "commits and published versions from stdin")
+        .failure();
+}
+
+#[test]
+fn cli_next_from_commits_cases() {
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_NEXT)
+        .arg("--from-commits")
+        .arg("1.2.3")
+        .write_stdin(COMMITS)
+        .assert();
+    assert
+        .append_context(COMMAND_NEXT, "feat is minor")
+        .stdout("1.3.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_NEXT)
+        .arg("--from-commits")
+        .arg("0.9.0")
+        .write_stdin("fix(api)!: rename everything\n")
+        .assert();
+    assert
+        .append_context(COMMAND_NEXT, "breaking before 1.0.0 is minor")
+        .stdout("0.10.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_NEXT)
+        .arg("--from-commits")
+        .arg("1.2.3")
+        .write_stdin("feat: a\n\nBREAKING-CHANGE: dropped flag\n")
+        .assert();
+    assert
+        .append_context(COMMAND_NEXT, "BREAKING-CHANGE footer is major")
+        .stdout("2.0.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("json")
+        .arg(COMMAND_NEXT)
+        .arg("--from-commits")
+        .arg("1.2.3")
+        .write_stdin(
+            "fix: a\n\nSigned-off-by: A <a@example.com>\nCo-authored-by: B <b@example.com>\n",
+        )
+        .assert();
+    let stdout = assert.get_output().stdout.clone();
+    assert
+        .append_context(COMMAND_NEXT, "trailers are not commits")
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+    assert_eq!(json["version"], "1.2.4");
+    assert_eq!(json["commits"].as_array().expect("commits").len(), 1);
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("json")
+        .arg(COMMAND_NEXT)
+        .arg("--from-commits")
+        .arg("1.2.3")
+        .arg("--type-bump")
+        .arg("feat=patch")
+        .arg("--type-bump")
+        .arg("chore=patch")
+        .write_stdin(COMMITS)
+        .assert();
+    let stdout = assert.get_output().stdout.clone();
+    assert
+        .append_context(COMMAND_NEXT, "configured bumps")
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+    assert_eq!(json["version"], "1.2.4");
+    assert_eq!(json["bump"], "patch");
+    let drivers: Vec<&str> = json["commits"]
+        .as_array()
+        .expect("commits")
+        .iter()
+        .map(|commit| commit["type"].as_str().expect("type"))
+        .collect();
+    assert_eq!(drivers, ["feat", "fix", "chore"]);
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("json")
+        .arg(COMMAND_NEXT)
+        .arg("--from-commits")
+        .arg("1.2.3")
+        .write_stdin("docs: readme\nMerge branch 'main'\n")
+        .assert();
+    let stdout = assert.get_output().stdout.clone();
+    assert
+        .append_context(COMMAND_NEXT, "nothing to release")
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+    assert_eq!(json["version"], "1.2.3");
+    assert_eq!(json["bump"], "none");
+    assert!(json.get("commits").is_none());
+}
+
+#[test]
+fn cli_next_level_cases() {
+    for (args, expected) in [
+        (vec!["patch", "1.2.3"], "1.2.4\n"),
+        (vec!["minor", "1.2.3"], "1.3.0\n"),
+        (vec!["major", "1.2.3"], "2.0.0\n"),
+        (vec!["release", "1.2.3"], "1.2.4\n"),
+        (vec!["minor", "1.3.0-rc.2"], "1.3.0\n"),
+        (vec!["major", "1.3.0-rc.2"], "2.0.0\n"),
+        (vec!["patch", "1.3.0-rc.2+b"], "1.3.0\n"),
+        (vec!["release", "1.3.0-rc.2"], "1.3.0\n"),
+        (vec!["major", "0.3.1"], "0.4.0\n"),
+        (
+            vec!["major", "--leave-initial-development", "0.3.1"],
+            "1.0.0\n",
+        ),
+    ] {
+        let assert = common_cmd()
+            .arg("-o")
+            .arg("text")
+            .arg(COMMAND_NEXT)
+            .args(&args)
+            .assert();
+        assert
+            .append_context(COMMAND_NEXT, format!("{args:?}"))
+            .stdout(expected)
+            .success();
+    }
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("json")
+        .arg(COMMAND_NEXT)
+        .arg("minor")
+        .arg("1.3.0-rc.2")
+        .assert();
+    let stdout = assert.get_output().stdout.clone();
+    assert
+        .append_context(COMMAND_NEXT, "decisions are explained")
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+    assert_eq!(json["bump"], "minor");
+    let decisions = json["decisions"].as_array().expect("decisions");
+    assert!(
+        decisions[0]
+            .as_str()
+            .expect("decision")
+            .contains("pre-release of 1.3.0")
+    );
+}
+
+#[test]
+fn cli_next_avoid_existing_cases() {
+    for (args, published, expected) in [
+        (vec!["patch", "1.2.3"], "1.2.4\n1.2.5\n1.3.0\n", "1.2.6\n"),
+        (vec!["patch", "1.2.3"], "1.2.4\n1.2.9\n", "1.2.10\n"),
+        (vec!["patch", "1.2.3"], "1.2.5-rc.1\n", "1.2.5\n"),
+        (
+            vec!["minor", "1.2.3"],
+            "1.3.0\n1.4.0-rc.1\n2.0.0\n",
+            "1.4.0\n",
+        ),
+        (vec!["major", "1.2.3"], "2.0.0\n2.1.0\n", "3.0.0\n"),
+        (vec!["major", "0.2.3"], "0.3.0\n", "0.4.0\n"),
+        (vec!["minor", "1.2.3"], "1.1.0\n", "1.3.0\n"),
+    ] {
+        let assert = common_cmd()
+            .arg("-o")
+            .arg("text")
+            .arg(COMMAND_NEXT)
+            .args(&args)
+            .arg("--avoid-existing")
+            .arg("-")
+            .write_stdin(published)
+            .assert();
+        assert
+            .append_context(COMMAND_NEXT, format!("{args:?} {published:?}"))
+            .stdout(expected)
+            .success();
+    }
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("json")
+        .arg(COMMAND_NEXT)
+        .arg("patch")
+        .arg("1.2.3")
+        .arg("--avoid-existing")
+        .arg("-")
+        .write_stdin("1.2.4\n1.2.5+build\n")
+        .assert();
+    let stdout = assert.get_output().stdout.clone();
+    assert
+        .append_context(COMMAND_NEXT, "skipped candidates are reported")
+        .success();
+    let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+    assert_eq!(json["version"], "1.2.6");
+    assert_eq!(json["skipped"], serde_json::json!(["1.2.4", "1.2.5"]));
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        fork: true,
+        cases: 256,
+        .. ProptestConfig::default()
+    })]
+    // The next version is only ever the base, or a release after it.
+    #[test]
+    fn prop_next_level(
+        base in (0u64..3, 0u64..3, 0u64..3),
+        pre: bool,
+        level in prop_oneof![Just("patch"), Just("minor"), Just("major"), Just("release")],
+    ) {
+        let base_version = format!("{}.{}.{}{}", base.0, base.1, base.2, if pre { "-rc.1" } else { "" });
+        let assert = common_cmd()
+            .arg("-o")
+            .arg("text")
+            .arg(COMMAND_NEXT)
+            .arg(level)
+            .arg(&base_version)
+            .assert();
+        let stdout = assert.get_output().stdout.clone();
+        assert.append_context(COMMAND_NEXT, "prop test").success();
+
+        let next = semver::Version::parse(String::from_utf8_lossy(&stdout).trim()).expect("version");
+        let base = semver::Version::parse(&base_version).expect("version");
+        prop_assert!(next > base, "{} {} -> {}", level, base, next);
+        prop_assert!(next.pre.is_empty());
+    }
+
+    #[test]
+    fn prop_next_from_commits(
+        base in (0u64..3, 0u64..3, 0u64..3),
+        kinds in proptest::collection::vec(prop_oneof![
+            Just("feat: a"),
+            Just("fix: b"),
+            Just("docs: c"),
+            Just("refactor!: d"),
+        ], 0..4),
+    ) {
+        let base_version = format!("{}.{}.{}", base.0, base.1, base.2);
+        let assert = common_cmd()
+            .arg("-o")
+            .arg("json")
+            .arg(COMMAND_NEXT)
+            .arg("--from-commits")
+            .arg(&base_version)
+            .write_stdin(kinds.join("\n"))
+            .assert();
+        let stdout = assert.get_output().stdout.clone();
+        assert.append_context(COMMAND_NEXT, "prop test").success();
+
+        let json: serde_json::Value = serde_json::from_slice(&stdout).expect("json output");
+        let version = json["version"].as_str().expect("version");
+        let next: Vec<u64> = version.split('.').map(|n| n.parse().expect("number")).collect();
+        let next = (next[0], next[1], next[2]);
+        if json["bump"] == "none" {
+            prop_assert_eq!(next, base);
+        } else {
+            prop_assert!(next > base);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+use proptest::prelude::*;
+use proptest_semver::*;
+use semver::Version;
+
+mod common;
+use common::subcommands::*;
+
+use crate::common::common_cmd;
+
+#[test]
+fn cli_pre_release_invalid_input() {
+    let assert = common_cmd()
+        .arg(COMMAND_PRE_RELEASE)
+        .arg("prerelease")
+        .arg("a.b.c")
+        .assert();
+    assert
+        .append_context(COMMAND_PRE_RELEASE, "bad semver")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_PRE_RELEASE)
+        .arg("promote")
+        .arg("1.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_PRE_RELEASE, "nothing to promote")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_PRE_RELEASE)
+        .arg("prerelease")
+        .arg("--id")
+        .arg("alpha")
+        .arg("1.0.0-beta.1")
+        .assert();
+    assert
+        .append_context(COMMAND_PRE_RELEASE, "channel goes backwards")
+        .failure();
+}
+
+#[test]
+fn cli_pre_release_basic_cases() {
+    for (args, expected) in [
+        (vec!["premajor", "1.2.3"], "2.0.0-rc.0"),
+        (vec!["preminor", "1.2.3"], "1.3.0-rc.0"),
+        (vec!["prepatch", "--id", "alpha", "1.2.3"], "1.2.4-alpha.0"),
+        (vec!["prerelease", "1.3.0-rc.4"], "1.3.0-rc.5"),
+        (
+            vec!["prerelease", "--id", "beta", "1.3.0-alpha.3"],
+            "1.3.0-beta.0",
+        ),
+        (vec!["promote", "1.3.0-rc.5+build"], "1.3.0"),
+    ] {
+        let assert = common_cmd()
+            .arg("-o")
+            .arg("text")
+            .arg(COMMAND_PRE_RELEASE)
+            .args(&args)
+            .assert();
+        assert
+            .append_context(COMMAND_PRE_RELEASE, format!("{args:?}"))
+            .stdout(expected)
+            .success();
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        fork: true,
+        cases: 256,
+        .. ProptestConfig::default()
+    })]
+    // Every step moves forward: starting a pre-release, incrementing it, and
+    // promoting it.
+    #[test]
+    fn prop_pre_release_moves_forward(
+        v in arb_version(),
+        step in prop_oneof![
+            Just("premajor"),
+            Just("preminor"),
+            Just("prepatch"),
+            Just("prerelease"),
+        ],
+    ) {
+        let assert = common_cmd()
+            .arg("-o")
+            .arg("text")
+            .arg(COMMAND_PRE_RELEASE)
+            .arg(step)
+            .arg(v.to_string())
+            .assert();
+        let output = assert.get_output().clone();
+        if output.status.success() {
+            let next = Version::parse(&String::from_utf8_lossy(&output.stdout)).expect("version");
+            prop_assert!(next.cmp_precedence(&v).is_gt(), "{} {} -> {}", step, v, next);
+            prop_assert!(!next.pre.is_empty());
+        }
+    }
+}
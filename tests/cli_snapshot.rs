@@ -0,0 +1,166 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+use proptest::prelude::*;
+use semver::Version;
+
+mod common;
+use common::subcommands::*;
+
+use crate::common::common_cmd;
+
+/// 2026-10-18T12:00:00Z
+const TIMESTAMP: &str = "1792324800";
+
+#[test]
+fn cli_snapshot_invalid_input() {
+    let assert = common_cmd()
+        .arg(COMMAND_SNAPSHOT)
+        .arg("--timestamp")
+        .arg(TIMESTAMP)
+        .arg("1.5.0-rc.1")
+        .assert();
+    assert
+        .append_context(COMMAND_SNAPSHOT, "no stable version")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_SNAPSHOT)
+        .arg("--timestamp")
+        .arg(TIMESTAMP)
+        .arg("1.4.2")
+        .arg("1.5.0-nightly.20261019.0")
+        .assert();
+    assert
+        .append_context(COMMAND_SNAPSHOT, "newer snapshot exists")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_SNAPSHOT)
+        .arg("--timestamp")
+        .arg(TIMESTAMP)
+        .write_stdin("1.4.2\n1.5.0-nightly.20261018.18446744073709551615\n")
+        .assert();
+    assert
+        .append_context(COMMAND_SNAPSHOT, "counter overflows")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_SNAPSHOT)
+        .arg("--id")
+        .arg("night_ly")
+        .arg("1.4.2")
+        .assert();
+    assert
+        .append_context(COMMAND_SNAPSHOT, "bad identifier")
+        .failure();
+}
+
+#[test]
+fn cli_snapshot_basic_cases() {
+    for (args, stdin, expected) in [
+        (
+            vec![],
+            "1.4.2\n1.0.0\n1.5.0-rc.1\n",
+            "1.5.0-nightly.20261018.0",
+        ),
+        (
+            vec![],
+            "1.4.2\n1.5.0-nightly.20261018.0\n1.5.0-nightly.20261018.2\n",
+            "1.5.0-nightly.20261018.3",
+        ),
+        (
+            vec!["--level", "patch", "--id", "snapshot"],
+            "1.4.2\n",
+            "1.4.3-snapshot.20261018.0",
+        ),
+        (
+            vec!["--id", "nightly.build"],
+            "1.4.2\n1.5.0-nightly.build.20261018.0\n",
+            "1.5.0-nightly.build.20261018.1",
+        ),
+        (
+            vec!["--level", "major"],
+            "0.4.2\n",
+            "0.5.0-nightly.20261018.0",
+        ),
+        (
+            vec!["--level", "major", "--leave-initial-development"],
+            "0.4.2\n",
+            "1.0.0-nightly.20261018.0",
+        ),
+    ] {
+        let assert = common_cmd()
+            .arg("-o")
+            .arg("text")
+            .arg(COMMAND_SNAPSHOT)
+            .arg("--timestamp")
+            .arg(TIMESTAMP)
+            .args(&args)
+            .write_stdin(stdin)
+            .assert();
+        assert
+            .append_context(COMMAND_SNAPSHOT, format!("{args:?} {stdin:?}"))
+            .stdout(format!("{expected}\n"))
+            .success();
+    }
+
+    // The date comes from SOURCE_DATE_EPOCH without --timestamp.
+    let assert = common_cmd()
+        .env("SOURCE_DATE_EPOCH", "0")
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_SNAPSHOT)
+        .arg("1.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_SNAPSHOT, "SOURCE_DATE_EPOCH")
+        .stdout("1.1.0-nightly.19700101.0\n")
+        .success();
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        fork: true,
+        cases: 256,
+        ..ProptestConfig::default()
+    })]
+
+    /// Each snapshot published is newer than the ones before it.
+    #[test]
+    fn prop_snapshot_is_newest(
+        (major, minor, patch) in (0u64..100, 0u64..100, 0u64..100),
+        published in 0usize..4,
+    ) {
+        let mut versions = vec![format!("{major}.{minor}.{patch}")];
+        for _ in 0..=published {
+            let assert = common_cmd()
+                .arg("-o")
+                .arg("text")
+                .arg(COMMAND_SNAPSHOT)
+                .arg("--timestamp")
+                .arg(TIMESTAMP)
+                .args(&versions)
+                .assert();
+            let stdout = String::from_utf8(assert.get_output().stdout.clone()).expect("utf8");
+            assert.success();
+            let snapshot = Version::parse(stdout.trim()).expect("a version");
+            for existing in &versions {
+                let existing = Version::parse(existing).expect("a version");
+                prop_assert!(snapshot.cmp_precedence(&existing).is_gt());
+            }
+            versions.push(snapshot.to_string());
+        }
+    }
+}
@@ -27,7 +27,18 @@ fn cli_sort_invalid_input() {
     let assert = common_cmd().arg(COMMAND_SORT).arg("a.b.c").assert();
     assert
         .append_context(COMMAND_SORT, "1 bad semver args")
-        .failure();
+        .code(2);
+
+    // Without --allow-v-prefix, a v-prefixed argument is a usage error too.
+    let assert = common_cmd()
+        .arg(COMMAND_SORT)
+        .arg("--flatten")
+        .arg("1.0.0")
+        .arg("v1.1.0")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "v-prefixed argument")
+        .code(2);
 }
 
 #[test]
@@ -145,6 +156,460 @@ fn cli_sort_basic_cases() {
         .success();
 }
 
+#[test]
+fn cli_sort_key_cases() {
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_SORT)
+        .arg("--key")
+        .arg("2")
+        .arg("--field-separator")
+        .arg(":")
+        .write_stdin("nginx:1.10.0\nnginx:latest\nnginx:1.2.0\nnginx:1.2.0-rc.1\n")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--key, separator")
+        .stdout("nginx:1.2.0-rc.1\nnginx:1.2.0\nnginx:1.10.0\nnginx:latest\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_SORT)
+        .arg("-k")
+        .arg("2")
+        .arg("--reverse")
+        .arg("--stable")
+        .arg("--unkeyed")
+        .arg("first")
+        .write_stdin("a 1.0.0 x\nb 2.0.0-rc.1 y\nc 1.5.0 z\nd - w\n")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--key, reverse, stable, unkeyed first")
+        .stdout("d - w\nc 1.5.0 z\na 1.0.0 x\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_SORT)
+        .arg("--key-regex")
+        .arg("^mytool-(?P<version>[0-9]+\\.[0-9]+\\.[0-9]+(-rc\\.[0-9]+)?)-")
+        .arg("--filter")
+        .arg(">=1.4")
+        .arg("--unkeyed")
+        .arg("drop")
+        .write_stdin(
+            "mytool-1.10.0-rc.1-x86_64-linux.tar.gz\n\
+             mytool-1.4.2-x86_64-linux.tar.gz\n\
+             mytool-1.3.0-x86_64-linux.tar.gz\n\
+             README.md\n",
+        )
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--key-regex, filter, unkeyed drop")
+        .stdout("mytool-1.4.2-x86_64-linux.tar.gz\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg(COMMAND_SORT)
+        .arg("--key")
+        .arg("1")
+        .arg("--fail-if-potentially-ambiguous")
+        .write_stdin("1.0.0+a first\n1.0.0+b second\n")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--key, ambiguous")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_SORT)
+        .arg("--key")
+        .arg("0")
+        .write_stdin("1.0.0\n")
+        .assert();
+    assert.append_context(COMMAND_SORT, "--key 0").failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_SORT)
+        .arg("--key")
+        .arg("1")
+        .arg("1.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--key with version arguments")
+        .failure();
+
+    // Options that don't apply to keyed lines are rejected, not ignored.
+    for args in [
+        vec!["--key", "2", "--merge", "a.txt", "b.txt"],
+        vec!["--key-regex", "v(.*)", "--merge", "a.txt"],
+        vec!["--key", "1", "--allow-v-prefix"],
+        vec!["--key-regex", "v(.*)", "--render", "original"],
+    ] {
+        let assert = common_cmd()
+            .arg(COMMAND_SORT)
+            .args(&args)
+            .write_stdin("")
+            .assert();
+        assert
+            .append_context(COMMAND_SORT, format!("{args:?}"))
+            .code(2);
+    }
+}
+
+#[test]
+fn cli_sort_conflicting_options() {
+    // Each mode of `sort` rejects the options it would otherwise ignore.
+    for args in [
+        vec!["--rules", "spec", "--merge", "a.txt"],
+        vec!["--rules", "spec", "--flatten", "--buffer-size", "2"],
+        vec!["--rules", "spec", "--check"],
+        vec!["--check", "--flatten", "--buffer-size", "2"],
+        vec!["--check", "--buffer-size", "2"],
+        vec!["--check", "--merge", "a.txt"],
+        vec!["--flatten", "--buffer-size", "2", "--merge", "a.txt"],
+        vec!["--unkeyed", "first", "1.0.0"],
+    ] {
+        let assert = common_cmd()
+            .arg(COMMAND_SORT)
+            .args(&args)
+            .write_stdin("")
+            .assert();
+        assert
+            .append_context(COMMAND_SORT, format!("{args:?}"))
+            .code(2);
+    }
+}
+
+#[test]
+fn cli_sort_token_cases() {
+    let assert = common_cmd().arg(COMMAND_SORT).arg("v1.0.0").assert();
+    assert
+        .append_context(COMMAND_SORT, "v prefix without --allow-v-prefix")
+        .failure();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_SORT)
+        .arg("--flatten")
+        .arg("--allow-v-prefix")
+        .arg("--render")
+        .arg("original")
+        .write_stdin("v1.10.0\n  v1.2.0  \n1.9.0\n")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--render original")
+        .stdout("v1.2.0\n1.9.0\nv1.10.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_SORT)
+        .arg("--flatten")
+        .arg("--allow-v-prefix")
+        .arg("v1.10.0")
+        .arg("v1.2.0")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--allow-v-prefix, canonical")
+        .stdout("1.2.0\n1.10.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("json")
+        .arg(COMMAND_SORT)
+        .arg("--flatten")
+        .arg("--render")
+        .arg("provenance")
+        .write_stdin("2.0.0\n1.0.0\n")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--render provenance")
+        .stdout(concat!(
+            r#"{"versions":[{"version":"1.0.0","original":"1.0.0","source":"stdin","line":2},"#,
+            r#"{"version":"2.0.0","original":"2.0.0","source":"stdin","line":1}],"#,
+            r#""potentially_ambiguous":false}"#,
+            "\n"
+        ))
+        .success();
+}
+
+#[test]
+fn cli_sort_check_merge_cases() {
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_SORT)
+        .arg("--check")
+        .write_stdin("1.0.0\n1.2.0\n1.1.0\n2.0.0\n0.1.0\n")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--check, first")
+        .stdout("stdin:3: disorder: 1.1.0 after 1.2.0\n")
+        .failure();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_SORT)
+        .arg("--check=all")
+        .write_stdin("1.0.0\n1.2.0\n1.1.0\n2.0.0\n0.1.0\n")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--check=all")
+        .stdout("stdin:3: disorder: 1.1.0 after 1.2.0\nstdin:5: disorder: 0.1.0 after 2.0.0\n")
+        .failure();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_SORT)
+        .arg("-c")
+        .arg("--reverse")
+        .arg("2.0.0")
+        .arg("2.0.0-rc.1")
+        .arg("1.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "-c, reverse, sorted")
+        .stdout("")
+        .success();
+
+    let assert = common_cmd()
+        .arg(COMMAND_SORT)
+        .arg("-c")
+        .arg("--lexical-sorting")
+        .arg("1.0.0+b")
+        .arg("1.0.0+a")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "-c, lexical build metadata")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_SORT)
+        .arg("-c")
+        .arg("--flatten")
+        .arg("1.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "-c with --flatten")
+        .failure();
+
+    let dir = std::env::temp_dir().join(format!("sem-tool-cli-sort-merge-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    let left = dir.join("left.txt");
+    let right = dir.join("right.txt");
+    let unsorted = dir.join("unsorted.txt");
+    std::fs::write(&left, "0.1.0\n1.0.0\n1.1.0\n").expect("write left");
+    std::fs::write(&right, "0.5.0\n1.1.5\n3.0.0\n").expect("write right");
+    std::fs::write(&unsorted, "2.0.0\n1.0.0\n").expect("write unsorted");
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_SORT)
+        .arg("--flatten")
+        .arg("--merge")
+        .arg(&left)
+        .arg(&right)
+        .arg("-")
+        .write_stdin("0.0.1\n4.0.0\n")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--merge")
+        .stdout("0.0.1\n0.1.0\n0.5.0\n1.0.0\n1.1.0\n1.1.5\n3.0.0\n4.0.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg(COMMAND_SORT)
+        .arg("--merge")
+        .arg(&left)
+        .arg(&unsorted)
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--merge, unsorted input")
+        .failure();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_SORT)
+        .arg("--flatten")
+        .arg("--reverse")
+        .arg("--merge")
+        .arg(&unsorted)
+        .arg("-")
+        .write_stdin("1.5.0\n")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--merge, reverse")
+        .stdout("2.0.0\n1.5.0\n1.0.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg(COMMAND_SORT)
+        .arg("--merge")
+        .arg("-")
+        .arg("-")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--merge, stdin twice")
+        .failure();
+
+    std::fs::remove_dir_all(&dir).expect("remove temp dir");
+}
+
+#[test]
+fn cli_sort_buffer_size_cases() {
+    let input = "1.10.0\nv1.2.0\n1.9.0+b\n1.9.0+a\n0.1.0\n2.0.0-rc.1\n";
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_SORT)
+        .arg("--flatten")
+        .arg("--lexical-sorting")
+        .arg("--allow-v-prefix")
+        .arg("--render")
+        .arg("provenance")
+        .arg("--buffer-size")
+        .arg("2")
+        .write_stdin(input)
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--buffer-size, spilled")
+        .stdout(
+            "0.1.0\tstdin:5\nv1.2.0\tstdin:2\n1.9.0+a\tstdin:4\n1.9.0+b\tstdin:3\n\
+             1.10.0\tstdin:1\n2.0.0-rc.1\tstdin:6\n",
+        )
+        .success();
+
+    for format in ["yaml", "json"] {
+        let spilled = common_cmd()
+            .arg("-o")
+            .arg(format)
+            .arg(COMMAND_SORT)
+            .arg("--flatten")
+            .arg("--lexical-sorting")
+            .arg("--reverse")
+            .arg("--stable")
+            .arg("--allow-v-prefix")
+            .arg("--buffer-size")
+            .arg("1")
+            .write_stdin(input)
+            .output()
+            .expect("run spilled sort");
+        let in_memory = common_cmd()
+            .arg("-o")
+            .arg(format)
+            .arg(COMMAND_SORT)
+            .arg("--flatten")
+            .arg("--lexical-sorting")
+            .arg("--reverse")
+            .arg("--stable")
+            .arg("--allow-v-prefix")
+            .write_stdin(input)
+            .output()
+            .expect("run in-memory sort");
+        assert!(spilled.status.success(), "{format}");
+        assert_eq!(spilled.stdout, in_memory.stdout, "{format}");
+    }
+
+    let assert = common_cmd()
+        .arg(COMMAND_SORT)
+        .arg("--buffer-size")
+        .arg("2")
+        .arg("1.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--buffer-size without --flatten")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_SORT)
+        .arg("--flatten")
+        .arg("--buffer-size")
+        .arg("0")
+        .arg("1.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--buffer-size 0")
+        .failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_SORT)
+        .arg("--flatten")
+        .arg("--buffer-size")
+        .arg("1")
+        .write_stdin("1.0.0\n2.0.0\na.b.c\n")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--buffer-size, bad input after a spill")
+        .failure();
+}
+
+#[test]
+fn cli_sort_rules_cases() {
+    let assert = common_cmd()
+        .args(["-o", "text", COMMAND_SORT, "--rules", "lexical"])
+        .args(["1.10.0", "1.9.0", "1.0.0-rc.1", "1.0.0"])
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--rules lexical")
+        .stdout("1.0.0\n1.0.0-rc.1\n1.10.0\n1.9.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .args([
+            "-o",
+            "text",
+            COMMAND_SORT,
+            "--rules",
+            "cargo",
+            "--reverse",
+            "--stable",
+        ])
+        .write_stdin("1.0.0+a\n1.0.0-rc.1\n1.0.0\n")
+        .assert();
+    assert
+        .append_context(COMMAND_SORT, "--rules cargo from stdin")
+        .stdout("1.0.0+a\n1.0.0\n")
+        .success();
+
+    let output = common_cmd()
+        .args([
+            "-o",
+            "json",
+            COMMAND_SORT,
+            "--rules",
+            "all",
+            "1.0.0+01",
+            "1.0.0+1",
+        ])
+        .output()
+        .expect("run sort --rules all");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("json output");
+    assert_eq!(json["rules_disagree"], true);
+    assert_eq!(json["orderings"][1]["rules"], "cargo");
+    assert_eq!(json["orderings"][1]["versions"][0], "1.0.0+1");
+
+    for conflicting in ["--flatten", "--lexical-sorting", "--check"] {
+        let assert = common_cmd()
+            .args([COMMAND_SORT, "--rules", "spec", conflicting, "1.0.0"])
+            .assert();
+        assert
+            .append_context(COMMAND_SORT, format!("--rules with {conflicting}"))
+            .code(2);
+    }
+}
+
 const SORT_TEST_VERSION_COUNT_SMALL: usize = 32;
 #[cfg(not(windows))]
 const SORT_TEST_VERSION_COUNT_LARGE: usize = 128;
@@ -268,6 +733,30 @@ proptest! {
     })]
     // Using some large number of filters is unlikely to provide us with half the
     // test cases,
+    #[test]
+    fn sort_test_buffer_size(reverse: bool, stable: bool, buffer_size in 1usize..8, versions in arb_vec_versions(SORT_TEST_VERSION_COUNT_SMALL)) {
+        let run = |buffer_size: Option<usize>| {
+            let mut cmd = common_cmd();
+            cmd.arg("-o").arg("json").arg(COMMAND_SORT).arg("--flatten").arg("--lexical-sorting");
+            if reverse {
+                cmd.arg("--reverse");
+            }
+            if stable {
+                cmd.arg("--stable");
+            }
+            if let Some(buffer_size) = buffer_size {
+                cmd.arg("--buffer-size").arg(buffer_size.to_string());
+            }
+            let input: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+            cmd.write_stdin(input.join("\n")).output().expect("run sort")
+        };
+
+        let spilled = run(Some(buffer_size));
+        let in_memory = run(None);
+        prop_assert!(spilled.status.success());
+        prop_assert_eq!(spilled.stdout, in_memory.stdout);
+    }
+
     #[test]
     fn sort_test_small(lexical_sorting: bool, reverse: bool, flatten: bool, fail_if_potentially_ambiguous: bool, stable: bool, filter in arb_optional_version_req(0.5, 2), versions in arb_vec_versions(SORT_TEST_VERSION_COUNT_SMALL)) {
         sort_test_generic(lexical_sorting, reverse, flatten, fail_if_potentially_ambiguous, stable, filter, versions);
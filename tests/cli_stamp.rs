@@ -0,0 +1,194 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+use std::path::PathBuf;
+
+mod common;
+use common::subcommands::*;
+
+use crate::common::common_cmd;
+
+const SHA: &str = "0a1b2c3d4e5f60718293a4b5c6d7e8f901234567";
+
+/// A command that won't pick up the commit of whatever CI runs the tests.
+fn stamp_cmd() -> assert_cmd::Command {
+    let mut cmd = common_cmd();
+    cmd.env_remove("GITHUB_SHA")
+        .env_remove("CI_COMMIT_SHA")
+        .env_remove("GIT_COMMIT")
+        .env_remove("SOURCE_DATE_EPOCH")
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_STAMP);
+    cmd
+}
+
+/// A fresh repository whose `HEAD` is on `main`, committed in `packed-refs`.
+fn git_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sem-tool-cli-stamp-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join(".git")).expect("create temp dir");
+    std::fs::write(dir.join(".git/HEAD"), "ref: refs/heads/main\n").expect("write HEAD");
+    std::fs::write(
+        dir.join(".git/packed-refs"),
+        format!("# pack-refs with: peeled fully-peeled sorted\n{SHA} refs/heads/main\n"),
+    )
+    .expect("write packed-refs");
+    std::fs::create_dir_all(dir.join("nested")).expect("create temp dir");
+    dir
+}
+
+#[test]
+fn cli_stamp_invalid_input() {
+    for (args, context) in [
+        (vec!["1.2.3"], "no template"),
+        (vec!["a.b.c", "-T", "x"], "bad semver"),
+        (vec!["1.2.3", "-T", "{sha"], "unclosed placeholder"),
+        (vec!["1.2.3", "-T", "{branch}"], "unknown placeholder"),
+        (vec!["1.2.3", "-T", "a..b"], "empty identifier"),
+        (vec!["1.2.3", "-T", "a_b"], "invalid literal"),
+        (vec!["1.2.3", "-T", "{date:%Q}"], "unknown date format"),
+        (
+            vec!["1.2.3", "-T", "{env:SEM_TOOL_TEST_UNSET}"],
+            "unset variable",
+        ),
+        (vec!["1.2.3", "-T", "{x}", "--value", "x="], "empty value"),
+    ] {
+        let assert = stamp_cmd().args(&args).assert();
+        assert.append_context(COMMAND_STAMP, context).failure();
+    }
+}
+
+#[test]
+fn cli_stamp_basic_cases() {
+    for (args, expected) in [
+        (
+            vec![
+                "1.2.3",
+                "-T",
+                "{sha:7}.{date}",
+                "--sha",
+                SHA,
+                "--timestamp",
+                "1709211909",
+            ],
+            "1.2.3+0a1b2c3.20240229",
+        ),
+        (
+            vec![
+                "1.2.3-rc.1+old",
+                "-T",
+                "{date:%Y%m%d%H%M%S}.{env:SEM_TOOL_TEST_PIPELINE}",
+                "--timestamp",
+                "1709211909",
+            ],
+            "1.2.3-rc.1+20240229130509.42",
+        ),
+        (
+            vec![
+                "1.2.3+ci",
+                "-T",
+                "{branch}",
+                "--value",
+                "branch=feature/a_b",
+                "--append",
+            ],
+            "1.2.3+ci.feature-a-b",
+        ),
+        (vec!["1.2.3", "-T", "build.7"], "1.2.3+build.7"),
+    ] {
+        let assert = stamp_cmd()
+            .env("SEM_TOOL_TEST_PIPELINE", "42")
+            .args(&args)
+            .assert();
+        assert
+            .append_context(COMMAND_STAMP, format!("{args:?}"))
+            .stdout(format!("{expected}\n"))
+            .success();
+    }
+}
+
+#[test]
+fn cli_stamp_sources() {
+    let dir = git_dir();
+
+    // Read from the `.git` directory above the working directory.
+    let assert = stamp_cmd()
+        .current_dir(dir.join("nested"))
+        .args(["1.0.0", "-T", "{sha:12}"])
+        .assert();
+    assert
+        .append_context(COMMAND_STAMP, ".git/HEAD")
+        .stdout("1.0.0+0a1b2c3d4e5f\n")
+        .success();
+
+    // CI variables win over `.git`, and `--sha` wins over both.
+    let assert = stamp_cmd()
+        .current_dir(&dir)
+        .env("CI_COMMIT_SHA", "fedcba9876")
+        .args(["1.0.0", "-T", "{sha:4}"])
+        .assert();
+    assert
+        .append_context(COMMAND_STAMP, "CI_COMMIT_SHA")
+        .stdout("1.0.0+fedc\n")
+        .success();
+    let assert = stamp_cmd()
+        .current_dir(&dir)
+        .env("CI_COMMIT_SHA", "fedcba9876")
+        .args(["1.0.0", "-T", "{sha:4}", "--sha", "abcdef"])
+        .assert();
+    assert
+        .append_context(COMMAND_STAMP, "--sha")
+        .stdout("1.0.0+abcd\n")
+        .success();
+
+    // A detached `HEAD` holds the commit itself.
+    std::fs::write(dir.join(".git/HEAD"), "1234567890abcdef\n").expect("write HEAD");
+    let assert = stamp_cmd()
+        .current_dir(&dir)
+        .args(["1.0.0", "-T", "{sha:7}"])
+        .assert();
+    assert
+        .append_context(COMMAND_STAMP, "detached HEAD")
+        .stdout("1.0.0+1234567\n")
+        .success();
+
+    let assert = stamp_cmd()
+        .env("SOURCE_DATE_EPOCH", "0")
+        .args(["1.0.0", "-T", "{date}"])
+        .assert();
+    assert
+        .append_context(COMMAND_STAMP, "SOURCE_DATE_EPOCH")
+        .stdout("1.0.0+19700101\n")
+        .success();
+
+    std::fs::remove_dir_all(&dir).expect("remove temp dir");
+}
+
+#[test]
+fn cli_stamp_reports_sanitized() {
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("json")
+        .arg(COMMAND_STAMP)
+        .args(["1.2.3", "-T", "{branch}", "--value", "branch=fix/#12"])
+        .assert();
+    let output = assert.get_output().stdout.clone();
+    assert.append_context(COMMAND_STAMP, "json").success();
+    let result: serde_json::Value = serde_json::from_slice(&output).expect("json");
+    assert_eq!(result["version"], "1.2.3+fix--12");
+    assert_eq!(
+        result["sanitized"],
+        serde_json::json!([{"placeholder": "{branch}", "value": "fix/#12", "sanitized": "fix--12"}])
+    );
+}
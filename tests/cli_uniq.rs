@@ -0,0 +1,181 @@
+//! SPDX-License-Identifier: Apache-2.0
+//! Copyright 2025 canardleteer
+//!
+//! Licensed under the Apache License, Version 2.0 (the "License");
+//! you may not use this file except in compliance with the License.
+//! You may obtain a copy of the License at
+//!
+//! http://www.apache.org/licenses/LICENSE-2.0
+//!
+//! Unless required by applicable law or agreed to in writing, software
+//! distributed under the License is distributed on an "AS IS" BASIS,
+//! WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//! See the License for the specific language governing permissions and
+//! limitations under the License.
+use proptest::prelude::*;
+use proptest_semver::*;
+use semver::{BuildMetadata, Version};
+use std::collections::HashSet;
+
+mod common;
+use common::subcommands::*;
+
+use crate::common::common_cmd;
+
+#[test]
+fn cli_uniq_invalid_input() {
+    let assert = common_cmd().arg(COMMAND_UNIQ).arg("a.b.c").assert();
+    assert.append_context(COMMAND_UNIQ, "bad semver").failure();
+
+    let assert = common_cmd()
+        .arg(COMMAND_UNIQ)
+        .arg("--by")
+        .arg("patch")
+        .arg("1.0.0")
+        .assert();
+    assert.append_context(COMMAND_UNIQ, "bad --by").failure();
+}
+
+#[test]
+fn cli_uniq_basic_cases() {
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_UNIQ)
+        .arg("1.0.0+bm0")
+        .arg("1.0.0+bm1")
+        .arg("2.0.0")
+        .arg("1.0.0")
+        .assert();
+    assert
+        .append_context(COMMAND_UNIQ, "precedence")
+        .stdout("1.0.0+bm0\n2.0.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_UNIQ)
+        .arg("--by")
+        .arg("exact")
+        .arg("1.0.0+bm0")
+        .arg("1.0.0+bm1")
+        .arg("1.0.0+bm0")
+        .assert();
+    assert
+        .append_context(COMMAND_UNIQ, "exact")
+        .stdout("1.0.0+bm0\n1.0.0+bm1\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_UNIQ)
+        .arg("--by")
+        .arg("major")
+        .arg("--keep")
+        .arg("last")
+        .arg("--count")
+        .arg("1.0.0")
+        .arg("1.4.0")
+        .arg("1.2.0")
+        .assert();
+    assert
+        .append_context(COMMAND_UNIQ, "major, keep last, count")
+        .stdout("      3 1.2.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_UNIQ)
+        .arg("--by")
+        .arg("compat")
+        .arg("--keep")
+        .arg("max")
+        .arg("0.2.1")
+        .arg("0.2.9")
+        .arg("0.0.1")
+        .arg("0.0.2")
+        .arg("0.3.0")
+        .assert();
+    assert
+        .append_context(COMMAND_UNIQ, "compat, keep max")
+        .stdout("0.2.9\n0.0.1\n0.0.2\n0.3.0\n")
+        .success();
+
+    let assert = common_cmd()
+        .arg("-o")
+        .arg("text")
+        .arg(COMMAND_UNIQ)
+        .arg("--by")
+        .arg("minor")
+        .arg("--keep")
+        .arg("min")
+        .write_stdin("1.2.3\n1.2.0-rc.1\n1.2.0\n")
+        .assert();
+    assert
+        .append_context(COMMAND_UNIQ, "stdin, minor, keep min")
+        .stdout("1.2.0-rc.1\n")
+        .success();
+}
+
+fn version_without_build(v: &Version) -> Version {
+    Version {
+        major: v.major,
+        minor: v.minor,
+        patch: v.patch,
+        pre: v.pre.clone(),
+        build: BuildMetadata::EMPTY,
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        fork: true,
+        cases: 256,
+        .. ProptestConfig::default()
+    })]
+    #[test]
+    fn prop_uniq_precedence(versions in arb_vec_versions(16), count: bool) {
+        let mut cmd = common_cmd();
+        cmd.arg("-o").arg("text").arg(COMMAND_UNIQ);
+        if count {
+            cmd.arg("--count");
+        }
+        cmd.args(versions.iter().map(|v| v.to_string()));
+
+        let assert = cmd.assert();
+        let stdout = String::from_utf8_lossy(&assert.get_output().stdout).into_owned();
+        assert.append_context(COMMAND_UNIQ, "prop test").success();
+
+        let mut expected: Vec<Version> = Vec::new();
+        let mut seen = HashSet::new();
+        for v in &versions {
+            if seen.insert(version_without_build(v)) {
+                expected.push(v.clone());
+            }
+        }
+
+        let mut total = 0;
+        let emitted: Vec<Version> = stdout
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                let token = if count {
+                    let (n, v) = l.trim_start().split_once(' ').expect("count prefix");
+                    total += n.parse::<usize>().expect("numeric count");
+                    v
+                } else {
+                    l
+                };
+                Version::parse(token).expect("uniq output parses")
+            })
+            .collect();
+
+        prop_assert_eq!(emitted, expected);
+        if count {
+            prop_assert_eq!(total, versions.len());
+        }
+    }
+}
@@ -15,7 +15,22 @@ pub(crate) mod subcommands {
     pub(crate) const COMMAND_MIN: &str = "min";
     pub(crate) const COMMAND_MAX: &str = "max";
     pub(crate) const COMMAND_LATEST: &str = "latest";
-    pub(crate) const ALL_COMMANDS: [&str; 13] = [
+    pub(crate) const COMMAND_UNIQ: &str = "uniq";
+    pub(crate) const COMMAND_DIFF: &str = "diff";
+    pub(crate) const COMMAND_GAPS: &str = "gaps";
+    pub(crate) const COMMAND_FILES: &str = "files";
+    pub(crate) const COMMAND_LATEST_FILE: &str = "latest-file";
+    pub(crate) const COMMAND_DISTANCE: &str = "distance";
+    pub(crate) const COMMAND_EXPAND: &str = "expand";
+    pub(crate) const COMMAND_EVAL: &str = "eval";
+    pub(crate) const COMMAND_ASSERT: &str = "assert";
+    pub(crate) const COMMAND_NEXT: &str = "next";
+    pub(crate) const COMMAND_PRE_RELEASE: &str = "pre-release";
+    pub(crate) const COMMAND_STAMP: &str = "stamp";
+    pub(crate) const COMMAND_SNAPSHOT: &str = "snapshot";
+    pub(crate) const COMMAND_GUARD: &str = "guard";
+    pub(crate) const COMMAND_LINT: &str = "lint";
+    pub(crate) const ALL_COMMANDS: [&str; 28] = [
         COMMAND_COMPARE,
         COMMAND_EXPLAIN,
         COMMAND_FILTER_TEST,
@@ -29,6 +44,21 @@ pub(crate) mod subcommands {
         COMMAND_MIN,
         COMMAND_MAX,
         COMMAND_LATEST,
+        COMMAND_UNIQ,
+        COMMAND_DIFF,
+        COMMAND_GAPS,
+        COMMAND_FILES,
+        COMMAND_LATEST_FILE,
+        COMMAND_DISTANCE,
+        COMMAND_EXPAND,
+        COMMAND_EVAL,
+        COMMAND_ASSERT,
+        COMMAND_NEXT,
+        COMMAND_PRE_RELEASE,
+        COMMAND_STAMP,
+        COMMAND_SNAPSHOT,
+        COMMAND_GUARD,
+        COMMAND_LINT,
     ];
 }
 